@@ -0,0 +1,294 @@
+//! Минимальный бэктест стратегий на исторических свечах.
+//!
+//! [`BacktestEngine`] передаёт свечи одного инструмента стратегии по порядку и
+//! исполняет её рыночные заявки по цене открытия следующей свечи.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::OhlcvBar;
+use crate::api::{OrderDirection, Quotation};
+
+/// Стратегия, принимающая решения по закрытым свечам.
+pub trait Strategy {
+    /// Обрабатывает закрытую свечу и возвращает заявки для исполнения
+    /// на следующей свече.
+    fn on_bar(&mut self, bar: &OhlcvBar, portfolio: &BacktestPortfolio) -> Vec<BacktestOrder>;
+}
+
+/// Рыночная заявка стратегии.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktestOrder {
+    /// Направление заявки
+    pub direction: OrderDirection,
+    /// Количество бумаг
+    pub quantity: Decimal,
+}
+
+impl BacktestOrder {
+    /// Создаёт заявку на покупку.
+    pub fn buy(quantity: Decimal) -> Self {
+        Self {
+            direction: OrderDirection::Buy,
+            quantity,
+        }
+    }
+
+    /// Создаёт заявку на продажу.
+    pub fn sell(quantity: Decimal) -> Self {
+        Self {
+            direction: OrderDirection::Sell,
+            quantity,
+        }
+    }
+}
+
+/// Состояние счёта в бэктесте.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BacktestPortfolio {
+    /// Свободные денежные средства
+    pub cash: Decimal,
+    /// Количество бумаг по FIGI
+    pub positions: HashMap<String, Decimal>,
+}
+
+impl BacktestPortfolio {
+    /// Возвращает количество бумаг инструмента.
+    pub fn position(&self, figi: &str) -> Decimal {
+        self.positions.get(figi).copied().unwrap_or_default()
+    }
+}
+
+/// Исполненная сделка бэктеста.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BacktestTrade {
+    /// Время свечи, на которой исполнена заявка
+    pub time: DateTime<Utc>,
+    /// FIGI инструмента
+    pub figi: String,
+    /// Направление сделки
+    pub direction: OrderDirection,
+    /// Количество бумаг
+    pub quantity: Decimal,
+    /// Цена исполнения
+    pub price: Decimal,
+    /// Уплаченная комиссия
+    pub commission: Decimal,
+}
+
+impl BacktestTrade {
+    /// Возвращает цену исполнения в виде Quotation.
+    pub fn price_quotation(&self) -> Result<Quotation, String> {
+        Quotation::try_from(self.price)
+    }
+}
+
+/// Результат бэктеста.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BacktestResult {
+    /// Доходность за период в процентах
+    pub total_return: Decimal,
+    /// Максимальная просадка стоимости счёта в процентах
+    pub max_drawdown: Decimal,
+    /// Исполненные сделки
+    pub trades: Vec<BacktestTrade>,
+}
+
+/// Прогон стратегии по свечам одного инструмента.
+///
+/// Заявки, выставленные на свече, исполняются по цене открытия следующей свечи,
+/// заявки последней свечи не исполняются. Покупка без достаточных средств на
+/// стоимость и комиссию и продажа больше имеющегося количества отклоняются.
+/// Стоимость счёта оценивается по цене закрытия каждой свечи.
+pub struct BacktestEngine<S> {
+    figi: String,
+    strategy: S,
+}
+
+impl<S: Strategy> BacktestEngine<S> {
+    /// Создаёт бэктест стратегии по инструменту.
+    pub fn new(figi: &str, strategy: S) -> Self {
+        Self {
+            figi: figi.to_string(),
+            strategy,
+        }
+    }
+
+    /// Возвращает стратегию.
+    pub fn into_strategy(self) -> S {
+        self.strategy
+    }
+
+    /// Прогоняет стратегию по свечам.
+    ///
+    /// # Аргументы
+    /// * `bars` - Свечи инструмента по возрастанию времени
+    /// * `initial_cash` - Начальные денежные средства
+    /// * `commission_rate` - Комиссия как доля от объёма сделки, например `0.0005`
+    pub fn run(
+        &mut self,
+        bars: &[OhlcvBar],
+        initial_cash: Decimal,
+        commission_rate: Decimal,
+    ) -> BacktestResult {
+        let mut portfolio = BacktestPortfolio {
+            cash: initial_cash,
+            positions: HashMap::new(),
+        };
+        let mut pending: Vec<BacktestOrder> = Vec::new();
+        let mut trades = Vec::new();
+        let mut equity = initial_cash;
+        let mut peak = initial_cash;
+        let mut max_drawdown = Decimal::ZERO;
+
+        for bar in bars {
+            for order in pending.drain(..) {
+                if let Some(trade) = self.fill(&mut portfolio, order, bar, commission_rate) {
+                    trades.push(trade);
+                }
+            }
+
+            equity = portfolio.cash + portfolio.position(&self.figi) * bar.close;
+            peak = peak.max(equity);
+            if peak > Decimal::ZERO {
+                max_drawdown = max_drawdown.max((peak - equity) / peak * Decimal::ONE_HUNDRED);
+            }
+
+            pending = self.strategy.on_bar(bar, &portfolio);
+        }
+
+        let total_return = if initial_cash.is_zero() {
+            Decimal::ZERO
+        } else {
+            (equity - initial_cash) / initial_cash * Decimal::ONE_HUNDRED
+        };
+
+        BacktestResult {
+            total_return,
+            max_drawdown,
+            trades,
+        }
+    }
+
+    fn fill(
+        &self,
+        portfolio: &mut BacktestPortfolio,
+        order: BacktestOrder,
+        bar: &OhlcvBar,
+        commission_rate: Decimal,
+    ) -> Option<BacktestTrade> {
+        if order.quantity <= Decimal::ZERO {
+            return None;
+        }
+
+        let value = order.quantity * bar.open;
+        let commission = value * commission_rate;
+        let position = portfolio.position(&self.figi);
+        let (cash, position) = match order.direction {
+            OrderDirection::Buy if portfolio.cash >= value + commission => (
+                portfolio.cash - value - commission,
+                position + order.quantity,
+            ),
+            OrderDirection::Sell if position >= order.quantity => (
+                portfolio.cash + value - commission,
+                position - order.quantity,
+            ),
+            _ => return None,
+        };
+
+        portfolio.cash = cash;
+        portfolio.positions.insert(self.figi.clone(), position);
+
+        Some(BacktestTrade {
+            time: bar.time,
+            figi: self.figi.clone(),
+            direction: order.direction,
+            quantity: order.quantity,
+            price: bar.open,
+            commission,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn bars(prices: &[(Decimal, Decimal)]) -> Vec<OhlcvBar> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(day, &(open, close))| OhlcvBar {
+                time: start + TimeDelta::days(day as i64),
+                open,
+                high: open.max(close),
+                low: open.min(close),
+                close,
+                volume: Decimal::ONE,
+            })
+            .collect()
+    }
+
+    /// Покупает после первой свечи и продаёт всё после третьей.
+    struct BuyAndSell {
+        bar: usize,
+    }
+
+    impl Strategy for BuyAndSell {
+        fn on_bar(&mut self, _: &OhlcvBar, portfolio: &BacktestPortfolio) -> Vec<BacktestOrder> {
+            self.bar += 1;
+            match self.bar {
+                1 => vec![BacktestOrder::buy(dec!(10)), BacktestOrder::buy(dec!(1000))],
+                3 => vec![BacktestOrder::sell(portfolio.position("SBER"))],
+                _ => Vec::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn replays_bars() {
+        let bars = bars(&[
+            (dec!(100), dec!(100)),
+            (dec!(100), dec!(90)),
+            (dec!(95), dec!(110)),
+            (dec!(120), dec!(120)),
+            (dec!(125), dec!(125)),
+        ]);
+        // Средств не хватает на комиссию, а продавать нечего.
+        let result =
+            BacktestEngine::new("SBER", BuyAndSell { bar: 0 }).run(&bars, dec!(1000), dec!(0.001));
+        assert!(result.trades.is_empty());
+        assert_eq!(Decimal::ZERO, result.total_return);
+        assert_eq!(Decimal::ZERO, result.max_drawdown);
+
+        let result =
+            BacktestEngine::new("SBER", BuyAndSell { bar: 0 }).run(&bars, dec!(2000), dec!(0.001));
+        assert_eq!(2, result.trades.len());
+        assert_eq!(
+            BacktestTrade {
+                time: bars[1].time,
+                figi: "SBER".to_string(),
+                direction: OrderDirection::Buy,
+                quantity: dec!(10),
+                price: dec!(100),
+                commission: dec!(1),
+            },
+            result.trades[0]
+        );
+        assert_eq!(OrderDirection::Sell, result.trades[1].direction);
+        assert_eq!(dec!(120), result.trades[1].price);
+        assert_eq!(dec!(1.2), result.trades[1].commission);
+        assert_eq!(Ok(Quotation::from(120)), result.trades[1].price_quotation());
+
+        // 2000 - 1000 - 1 + 1200 - 1.2 = 2197.8
+        assert_eq!(dec!(9.89), result.total_return);
+        // Пик 2000, минимум на второй свече: 999 + 10 * 90 = 1899
+        assert_eq!(dec!(5.05), result.max_drawdown);
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    self, CouponType, GetBondCouponsRequest, GetLastPricesRequest, InstrumentIdType,
+    InstrumentRequest, instruments_service_client::InstrumentsServiceClient,
+};
+use crate::fixed_income::yield_to_maturity;
+use crate::{
+    TInvestError, TInvestInterceptor, TInvestSdk, naive_date_to_timestamp, timestamp_to_naive_date,
+};
+
+/// Купонная выплата по облигации.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coupon {
+    /// Дата выплаты купона
+    pub pay_date: NaiveDate,
+    /// Начало купонного периода
+    pub start_date: NaiveDate,
+    /// Выплата на одну облигацию
+    pub amount: Decimal,
+    /// Валюта выплаты
+    pub currency: String,
+    /// Тип купона
+    pub coupon_type: CouponType,
+}
+
+impl Coupon {
+    /// Возвращает накопленный купонный доход на дату расчётов.
+    ///
+    /// Доход растёт линейно от нуля в начале купонного периода до `amount`
+    /// в дату выплаты. До начала периода возвращается ноль, после выплаты — `amount`.
+    pub fn accrued_interest(&self, settlement_date: NaiveDate) -> Decimal {
+        if settlement_date <= self.start_date {
+            return Decimal::ZERO;
+        }
+        if settlement_date >= self.pay_date {
+            return self.amount;
+        }
+
+        let elapsed = (settlement_date - self.start_date).num_days();
+        let period = (self.pay_date - self.start_date).num_days();
+        self.amount * Decimal::from(elapsed) / Decimal::from(period)
+    }
+}
+
+impl TryFrom<api::Coupon> for Coupon {
+    type Error = String;
+
+    /// Начало периода берётся из `coupon_start_date`, а если его нет —
+    /// вычисляется по дате выплаты и длительности периода `coupon_period`.
+    fn try_from(coupon: api::Coupon) -> Result<Self, Self::Error> {
+        let pay_date = coupon
+            .coupon_date
+            .ok_or_else(|| format!("Coupon {} has no payment date", coupon.coupon_number))
+            .and_then(timestamp_to_naive_date)?;
+        let start_date = match coupon.coupon_start_date {
+            Some(start_date) => timestamp_to_naive_date(start_date)?,
+            None => pay_date - Duration::days(coupon.coupon_period.into()),
+        };
+        let coupon_type = coupon.coupon_type();
+        let payment = coupon.pay_one_bond.unwrap_or_default();
+
+        Ok(Self {
+            pay_date,
+            start_date,
+            currency: payment.currency.clone(),
+            amount: payment.into(),
+            coupon_type,
+        })
+    }
+}
+
+/// Возвращает купоны облигации с датой выплаты в интервале `[from, to]`.
+///
+/// # Аргументы
+/// * `client` - Клиент сервиса инструментов
+/// * `figi` - FIGI облигации
+/// * `from` - Начало интервала
+/// * `to` - Конец интервала (включительно)
+pub async fn get_coupon_schedule(
+    client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    figi: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Coupon>, TInvestError> {
+    let events = client
+        .get_bond_coupons(GetBondCouponsRequest {
+            instrument_id: figi.to_string(),
+            from: Some(naive_date_to_timestamp(from)),
+            to: Some(naive_date_to_timestamp(to + Duration::days(1))),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .events;
+
+    let mut coupons = events
+        .into_iter()
+        .map(Coupon::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(TInvestError::Calculation)?;
+    coupons.retain(|coupon| (from..=to).contains(&coupon.pay_date));
+    coupons.sort_by_key(|coupon| coupon.pay_date);
+
+    Ok(coupons)
+}
+
+/// Возвращает сумму выплат по купонам без учёта валюты.
+pub fn total_cash_flow(coupons: &[Coupon]) -> Decimal {
+    coupons.iter().map(|coupon| coupon.amount).sum()
+}
+
+/// Рассчитывает доходность облигации к погашению на дату расчётов.
+///
+/// Чистая цена берётся из последней цены (в процентах от номинала),
+/// полная цена получается добавлением накопленного купонного дохода текущего купона.
+/// Денежный поток состоит из купонов до даты погашения и номинала.
+/// Для облигаций с плавающим купоном учитываются только объявленные купоны.
+///
+/// # Аргументы
+/// * `sdk` - Экземпляр SDK
+/// * `figi` - FIGI облигации
+/// * `settlement_date` - Дата расчётов
+///
+/// # Ошибки
+/// Возвращает ошибку, если облигация погашена, нет последней цены
+/// или доходность не удалось рассчитать.
+pub async fn get_bond_ytm(
+    sdk: &TInvestSdk,
+    figi: &str,
+    settlement_date: NaiveDate,
+) -> Result<Decimal, TInvestError> {
+    let mut instruments = sdk.instruments();
+
+    let bond = instruments
+        .bond_by(InstrumentRequest {
+            id_type: InstrumentIdType::Figi.into(),
+            id: figi.to_string(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .instrument
+        .ok_or_else(|| TInvestError::NotFound(format!("bond with FIGI {}", figi)))?;
+
+    let maturity_date = bond
+        .maturity_date
+        .map(timestamp_to_naive_date)
+        .transpose()
+        .map_err(TInvestError::Calculation)?
+        .ok_or_else(|| TInvestError::Calculation(format!("Bond {} has no maturity date", figi)))?;
+    if maturity_date <= settlement_date {
+        return Err(TInvestError::InvalidArgument(format!(
+            "Bond {} matured on {}",
+            figi, maturity_date
+        )));
+    }
+
+    let price = sdk
+        .market_data()
+        .get_last_prices(GetLastPricesRequest {
+            instrument_id: vec![figi.to_string()],
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .last_prices
+        .into_iter()
+        .find_map(|last_price| last_price.price)
+        .map(Decimal::from)
+        .ok_or_else(|| TInvestError::NotFound(format!("last price for bond {}", figi)))?;
+
+    let nominal = bond.nominal.map(Decimal::from).unwrap_or_default();
+    let coupons =
+        get_coupon_schedule(&mut instruments, figi, settlement_date, maturity_date).await?;
+
+    bond_ytm(price, nominal, maturity_date, &coupons, settlement_date).ok_or_else(|| {
+        TInvestError::Calculation(format!(
+            "Can't calculate yield to maturity for bond {}",
+            figi
+        ))
+    })
+}
+
+/// Рассчитывает доходность по цене в процентах от номинала и купонам облигации.
+fn bond_ytm(
+    price_percent: Decimal,
+    nominal: Decimal,
+    maturity_date: NaiveDate,
+    coupons: &[Coupon],
+    settlement_date: NaiveDate,
+) -> Option<Decimal> {
+    let clean_price = price_percent * nominal / Decimal::ONE_HUNDRED;
+    let accrued_interest = coupons
+        .iter()
+        .filter(|coupon| coupon.pay_date > settlement_date)
+        .min_by_key(|coupon| coupon.pay_date)
+        .map(|coupon| coupon.accrued_interest(settlement_date))
+        .unwrap_or_default();
+
+    let cash_flows: Vec<(NaiveDate, Decimal)> = coupons
+        .iter()
+        .filter(|coupon| coupon.pay_date <= maturity_date)
+        .map(|coupon| (coupon.pay_date, coupon.amount))
+        .chain(std::iter::once((maturity_date, nominal)))
+        .collect();
+
+    yield_to_maturity(clean_price + accrued_interest, &cash_flows, settlement_date)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::MoneyValue;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn coupon(start: NaiveDate, pay: NaiveDate, amount: Decimal) -> Coupon {
+        Coupon {
+            pay_date: pay,
+            start_date: start,
+            amount,
+            currency: "rub".to_string(),
+            coupon_type: CouponType::Constant,
+        }
+    }
+
+    #[test]
+    fn accrued_interest() {
+        // Период 1 января – 31 марта 2024 года длится 90 дней.
+        let coupon = coupon(date(1, 1), date(3, 31), dec!(36));
+
+        assert_eq!(Decimal::ZERO, coupon.accrued_interest(date(1, 1)));
+        assert_eq!(
+            Decimal::ZERO,
+            coupon.accrued_interest(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap())
+        );
+        assert_eq!(dec!(18), coupon.accrued_interest(date(2, 15)));
+        assert_eq!(dec!(0.4), coupon.accrued_interest(date(1, 2)));
+        assert_eq!(dec!(36), coupon.accrued_interest(date(3, 31)));
+        assert_eq!(dec!(36), coupon.accrued_interest(date(6, 1)));
+    }
+
+    #[test]
+    fn cash_flow_and_conversion() {
+        let coupons = [
+            coupon(date(1, 1), date(3, 31), dec!(36)),
+            coupon(date(3, 31), date(6, 29), dec!(36.5)),
+        ];
+        assert_eq!(dec!(72.5), total_cash_flow(&coupons));
+        assert_eq!(Decimal::ZERO, total_cash_flow(&[]));
+
+        let converted = Coupon::try_from(api::Coupon {
+            coupon_date: Some(naive_date_to_timestamp(date(3, 31))),
+            coupon_period: 90,
+            coupon_type: CouponType::Fix.into(),
+            pay_one_bond: Some(MoneyValue {
+                currency: "rub".to_string(),
+                units: 36,
+                nano: 500_000_000,
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            Coupon {
+                pay_date: date(3, 31),
+                start_date: date(1, 1),
+                amount: dec!(36.5),
+                currency: "rub".to_string(),
+                coupon_type: CouponType::Fix,
+            },
+            converted
+        );
+        assert!(Coupon::try_from(api::Coupon::default()).is_err());
+    }
+
+    #[test]
+    fn ytm_from_percent_price() {
+        // Полугодовой купон 50 при номинале 1000, до погашения один год.
+        let coupons = [
+            coupon(date(1, 1), date(7, 1), dec!(50)),
+            coupon(date(7, 1), date(12, 31), dec!(50)),
+        ];
+
+        let at_par = bond_ytm(dec!(100), dec!(1000), date(12, 31), &coupons, date(1, 1)).unwrap();
+        assert!(at_par > dec!(0.1) && at_par < dec!(0.1030));
+
+        // В середине купонного периода полная цена включает накопленный доход.
+        let mid_period =
+            bond_ytm(dec!(100), dec!(1000), date(12, 31), &coupons, date(4, 1)).unwrap();
+        assert!(mid_period > dec!(0.0) && mid_period < at_par);
+
+        let discount = bond_ytm(dec!(95), dec!(1000), date(12, 31), &coupons, date(1, 1)).unwrap();
+        assert!(discount > at_par);
+    }
+}
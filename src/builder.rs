@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use tonic::transport::{Channel, Endpoint};
+
+use crate::{Environment, TInvestError, TInvestInterceptor, TInvestSdk, TrackingIdStrategy};
+
+/// Построитель SDK с настройками соединения.
+///
+/// Все настройки по умолчанию не заданы, и соединение создаётся так же,
+/// как в [`TInvestSdk::new`].
+///
+/// Балансировщики нагрузки могут молча разрывать соединения, по которым долго
+/// не передаются данные. Больше всего от этого страдают долгоживущие стримы
+/// `MarketDataStream`, `OrdersStream` и `OperationsStream`, подписки которых
+/// могут долго не получать сообщений. Для них рекомендуется задать
+/// `keepalive_interval` около 30 секунд, `keepalive_timeout` около 10 секунд
+/// и `tcp_keepalive` около 60 секунд.
+///
+/// # Пример
+///
+/// ```no_run
+/// # async fn example() -> Result<(), t_invest_sdk::TInvestError> {
+/// use std::time::Duration;
+/// use t_invest_sdk::{Environment, TInvestSdk};
+///
+/// let sdk = TInvestSdk::builder("t.xxxxxx", Environment::Production)
+///     .keepalive_interval(Duration::from_secs(30))
+///     .keepalive_timeout(Duration::from_secs(10))
+///     .tcp_keepalive(Duration::from_secs(60))
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TInvestSdkBuilder {
+    token: String,
+    environment: Environment,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    keepalive_interval: Option<Duration>,
+    keepalive_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    tracking_id: TrackingIdStrategy,
+}
+
+impl TInvestSdkBuilder {
+    /// Создаёт построитель для указанного токена и среды.
+    pub fn new(token: &str, environment: Environment) -> Self {
+        Self {
+            token: token.to_string(),
+            environment,
+            connect_timeout: None,
+            request_timeout: None,
+            keepalive_interval: None,
+            keepalive_timeout: None,
+            tcp_keepalive: None,
+            tracking_id: TrackingIdStrategy::default(),
+        }
+    }
+
+    /// Задаёт таймаут установки соединения.
+    pub fn connect_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Задаёт таймаут выполнения запроса.
+    pub fn request_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Задаёт интервал отправки HTTP/2 PING для проверки соединения.
+    pub fn keepalive_interval(&mut self, interval: Duration) -> &mut Self {
+        self.keepalive_interval = Some(interval);
+        self
+    }
+
+    /// Задаёт время ожидания ответа на HTTP/2 PING, после которого соединение
+    /// считается разорванным. Используется вместе с `keepalive_interval`.
+    pub fn keepalive_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.keepalive_timeout = Some(timeout);
+        self
+    }
+
+    /// Включает TCP keepalive с указанным временем простоя до первой проверки.
+    pub fn tcp_keepalive(&mut self, idle: Duration) -> &mut Self {
+        self.tcp_keepalive = Some(idle);
+        self
+    }
+
+    /// Задаёт способ получения заголовка `x-tracking-id` для запросов SDK.
+    pub fn tracking_id(&mut self, strategy: TrackingIdStrategy) -> &mut Self {
+        self.tracking_id = strategy;
+        self
+    }
+
+    /// Устанавливает соединение и создаёт SDK.
+    pub async fn connect(&self) -> Result<TInvestSdk, TInvestError> {
+        TInvestSdk::connect(self.interceptor(), self.endpoint()).await
+    }
+
+    fn interceptor(&self) -> TInvestInterceptor {
+        TInvestInterceptor::new(&self.token).with_tracking_id(self.tracking_id.clone())
+    }
+
+    fn endpoint(&self) -> Endpoint {
+        let mut endpoint =
+            Channel::from_static(self.environment.api_url()).tcp_keepalive(self.tcp_keepalive);
+        if let Some(timeout) = self.connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            endpoint = endpoint.timeout(timeout);
+        }
+        if let Some(interval) = self.keepalive_interval {
+            endpoint = endpoint.http2_keep_alive_interval(interval);
+        }
+        if let Some(timeout) = self.keepalive_timeout {
+            endpoint = endpoint.keep_alive_timeout(timeout);
+        }
+
+        endpoint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_options() {
+        let mut builder = TInvestSdkBuilder::new("t.test", Environment::Sandbox);
+        assert_eq!(None, builder.keepalive_interval);
+        assert_eq!(None, builder.tcp_keepalive);
+
+        builder
+            .keepalive_interval(Duration::from_secs(30))
+            .keepalive_timeout(Duration::from_secs(10))
+            .tcp_keepalive(Duration::from_secs(60));
+
+        assert_eq!(Some(Duration::from_secs(30)), builder.keepalive_interval);
+        assert_eq!(Some(Duration::from_secs(10)), builder.keepalive_timeout);
+        assert_eq!(Some(Duration::from_secs(60)), builder.tcp_keepalive);
+        assert_eq!(None, builder.connect_timeout);
+        assert_eq!(
+            "sandbox-invest-public-api.tbank.ru",
+            builder.endpoint().uri().host().unwrap()
+        );
+    }
+}
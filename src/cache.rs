@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    GetLastPricesRequest, GetOrderBookRequest, GetOrderBookResponse, LastPrice,
+    market_data_service_client::MarketDataServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor};
+
+type Entries<K, V> = Arc<Mutex<HashMap<K, (DateTime<Utc>, V)>>>;
+
+/// Кэш последних цен и стаканов с настраиваемым временем жизни.
+///
+/// Повторные запросы в пределах `ttl` возвращают сохранённое значение
+/// без обращения к API, что снижает расход лимитов при частом опросе.
+/// Клоны кэша разделяют общее хранилище, поэтому его можно использовать
+/// из нескольких задач.
+#[derive(Clone)]
+pub struct MarketDataCache {
+    client: MarketDataServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    ttl: Duration,
+    last_prices: Entries<String, LastPrice>,
+    order_books: Entries<(String, i32), GetOrderBookResponse>,
+}
+
+impl MarketDataCache {
+    /// Создаёт пустой кэш.
+    ///
+    /// # Аргументы
+    /// * `client` - Клиент сервиса Market Data
+    /// * `ttl` - Время, в течение которого значение считается актуальным
+    pub fn new(
+        client: MarketDataServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            client,
+            ttl,
+            last_prices: Arc::default(),
+            order_books: Arc::default(),
+        }
+    }
+
+    /// Возвращает последнюю цену инструмента (FIGI или UID).
+    ///
+    /// Возвращает [`TInvestError::NotFound`], если API не вернул цену для инструмента.
+    pub async fn get_last_price(&self, figi: &str) -> Result<LastPrice, TInvestError> {
+        get_or_fetch(&self.last_prices, figi.to_string(), self.ttl, || async {
+            let response = self
+                .client
+                .clone()
+                .get_last_prices(GetLastPricesRequest {
+                    instrument_id: vec![figi.to_string()],
+                    ..Default::default()
+                })
+                .await?
+                .into_inner();
+
+            response
+                .last_prices
+                .into_iter()
+                .next()
+                .ok_or_else(|| TInvestError::NotFound(format!("last price for {}", figi)))
+        })
+        .await
+    }
+
+    /// Возвращает стакан инструмента (FIGI или UID) указанной глубины.
+    pub async fn get_order_book(
+        &self,
+        figi: &str,
+        depth: i32,
+    ) -> Result<GetOrderBookResponse, TInvestError> {
+        get_or_fetch(
+            &self.order_books,
+            (figi.to_string(), depth),
+            self.ttl,
+            || async {
+                Ok(self
+                    .client
+                    .clone()
+                    .get_order_book(GetOrderBookRequest {
+                        depth,
+                        instrument_id: Some(figi.to_string()),
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_inner())
+            },
+        )
+        .await
+    }
+
+    /// Удаляет из кэша все значения инструмента.
+    pub fn invalidate(&self, figi: &str) {
+        lock(&self.last_prices).remove(figi);
+        lock(&self.order_books).retain(|(key, _), _| key != figi);
+    }
+
+    /// Очищает кэш.
+    pub fn invalidate_all(&self) {
+        lock(&self.last_prices).clear();
+        lock(&self.order_books).clear();
+    }
+}
+
+fn lock<K, V>(
+    entries: &Entries<K, V>,
+) -> std::sync::MutexGuard<'_, HashMap<K, (DateTime<Utc>, V)>> {
+    entries
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Возвращает актуальное значение из кэша либо загружает и сохраняет новое.
+async fn get_or_fetch<K, V, F, Fut>(
+    entries: &Entries<K, V>,
+    key: K,
+    ttl: Duration,
+    fetch: F,
+) -> Result<V, TInvestError>
+where
+    K: Hash + Eq,
+    V: Clone,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<V, TInvestError>>,
+{
+    let ttl = TimeDelta::from_std(ttl).unwrap_or(TimeDelta::MAX);
+
+    if let Some((cached_at, value)) = lock(entries).get(&key)
+        && Utc::now() - *cached_at < ttl
+    {
+        return Ok(value.clone());
+    }
+
+    let value = fetch().await?;
+    lock(entries).insert(key, (Utc::now(), value.clone()));
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    async fn fetch_counted(
+        entries: &Entries<String, i32>,
+        ttl: Duration,
+        calls: &AtomicUsize,
+    ) -> i32 {
+        get_or_fetch(entries, "BBG004730N88".to_string(), ttl, || async {
+            Ok(calls.fetch_add(1, Ordering::SeqCst) as i32)
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fetches_once_within_ttl() {
+        let entries = Entries::default();
+        let calls = AtomicUsize::new(0);
+
+        assert_eq!(
+            0,
+            fetch_counted(&entries, Duration::from_secs(60), &calls).await
+        );
+        assert_eq!(
+            0,
+            fetch_counted(&entries, Duration::from_secs(60), &calls).await
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn refetches_expired() {
+        let entries = Entries::default();
+        let calls = AtomicUsize::new(0);
+
+        assert_eq!(0, fetch_counted(&entries, Duration::ZERO, &calls).await);
+        assert_eq!(1, fetch_counted(&entries, Duration::ZERO, &calls).await);
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn invalidate() {
+        let channel = Channel::from_static("https://localhost").connect_lazy();
+        let client =
+            MarketDataServiceClient::with_interceptor(channel, TInvestInterceptor::new("t.test"));
+        let cache = MarketDataCache::new(client, Duration::from_secs(60));
+
+        for figi in ["BBG004730N88", "BBG004731032"] {
+            lock(&cache.last_prices).insert(figi.to_string(), (Utc::now(), LastPrice::default()));
+            lock(&cache.order_books).insert(
+                (figi.to_string(), 10),
+                (Utc::now(), GetOrderBookResponse::default()),
+            );
+        }
+
+        cache.invalidate("BBG004730N88");
+        assert!(!lock(&cache.last_prices).contains_key("BBG004730N88"));
+        assert_eq!(1, lock(&cache.last_prices).len());
+        assert_eq!(1, lock(&cache.order_books).len());
+
+        cache.invalidate_all();
+        assert!(lock(&cache.last_prices).is_empty());
+        assert!(lock(&cache.order_books).is_empty());
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use prost_types::Timestamp;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    InstrumentIdType, InstrumentRequest, TradingDay, TradingSchedulesRequest,
+    instruments_service_client::InstrumentsServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor, naive_date_to_timestamp, timestamp_to_datetime_utc};
+
+/// На сколько дней вперёд запрашивается расписание торгов.
+const SCHEDULE_DAYS: i64 = 14;
+
+/// Расписания торгов, загруженные за один день.
+#[derive(Debug, Default)]
+struct Schedules {
+    date: Option<NaiveDate>,
+    exchanges: HashMap<String, String>,
+    days: HashMap<String, Vec<TradingDay>>,
+}
+
+/// Календарь торгов по расписаниям бирж.
+///
+/// Для инструмента определяется торговая площадка, расписание которой
+/// запрашивается через `TradingSchedules` на две недели вперёд. Площадки
+/// инструментов и расписания кэшируются до полуночи по UTC.
+/// Клоны календаря разделяют общий кэш.
+#[derive(Clone)]
+pub struct TradingCalendar {
+    client: InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    schedules: Arc<Mutex<Schedules>>,
+}
+
+impl TradingCalendar {
+    /// Создаёт календарь с пустым кэшем.
+    pub fn new(
+        client: InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    ) -> Self {
+        Self {
+            client,
+            schedules: Arc::default(),
+        }
+    }
+
+    /// Проверяет, идут ли сейчас торги инструментом в основную или вечернюю сессию.
+    pub async fn is_tradeable(&self, figi: &str) -> Result<bool, TInvestError> {
+        let now = Utc::now();
+        let days = self.trading_days(figi).await?;
+
+        Ok(today(&days, now).is_some_and(|day| {
+            in_session(now, &day.start_time, &day.end_time)
+                || in_session(now, &day.evening_start_time, &day.evening_end_time)
+        }))
+    }
+
+    /// Проверяет, идёт ли сейчас премаркет.
+    pub async fn is_in_pre_market(&self, figi: &str) -> Result<bool, TInvestError> {
+        let now = Utc::now();
+        let days = self.trading_days(figi).await?;
+
+        Ok(today(&days, now)
+            .is_some_and(|day| in_session(now, &day.premarket_start_time, &day.premarket_end_time)))
+    }
+
+    /// Проверяет, идёт ли сейчас вечерняя сессия.
+    pub async fn is_in_post_market(&self, figi: &str) -> Result<bool, TInvestError> {
+        let now = Utc::now();
+        let days = self.trading_days(figi).await?;
+
+        Ok(today(&days, now)
+            .is_some_and(|day| in_session(now, &day.evening_start_time, &day.evening_end_time)))
+    }
+
+    /// Возвращает ближайшее время начала торгов после текущего момента.
+    ///
+    /// Возвращает [`TInvestError::NotFound`], если в ближайшие две недели торгов нет.
+    pub async fn next_open(&self, figi: &str) -> Result<DateTime<Utc>, TInvestError> {
+        let now = Utc::now();
+        let days = self.trading_days(figi).await?;
+
+        next_open(&days, now)
+            .ok_or_else(|| TInvestError::NotFound(format!("trading sessions for {}", figi)))
+    }
+
+    /// Возвращает расписание площадки инструмента, загружая его при необходимости.
+    async fn trading_days(&self, figi: &str) -> Result<Vec<TradingDay>, TInvestError> {
+        let today = Utc::now().date_naive();
+
+        let exchange = self.lock(today).exchanges.get(figi).cloned();
+        let exchange = match exchange {
+            Some(exchange) => exchange,
+            None => {
+                let exchange = self.fetch_exchange(figi).await?;
+                self.lock(today)
+                    .exchanges
+                    .insert(figi.to_string(), exchange.clone());
+                exchange
+            }
+        };
+
+        if let Some(days) = self.lock(today).days.get(&exchange) {
+            return Ok(days.clone());
+        }
+
+        let days = self.fetch_schedule(&exchange, today).await?;
+        self.lock(today).days.insert(exchange, days.clone());
+
+        Ok(days)
+    }
+
+    async fn fetch_exchange(&self, figi: &str) -> Result<String, TInvestError> {
+        let response = self
+            .client
+            .clone()
+            .get_instrument_by(InstrumentRequest {
+                id_type: InstrumentIdType::Figi.into(),
+                id: figi.to_string(),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        response
+            .instrument
+            .map(|instrument| instrument.exchange)
+            .ok_or_else(|| TInvestError::NotFound(format!("instrument with FIGI {}", figi)))
+    }
+
+    async fn fetch_schedule(
+        &self,
+        exchange: &str,
+        today: NaiveDate,
+    ) -> Result<Vec<TradingDay>, TInvestError> {
+        let from = naive_date_to_timestamp(today);
+        let to = naive_date_to_timestamp(today + TimeDelta::days(SCHEDULE_DAYS));
+
+        let response = self
+            .client
+            .clone()
+            .trading_schedules(TradingSchedulesRequest {
+                exchange: Some(exchange.to_string()),
+                from: Some(from),
+                to: Some(to),
+            })
+            .await?
+            .into_inner();
+
+        Ok(response
+            .exchanges
+            .into_iter()
+            .flat_map(|schedule| schedule.days)
+            .collect())
+    }
+
+    /// Блокирует кэш, очищая его, если он был загружен в другой день.
+    fn lock(&self, today: NaiveDate) -> MutexGuard<'_, Schedules> {
+        let mut schedules = self
+            .schedules
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if schedules.date != Some(today) {
+            *schedules = Schedules {
+                date: Some(today),
+                ..Default::default()
+            };
+        }
+
+        schedules
+    }
+}
+
+fn datetime(timestamp: &Option<Timestamp>) -> Option<DateTime<Utc>> {
+    timestamp.and_then(|timestamp| timestamp_to_datetime_utc(timestamp).ok())
+}
+
+/// Возвращает торговый день, в который попадает `now`.
+fn today(days: &[TradingDay], now: DateTime<Utc>) -> Option<&TradingDay> {
+    days.iter().find(|day| {
+        day.is_trading_day
+            && datetime(&day.date).is_some_and(|date| date.date_naive() == now.date_naive())
+    })
+}
+
+fn in_session(now: DateTime<Utc>, start: &Option<Timestamp>, end: &Option<Timestamp>) -> bool {
+    match (datetime(start), datetime(end)) {
+        (Some(start), Some(end)) => start <= now && now < end,
+        _ => false,
+    }
+}
+
+/// Возвращает ближайшее начало основной сессии после `now`.
+fn next_open(days: &[TradingDay], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    days.iter()
+        .filter(|day| day.is_trading_day)
+        .filter_map(|day| datetime(&day.start_time))
+        .filter(|start| *start > now)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::datetime_utc_to_timestamp;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap()
+    }
+
+    fn trading_day(day: u32, is_trading_day: bool) -> TradingDay {
+        let timestamp = |hour| Some(datetime_utc_to_timestamp(at(day, hour)));
+        TradingDay {
+            date: timestamp(0),
+            is_trading_day,
+            premarket_start_time: timestamp(4),
+            premarket_end_time: timestamp(7),
+            start_time: timestamp(7),
+            end_time: timestamp(15),
+            evening_start_time: timestamp(16),
+            evening_end_time: timestamp(20),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sessions() {
+        let days = [trading_day(12, true)];
+        let day = today(&days, at(12, 10)).unwrap();
+
+        assert!(in_session(at(12, 10), &day.start_time, &day.end_time));
+        assert!(!in_session(at(12, 15), &day.start_time, &day.end_time));
+        assert!(in_session(
+            at(12, 5),
+            &day.premarket_start_time,
+            &day.premarket_end_time
+        ));
+        assert!(in_session(
+            at(12, 17),
+            &day.evening_start_time,
+            &day.evening_end_time
+        ));
+        assert!(today(&days, at(13, 10)).is_none());
+    }
+
+    #[test]
+    fn next_open_skips_non_trading_days() {
+        let days = [
+            trading_day(8, true),
+            trading_day(9, false),
+            trading_day(10, false),
+            trading_day(11, true),
+        ];
+
+        assert_eq!(Some(at(8, 7)), next_open(&days, at(8, 6)));
+        assert_eq!(Some(at(11, 7)), next_open(&days, at(8, 10)));
+        assert_eq!(None, next_open(&days, at(11, 10)));
+    }
+
+    /// Проверка на песочнице: `T_INVEST_SANDBOX_TOKEN=<токен> cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore = "requires T_INVEST_SANDBOX_TOKEN and network access"]
+    async fn sandbox_schedule() {
+        const SBER_FIGI: &str = "BBG004730N88";
+
+        let token = std::env::var("T_INVEST_SANDBOX_TOKEN").expect("T_INVEST_SANDBOX_TOKEN");
+        let sdk = crate::TInvestSdk::new_sandbox(&token).await.unwrap();
+        let calendar = TradingCalendar::new(sdk.instruments());
+
+        assert!(calendar.next_open(SBER_FIGI).await.unwrap() > Utc::now());
+        let tradeable = calendar.is_tradeable(SBER_FIGI).await.unwrap();
+        let pre_market = calendar.is_in_pre_market(SBER_FIGI).await.unwrap();
+        assert!(!(tradeable && pre_market));
+
+        // Второй календарь разделяет кэш первого.
+        let cached = calendar.clone();
+        assert_eq!(tradeable, cached.is_tradeable(SBER_FIGI).await.unwrap());
+
+        assert!(calendar.is_tradeable("UNKNOWN_FIGI").await.is_err());
+    }
+}
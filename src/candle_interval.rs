@@ -0,0 +1,103 @@
+//! Сериализация [`CandleInterval`] строками вида `"1min"`, `"1hour"`, `"1day"`.
+
+use serde::de::{self, Deserialize, Deserializer, Unexpected};
+use serde::ser::{Serialize, Serializer};
+
+use crate::api::CandleInterval;
+
+/// Возвращает строковое обозначение интервала.
+fn name(interval: CandleInterval) -> &'static str {
+    match interval {
+        CandleInterval::Unspecified => "unspecified",
+        CandleInterval::CandleInterval5Sec => "5sec",
+        CandleInterval::CandleInterval10Sec => "10sec",
+        CandleInterval::CandleInterval30Sec => "30sec",
+        CandleInterval::CandleInterval1Min => "1min",
+        CandleInterval::CandleInterval2Min => "2min",
+        CandleInterval::CandleInterval3Min => "3min",
+        CandleInterval::CandleInterval5Min => "5min",
+        CandleInterval::CandleInterval10Min => "10min",
+        CandleInterval::CandleInterval15Min => "15min",
+        CandleInterval::CandleInterval30Min => "30min",
+        CandleInterval::Hour => "1hour",
+        CandleInterval::CandleInterval2Hour => "2hour",
+        CandleInterval::CandleInterval4Hour => "4hour",
+        CandleInterval::Day => "1day",
+        CandleInterval::Week => "1week",
+        CandleInterval::Month => "1month",
+    }
+}
+
+/// Возвращает интервал по строковому обозначению.
+fn from_name(name: &str) -> Option<CandleInterval> {
+    Some(match name {
+        "unspecified" => CandleInterval::Unspecified,
+        "5sec" => CandleInterval::CandleInterval5Sec,
+        "10sec" => CandleInterval::CandleInterval10Sec,
+        "30sec" => CandleInterval::CandleInterval30Sec,
+        "1min" => CandleInterval::CandleInterval1Min,
+        "2min" => CandleInterval::CandleInterval2Min,
+        "3min" => CandleInterval::CandleInterval3Min,
+        "5min" => CandleInterval::CandleInterval5Min,
+        "10min" => CandleInterval::CandleInterval10Min,
+        "15min" => CandleInterval::CandleInterval15Min,
+        "30min" => CandleInterval::CandleInterval30Min,
+        "1hour" => CandleInterval::Hour,
+        "2hour" => CandleInterval::CandleInterval2Hour,
+        "4hour" => CandleInterval::CandleInterval4Hour,
+        "1day" => CandleInterval::Day,
+        "1week" => CandleInterval::Week,
+        "1month" => CandleInterval::Month,
+        _ => return None,
+    })
+}
+
+impl Serialize for CandleInterval {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(name(*self))
+    }
+}
+
+impl<'de> Deserialize<'de> for CandleInterval {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        from_name(&value).ok_or_else(|| {
+            de::Error::invalid_value(Unexpected::Str(&value), &"candle interval like \"1min\"")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_representation() {
+        assert_eq!(
+            "\"5min\"",
+            serde_json::to_string(&CandleInterval::CandleInterval5Min).unwrap()
+        );
+        assert_eq!(
+            "[\"1min\",\"1hour\",\"1day\"]",
+            serde_json::to_string(&[
+                CandleInterval::CandleInterval1Min,
+                CandleInterval::Hour,
+                CandleInterval::Day
+            ])
+            .unwrap()
+        );
+
+        // Все значения перечисления переживают сериализацию.
+        for interval in (0..).map_while(|value| CandleInterval::try_from(value).ok()) {
+            let json = serde_json::to_string(&interval).unwrap();
+            assert_eq!(format!("\"{}\"", name(interval)), json);
+            assert_eq!(
+                interval,
+                serde_json::from_str::<CandleInterval>(&json).unwrap()
+            );
+        }
+
+        assert!(serde_json::from_str::<CandleInterval>("\"7min\"").is_err());
+        assert!(serde_json::from_str::<CandleInterval>("2").is_err());
+    }
+}
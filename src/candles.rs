@@ -0,0 +1,983 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Months, TimeDelta, Utc};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    Candle, CandleInterval, GetCandlesRequest, HistoricCandle, Quotation,
+    market_data_service_client::MarketDataServiceClient,
+};
+use crate::{
+    TInvestError, TInvestInterceptor, datetime_utc_to_timestamp, timestamp_to_datetime_utc,
+};
+
+/// Количество предыдущих свечей для расчёта среднего объёма в [`filter_volume_outliers`].
+const VOLUME_OUTLIER_WINDOW: usize = 20;
+
+/// Максимальный период запроса свечей с интервалом 5 секунд.
+const MAX_RANGE_5_SEC: TimeDelta = TimeDelta::minutes(200);
+/// Максимальный период запроса свечей с интервалом 10 секунд.
+const MAX_RANGE_10_SEC: TimeDelta = TimeDelta::minutes(200);
+/// Максимальный период запроса свечей с интервалом 30 секунд.
+const MAX_RANGE_30_SEC: TimeDelta = TimeDelta::hours(20);
+/// Максимальный период запроса свечей с интервалами 1, 2 и 3 минуты.
+const MAX_RANGE_1_MIN: TimeDelta = TimeDelta::days(1);
+/// Максимальный период запроса свечей с интервалами 5 и 10 минут.
+const MAX_RANGE_5_MIN: TimeDelta = TimeDelta::weeks(1);
+/// Максимальный период запроса свечей с интервалами 15 и 30 минут.
+const MAX_RANGE_15_MIN: TimeDelta = TimeDelta::weeks(3);
+/// Максимальный период запроса свечей с интервалами 1, 2 и 4 часа.
+const MAX_RANGE_HOUR: TimeDelta = TimeDelta::days(90);
+/// Максимальный период запроса дневных свечей.
+const MAX_RANGE_DAY: TimeDelta = TimeDelta::days(6 * 365);
+/// Максимальный период запроса недельных свечей.
+const MAX_RANGE_WEEK: TimeDelta = TimeDelta::days(5 * 365);
+/// Максимальный период запроса месячных свечей.
+const MAX_RANGE_MONTH: TimeDelta = TimeDelta::days(10 * 365);
+
+/// Возвращает максимальный период одного запроса `GetCandles` для интервала.
+///
+/// Значения соответствуют ограничениям, описанным в контракте `CandleInterval`.
+/// Для `CandleInterval::Unspecified` возвращает `None`.
+pub fn max_candles_request_range(interval: CandleInterval) -> Option<TimeDelta> {
+    match interval {
+        CandleInterval::Unspecified => None,
+        CandleInterval::CandleInterval5Sec => Some(MAX_RANGE_5_SEC),
+        CandleInterval::CandleInterval10Sec => Some(MAX_RANGE_10_SEC),
+        CandleInterval::CandleInterval30Sec => Some(MAX_RANGE_30_SEC),
+        CandleInterval::CandleInterval1Min
+        | CandleInterval::CandleInterval2Min
+        | CandleInterval::CandleInterval3Min => Some(MAX_RANGE_1_MIN),
+        CandleInterval::CandleInterval5Min | CandleInterval::CandleInterval10Min => {
+            Some(MAX_RANGE_5_MIN)
+        }
+        CandleInterval::CandleInterval15Min | CandleInterval::CandleInterval30Min => {
+            Some(MAX_RANGE_15_MIN)
+        }
+        CandleInterval::Hour
+        | CandleInterval::CandleInterval2Hour
+        | CandleInterval::CandleInterval4Hour => Some(MAX_RANGE_HOUR),
+        CandleInterval::Day => Some(MAX_RANGE_DAY),
+        CandleInterval::Week => Some(MAX_RANGE_WEEK),
+        CandleInterval::Month => Some(MAX_RANGE_MONTH),
+    }
+}
+
+/// Нарушения целостности данных свечи.
+///
+/// - `HighLessThanLow`: Максимальная цена меньше минимальной
+/// - `CloseOutOfRange`: Цена закрытия вне диапазона `[low, high]`
+/// - `VolumeNegative`: Отрицательный объём
+/// - `TimeNotMonotonic`: Время свечи не больше времени предыдущей свечи
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CandleError {
+    #[error("Candle high is less than low")]
+    HighLessThanLow,
+    #[error("Candle close is out of [low, high] range")]
+    CloseOutOfRange,
+    #[error("Candle volume is negative")]
+    VolumeNegative,
+    #[error("Candle time {1} is not after previous candle time {0}")]
+    TimeNotMonotonic(prost_types::Timestamp, prost_types::Timestamp),
+}
+
+/// Проверяет корректность цен и объёма свечи.
+///
+/// Проверки, для которых в свече нет нужных цен, пропускаются.
+pub fn validate_candle(candle: &HistoricCandle) -> Result<(), CandleError> {
+    let high = candle.high.map(Decimal::from);
+    let low = candle.low.map(Decimal::from);
+    let close = candle.close.map(Decimal::from);
+
+    if let (Some(high), Some(low)) = (high, low) {
+        if high < low {
+            return Err(CandleError::HighLessThanLow);
+        }
+
+        if let Some(close) = close
+            && (close < low || close > high)
+        {
+            return Err(CandleError::CloseOutOfRange);
+        }
+    }
+
+    if candle.volume < 0 {
+        return Err(CandleError::VolumeNegative);
+    }
+
+    Ok(())
+}
+
+/// Проверяет последовательность свечей.
+///
+/// Помимо проверок [`validate_candle`] для каждой свечи проверяет,
+/// что время свечей строго возрастает.
+///
+/// # Возвращает
+/// Все найденные нарушения с индексами свечей
+pub fn validate_candle_sequence(candles: &[HistoricCandle]) -> Vec<(usize, CandleError)> {
+    let mut errors = Vec::new();
+    let mut prev_time: Option<prost_types::Timestamp> = None;
+
+    for (index, candle) in candles.iter().enumerate() {
+        if let Err(error) = validate_candle(candle) {
+            errors.push((index, error));
+        }
+
+        if let Some(time) = candle.time {
+            if let Some(prev) = prev_time
+                && (time.seconds, time.nanos) <= (prev.seconds, prev.nanos)
+            {
+                errors.push((index, CandleError::TimeNotMonotonic(prev, time)));
+            }
+            prev_time = Some(time);
+        }
+    }
+
+    errors
+}
+
+/// Отчёт о качестве ряда свечей, см. [`candle_quality_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CandleQualityReport {
+    /// Время, встречающееся у нескольких свечей, в порядке первого повтора
+    pub duplicate_timestamps: Vec<DateTime<Utc>>,
+    /// Количество свечей со временем раньше предыдущей свечи
+    pub out_of_order_timestamps: usize,
+    /// Количество свечей с `high < low` или ценой закрытия вне `[low, high]`
+    pub ohlc_violations: usize,
+    /// Количество свечей с нулевым объёмом
+    pub zero_volume_candles: usize,
+    /// Проблемы не найдены
+    pub is_clean: bool,
+}
+
+/// Проверяет ряд загруженных свечей на типичные ошибки данных.
+///
+/// Свечи без времени или с некорректным временем не участвуют в проверках
+/// времени. Ряд считается чистым, если не найдено ни одной проблемы, включая
+/// свечи с нулевым объёмом.
+pub fn candle_quality_report(candles: &[HistoricCandle]) -> CandleQualityReport {
+    let mut report = CandleQualityReport::default();
+    let mut seen = HashSet::new();
+    let mut prev_time: Option<DateTime<Utc>> = None;
+
+    for candle in candles {
+        if matches!(
+            validate_candle(candle),
+            Err(CandleError::HighLessThanLow | CandleError::CloseOutOfRange)
+        ) {
+            report.ohlc_violations += 1;
+        }
+        if candle.volume == 0 {
+            report.zero_volume_candles += 1;
+        }
+
+        let Some(time) = candle
+            .time
+            .and_then(|time| timestamp_to_datetime_utc(time).ok())
+        else {
+            continue;
+        };
+        if !seen.insert(time) && !report.duplicate_timestamps.contains(&time) {
+            report.duplicate_timestamps.push(time);
+        }
+        if prev_time.is_some_and(|prev| time < prev) {
+            report.out_of_order_timestamps += 1;
+        }
+        prev_time = Some(time);
+    }
+
+    report.is_clean = report.duplicate_timestamps.is_empty()
+        && report.out_of_order_timestamps == 0
+        && report.ohlc_violations == 0
+        && report.zero_volume_candles == 0;
+
+    report
+}
+
+/// Свеча с ценами и объёмом в виде `Decimal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OhlcvBar {
+    /// Время начала интервала свечи по UTC
+    pub time: DateTime<Utc>,
+    /// Цена открытия
+    pub open: Decimal,
+    /// Максимальная цена
+    pub high: Decimal,
+    /// Минимальная цена
+    pub low: Decimal,
+    /// Цена закрытия
+    pub close: Decimal,
+    /// Объём в лотах
+    pub volume: Decimal,
+}
+
+impl OhlcvBar {
+    /// Возвращает диапазон цен свечи `high - low`.
+    pub fn range(&self) -> Decimal {
+        self.high - self.low
+    }
+
+    /// Возвращает размер тела свечи `|close - open|`.
+    pub fn body(&self) -> Decimal {
+        (self.close - self.open).abs()
+    }
+}
+
+/// Ошибка преобразования свечи API в [`OhlcvBar`]: поле отсутствует или
+/// содержит некорректное значение.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Candle {field} is missing or invalid")]
+pub struct CandleConversionError {
+    /// Название поля свечи
+    pub field: &'static str,
+}
+
+impl TryFrom<&HistoricCandle> for OhlcvBar {
+    type Error = CandleConversionError;
+
+    /// Преобразует свечу API.
+    ///
+    /// Поля проверяются в порядке `time`, `open`, `high`, `low`, `close`, ошибка
+    /// содержит первое отсутствующее поле.
+    fn try_from(candle: &HistoricCandle) -> Result<Self, Self::Error> {
+        let price = |price: Option<Quotation>, field| {
+            price
+                .map(Decimal::from)
+                .ok_or(CandleConversionError { field })
+        };
+        let time = candle
+            .time
+            .and_then(|time| timestamp_to_datetime_utc(time).ok())
+            .ok_or(CandleConversionError { field: "time" })?;
+
+        Ok(Self {
+            time,
+            open: price(candle.open, "open")?,
+            high: price(candle.high, "high")?,
+            low: price(candle.low, "low")?,
+            close: price(candle.close, "close")?,
+            volume: Decimal::from(candle.volume),
+        })
+    }
+}
+
+impl TryFrom<HistoricCandle> for OhlcvBar {
+    type Error = CandleConversionError;
+
+    /// Преобразует свечу API. См. реализацию для `&HistoricCandle`.
+    fn try_from(candle: HistoricCandle) -> Result<Self, Self::Error> {
+        OhlcvBar::try_from(&candle)
+    }
+}
+
+/// Преобразует свечи API в [`OhlcvBar`].
+///
+/// # Ошибки
+/// Возвращает [`TInvestError::InvalidArgument`] для первой свечи, которую не удалось преобразовать.
+pub fn bars_from_candles(candles: Vec<HistoricCandle>) -> Result<Vec<OhlcvBar>, TInvestError> {
+    candles
+        .into_iter()
+        .map(|candle| {
+            OhlcvBar::try_from(candle).map_err(|err| TInvestError::InvalidArgument(err.to_string()))
+        })
+        .collect()
+}
+
+/// Преобразует свечу в свечу Heikin-Ashi.
+///
+/// `ha_close = (open + high + low + close) / 4`, `ha_open = (prev_open + prev_close) / 2`
+/// по предыдущей свече Heikin-Ashi, а для первой свечи ряда — `(open + close) / 2`.
+/// Максимум и минимум расширяются до `ha_open` и `ha_close`. Остальные поля
+/// копируются из `current`, отсутствующие цены считаются нулевыми.
+///
+/// # Аргументы
+/// * `prev_ha` - Предыдущая свеча Heikin-Ashi
+/// * `current` - Текущая свеча
+pub fn candle_to_heikin_ashi(prev_ha: Option<&Candle>, current: &Candle) -> Candle {
+    let price = |quotation: Option<Quotation>| quotation.map(Decimal::from).unwrap_or_default();
+    let (open, high, low, close) = (
+        price(current.open),
+        price(current.high),
+        price(current.low),
+        price(current.close),
+    );
+
+    let ha_close = (open + high + low + close) / Decimal::from(4);
+    let ha_open = match prev_ha {
+        Some(prev) => (price(prev.open) + price(prev.close)) / Decimal::TWO,
+        None => (open + close) / Decimal::TWO,
+    };
+    let quotation = |value: Decimal| Quotation::try_from(value.round_dp(9)).ok();
+
+    Candle {
+        open: quotation(ha_open),
+        high: quotation(high.max(ha_open).max(ha_close)),
+        low: quotation(low.min(ha_open).min(ha_close)),
+        close: quotation(ha_close),
+        ..current.clone()
+    }
+}
+
+/// Преобразует ряд свечей в свечи Heikin-Ashi.
+pub fn transform_to_heikin_ashi(candles: &[Candle]) -> Vec<Candle> {
+    let mut heikin_ashi: Vec<Candle> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        let next = candle_to_heikin_ashi(heikin_ashi.last(), candle);
+        heikin_ashi.push(next);
+    }
+
+    heikin_ashi
+}
+
+/// Удаляет свечи с нулевым объёмом.
+pub fn filter_zero_volume(candles: Vec<Candle>) -> Vec<Candle> {
+    candles
+        .into_iter()
+        .filter(|candle| candle.volume != 0)
+        .collect()
+}
+
+/// Удаляет свечи с аномальным объёмом.
+///
+/// Для каждой свечи рассчитываются среднее и стандартное отклонение объёма
+/// по `VOLUME_OUTLIER_WINDOW` предыдущим свечам исходного ряда. Свеча удаляется,
+/// если её объём отклоняется от среднего больше чем на `z_score_threshold`
+/// отклонений. Свечи, перед которыми меньше двух свечей, сохраняются.
+pub fn filter_volume_outliers(candles: Vec<Candle>, z_score_threshold: f64) -> Vec<Candle> {
+    let volumes: Vec<f64> = candles.iter().map(|candle| candle.volume as f64).collect();
+
+    candles
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            let window = &volumes[i.saturating_sub(VOLUME_OUTLIER_WINDOW)..*i];
+            if window.len() < 2 {
+                return true;
+            }
+
+            let mean = window.iter().sum::<f64>() / window.len() as f64;
+            let variance = window
+                .iter()
+                .map(|volume| (volume - mean).powi(2))
+                .sum::<f64>()
+                / window.len() as f64;
+            let deviation = (volumes[*i] - mean).abs();
+            if variance == 0.0 {
+                return deviation == 0.0;
+            }
+
+            deviation / variance.sqrt() <= z_score_threshold
+        })
+        .map(|(_, candle)| candle)
+        .collect()
+}
+
+/// Заполняет пропуски в ряду свечей синтетическими свечами.
+///
+/// Для каждого пропущенного интервала между соседними свечами добавляется свеча,
+/// все цены которой равны цене закрытия предыдущей свечи, с нулевым объёмом
+/// и `is_complete = false`. Пропуски заполняются и в неторговое время, поэтому
+/// для внутридневных интервалов ряд стоит ограничивать одной торговой сессией.
+/// Свечи без времени и ряды с интервалом `CandleInterval::Unspecified`
+/// не заполняются.
+///
+/// # Аргументы
+/// * `candles` - Свечи, упорядоченные по времени
+/// * `interval` - Интервал свечей
+pub fn fill_candle_gaps(
+    candles: &[HistoricCandle],
+    interval: CandleInterval,
+) -> Vec<HistoricCandle> {
+    let time = |candle: &HistoricCandle| {
+        candle
+            .time
+            .and_then(|time| timestamp_to_datetime_utc(time).ok())
+    };
+
+    let mut filled: Vec<HistoricCandle> = Vec::with_capacity(candles.len());
+    for candle in candles {
+        if let (Some(prev), Some(current)) = (filled.last().copied(), time(candle)) {
+            let mut next = time(&prev).and_then(|prev_time| next_candle_time(prev_time, interval));
+            while let Some(gap) = next.filter(|gap| *gap < current) {
+                filled.push(HistoricCandle {
+                    open: prev.close,
+                    high: prev.close,
+                    low: prev.close,
+                    close: prev.close,
+                    volume: 0,
+                    time: Some(datetime_utc_to_timestamp(gap)),
+                    is_complete: false,
+                    ..Default::default()
+                });
+                next = next_candle_time(gap, interval);
+            }
+        }
+        filled.push(*candle);
+    }
+
+    filled
+}
+
+/// Возвращает время начала свечи, следующей за свечой со временем `time`.
+fn next_candle_time(time: DateTime<Utc>, interval: CandleInterval) -> Option<DateTime<Utc>> {
+    let step = match interval {
+        CandleInterval::Unspecified => return None,
+        CandleInterval::Month => return time.checked_add_months(Months::new(1)),
+        CandleInterval::CandleInterval5Sec => TimeDelta::seconds(5),
+        CandleInterval::CandleInterval10Sec => TimeDelta::seconds(10),
+        CandleInterval::CandleInterval30Sec => TimeDelta::seconds(30),
+        CandleInterval::CandleInterval1Min => TimeDelta::minutes(1),
+        CandleInterval::CandleInterval2Min => TimeDelta::minutes(2),
+        CandleInterval::CandleInterval3Min => TimeDelta::minutes(3),
+        CandleInterval::CandleInterval5Min => TimeDelta::minutes(5),
+        CandleInterval::CandleInterval10Min => TimeDelta::minutes(10),
+        CandleInterval::CandleInterval15Min => TimeDelta::minutes(15),
+        CandleInterval::CandleInterval30Min => TimeDelta::minutes(30),
+        CandleInterval::Hour => TimeDelta::hours(1),
+        CandleInterval::CandleInterval2Hour => TimeDelta::hours(2),
+        CandleInterval::CandleInterval4Hour => TimeDelta::hours(4),
+        CandleInterval::Day => TimeDelta::days(1),
+        CandleInterval::Week => TimeDelta::weeks(1),
+    };
+
+    time.checked_add_signed(step)
+}
+
+/// Загрузчик исторических свечей за произвольный период.
+///
+/// API ограничивает период одного запроса свечей в зависимости от интервала
+/// (см. [`max_candles_request_range`]). Загрузчик разбивает запрошенный период
+/// на допустимые части, последовательно запрашивает их и объединяет результат,
+/// удаляя дубликаты по времени свечи.
+#[derive(Clone)]
+pub struct HistoricalCandleFetcher {
+    client: MarketDataServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    instrument_id: String,
+    interval: CandleInterval,
+}
+
+impl HistoricalCandleFetcher {
+    /// Создаёт загрузчик свечей инструмента.
+    ///
+    /// # Аргументы
+    /// * `client` - Клиент сервиса Market Data
+    /// * `figi` - FIGI или UID инструмента
+    /// * `interval` - Интервал свечей
+    pub fn new(
+        client: MarketDataServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+        figi: &str,
+        interval: CandleInterval,
+    ) -> Self {
+        Self {
+            client,
+            instrument_id: figi.to_string(),
+            interval,
+        }
+    }
+
+    /// Загружает свечи за период `[from, to)`.
+    ///
+    /// # Возвращает
+    /// Свечи, отсортированные по времени, без дубликатов
+    pub async fn fetch(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<HistoricCandle>, TInvestError> {
+        let mut client = self.client.clone();
+        let mut candles = BTreeMap::new();
+
+        for (chunk_from, chunk_to) in
+            split_range(from, to, max_candles_request_range(self.interval))
+        {
+            let response = client
+                .get_candles(GetCandlesRequest {
+                    from: Some(datetime_utc_to_timestamp(chunk_from)),
+                    to: Some(datetime_utc_to_timestamp(chunk_to)),
+                    interval: self.interval as i32,
+                    instrument_id: Some(self.instrument_id.clone()),
+                    ..Default::default()
+                })
+                .await?
+                .into_inner();
+
+            for candle in response.candles {
+                let key = candle.time.map(|time| (time.seconds, time.nanos));
+                candles.insert(key, candle);
+            }
+        }
+
+        Ok(candles.into_values().collect())
+    }
+
+    /// Загружает свечи за период `[from, to)` и проверяет их с помощью
+    /// [`validate_candle_sequence`].
+    ///
+    /// # Возвращает
+    /// Свечи и найденные в них нарушения
+    pub async fn fetch_validated(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(Vec<HistoricCandle>, Vec<(usize, CandleError)>), TInvestError> {
+        let candles = self.fetch(from, to).await?;
+        let errors = validate_candle_sequence(&candles);
+
+        Ok((candles, errors))
+    }
+}
+
+/// Разбивает период `[from, to)` на последовательные части не длиннее `max_range`.
+fn split_range(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    max_range: Option<TimeDelta>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let Some(max_range) = max_range else {
+        return vec![(from, to)];
+    };
+
+    let mut chunks = Vec::new();
+    let mut chunk_from = from;
+    while chunk_from < to {
+        let chunk_to = (chunk_from + max_range).min(to);
+        chunks.push((chunk_from, chunk_to));
+        chunk_from = chunk_to;
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn candle(seconds: i64, high: i64, low: i64, close: i64, volume: i64) -> HistoricCandle {
+        HistoricCandle {
+            open: Some(Quotation {
+                units: low,
+                nano: 0,
+            }),
+            high: Some(Quotation {
+                units: high,
+                nano: 0,
+            }),
+            low: Some(Quotation {
+                units: low,
+                nano: 0,
+            }),
+            close: Some(Quotation {
+                units: close,
+                nano: 0,
+            }),
+            volume,
+            time: Some(prost_types::Timestamp { seconds, nanos: 0 }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_single_candle() {
+        assert_eq!(Ok(()), validate_candle(&candle(0, 110, 100, 105, 10)));
+        assert_eq!(Ok(()), validate_candle(&candle(0, 100, 100, 100, 0)));
+        assert_eq!(Ok(()), validate_candle(&HistoricCandle::default()));
+
+        assert_eq!(
+            Err(CandleError::HighLessThanLow),
+            validate_candle(&candle(0, 90, 100, 95, 10))
+        );
+        assert_eq!(
+            Err(CandleError::CloseOutOfRange),
+            validate_candle(&candle(0, 110, 100, 111, 10))
+        );
+        assert_eq!(
+            Err(CandleError::CloseOutOfRange),
+            validate_candle(&candle(0, 110, 100, 99, 10))
+        );
+        assert_eq!(
+            Err(CandleError::VolumeNegative),
+            validate_candle(&candle(0, 110, 100, 105, -1))
+        );
+    }
+
+    #[test]
+    fn validate_sequence() {
+        let candles = vec![
+            candle(60, 110, 100, 105, 10),
+            candle(120, 90, 100, 95, 10),
+            candle(120, 110, 100, 105, 10),
+            candle(60, 110, 100, 105, -5),
+        ];
+
+        assert_eq!(
+            vec![
+                (1, CandleError::HighLessThanLow),
+                (
+                    2,
+                    CandleError::TimeNotMonotonic(
+                        prost_types::Timestamp {
+                            seconds: 120,
+                            nanos: 0
+                        },
+                        prost_types::Timestamp {
+                            seconds: 120,
+                            nanos: 0
+                        }
+                    )
+                ),
+                (3, CandleError::VolumeNegative),
+                (
+                    3,
+                    CandleError::TimeNotMonotonic(
+                        prost_types::Timestamp {
+                            seconds: 120,
+                            nanos: 0
+                        },
+                        prost_types::Timestamp {
+                            seconds: 60,
+                            nanos: 0
+                        }
+                    )
+                ),
+            ],
+            validate_candle_sequence(&candles)
+        );
+
+        assert!(validate_candle_sequence(&candles[..1]).is_empty());
+    }
+
+    #[test]
+    fn quality_report() {
+        let clean = vec![
+            candle(60, 110, 100, 105, 10),
+            candle(120, 110, 100, 105, 10),
+        ];
+        let report = candle_quality_report(&clean);
+        assert!(report.is_clean);
+        assert_eq!(
+            CandleQualityReport {
+                is_clean: true,
+                ..Default::default()
+            },
+            report
+        );
+        assert!(candle_quality_report(&[]).is_clean);
+
+        let candles = vec![
+            candle(60, 110, 100, 105, 10),
+            candle(120, 90, 100, 95, 10),
+            candle(120, 110, 100, 105, 0),
+            candle(60, 110, 100, 111, 10),
+            candle(120, 110, 100, 105, 10),
+            HistoricCandle {
+                time: None,
+                ..candle(0, 110, 100, 105, 0)
+            },
+        ];
+        let time = |seconds| Utc.timestamp_opt(seconds, 0).unwrap();
+        assert_eq!(
+            CandleQualityReport {
+                duplicate_timestamps: vec![time(120), time(60)],
+                out_of_order_timestamps: 1,
+                ohlc_violations: 2,
+                zero_volume_candles: 2,
+                is_clean: false,
+            },
+            candle_quality_report(&candles)
+        );
+    }
+
+    #[test]
+    fn bar_from_candle() {
+        let bar = OhlcvBar::try_from(HistoricCandle {
+            open: Some(Quotation {
+                units: 100,
+                nano: 500_000_000,
+            }),
+            high: Some(Quotation {
+                units: 110,
+                nano: 250_000_000,
+            }),
+            low: Some(Quotation { units: 99, nano: 0 }),
+            close: Some(Quotation { units: 98, nano: 1 }),
+            volume: 42,
+            time: Some(prost_types::Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 0,
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(Utc.timestamp_opt(1_700_000_000, 0).unwrap(), bar.time);
+        assert_eq!(dec!(100.5), bar.open);
+        assert_eq!(dec!(110.25), bar.high);
+        assert_eq!(dec!(99), bar.low);
+        assert_eq!(dec!(98.000000001), bar.close);
+        assert_eq!(dec!(42), bar.volume);
+        assert_eq!(dec!(11.25), bar.range());
+        assert_eq!(dec!(2.499999999), bar.body());
+    }
+
+    #[test]
+    fn bar_from_incomplete_candle() {
+        let complete = candle(60, 110, 100, 105, 10);
+        assert_eq!(OhlcvBar::try_from(complete), OhlcvBar::try_from(&complete));
+
+        let missing = |candle: HistoricCandle| OhlcvBar::try_from(&candle).map_err(|err| err.field);
+        assert_eq!(
+            Err("time"),
+            missing(HistoricCandle {
+                time: None,
+                ..complete
+            })
+        );
+        assert_eq!(
+            Err("time"),
+            missing(HistoricCandle {
+                time: Some(prost_types::Timestamp {
+                    seconds: 0,
+                    nanos: -1,
+                }),
+                ..complete
+            })
+        );
+        assert_eq!(
+            Err("open"),
+            missing(HistoricCandle {
+                open: None,
+                ..complete
+            })
+        );
+        assert_eq!(
+            Err("high"),
+            missing(HistoricCandle {
+                high: None,
+                ..complete
+            })
+        );
+        assert_eq!(
+            Err("low"),
+            missing(HistoricCandle {
+                low: None,
+                ..complete
+            })
+        );
+        assert_eq!(
+            Err("close"),
+            missing(HistoricCandle {
+                close: None,
+                ..complete
+            })
+        );
+        assert_eq!(
+            Err("high"),
+            missing(HistoricCandle {
+                high: None,
+                close: None,
+                ..complete
+            })
+        );
+        assert_eq!(
+            "Candle close is missing or invalid",
+            CandleConversionError { field: "close" }.to_string()
+        );
+    }
+
+    #[test]
+    fn bars_from_invalid_candles() {
+        let candles = vec![candle(60, 110, 100, 105, 10), HistoricCandle::default()];
+        assert!(OhlcvBar::try_from(candles[1]).is_err());
+        assert!(matches!(
+            bars_from_candles(candles.clone()),
+            Err(TInvestError::InvalidArgument(_))
+        ));
+
+        let bars = bars_from_candles(candles[..1].to_vec()).unwrap();
+        assert_eq!(dec!(105), bars[0].close);
+    }
+
+    #[test]
+    fn split_range_into_chunks() {
+        let from = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 1, 3, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            vec![
+                (from, from + TimeDelta::days(1)),
+                (from + TimeDelta::days(1), from + TimeDelta::days(2)),
+                (from + TimeDelta::days(2), to),
+            ],
+            split_range(
+                from,
+                to,
+                max_candles_request_range(CandleInterval::CandleInterval1Min)
+            )
+        );
+
+        assert_eq!(
+            vec![(from, to)],
+            split_range(from, to, max_candles_request_range(CandleInterval::Day))
+        );
+
+        assert_eq!(vec![(from, to)], split_range(from, to, None));
+        assert!(split_range(to, from, Some(MAX_RANGE_1_MIN)).is_empty());
+    }
+
+    #[test]
+    fn split_five_years_of_daily_candles() {
+        let from = Utc.with_ymd_and_hms(2015, 1, 1, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+        let chunks = split_range(from, to, max_candles_request_range(CandleInterval::Day));
+
+        assert_eq!(2, chunks.len());
+        assert_eq!(from, chunks[0].0);
+        assert_eq!(chunks[0].1, chunks[1].0);
+        assert_eq!(to, chunks[1].1);
+    }
+
+    #[test]
+    fn heikin_ashi_series() {
+        let price = |value: Decimal| Some(Quotation::try_from(value).unwrap());
+        let stream_candle = |open, high, low, close| Candle {
+            figi: "FIGI".to_string(),
+            open: price(open),
+            high: price(high),
+            low: price(low),
+            close: price(close),
+            volume: 100,
+            ..Default::default()
+        };
+        let candles = [
+            stream_candle(dec!(10), dec!(14), dec!(8), dec!(12)),
+            stream_candle(dec!(12), dec!(16), dec!(11), dec!(15)),
+            stream_candle(dec!(15), dec!(15), dec!(13), dec!(14)),
+        ];
+
+        let heikin_ashi = transform_to_heikin_ashi(&candles);
+
+        assert_eq!(3, heikin_ashi.len());
+        assert_eq!(
+            stream_candle(dec!(11), dec!(14), dec!(8), dec!(11)),
+            heikin_ashi[0]
+        );
+        assert_eq!(
+            stream_candle(dec!(11), dec!(16), dec!(11), dec!(13.5)),
+            heikin_ashi[1]
+        );
+        // Минимум опускается до ha_open = (11 + 13.5) / 2.
+        assert_eq!(
+            stream_candle(dec!(12.25), dec!(15), dec!(12.25), dec!(14.25)),
+            heikin_ashi[2]
+        );
+        assert_eq!(
+            heikin_ashi[2],
+            candle_to_heikin_ashi(Some(&heikin_ashi[1]), &candles[2])
+        );
+
+        assert!(transform_to_heikin_ashi(&[]).is_empty());
+    }
+
+    #[test]
+    fn volume_filters() {
+        let candles: Vec<Candle> = [100, 0, 110, 90, 105, 5000, 95, 0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, volume)| Candle {
+                volume,
+                time: Some(prost_types::Timestamp {
+                    seconds: i as i64 * 60,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            })
+            .collect();
+
+        let volumes = |candles: &[Candle]| candles.iter().map(|c| c.volume).collect::<Vec<_>>();
+
+        let non_zero = filter_zero_volume(candles.clone());
+        assert_eq!(vec![100, 110, 90, 105, 5000, 95], volumes(&non_zero));
+
+        let filtered = filter_volume_outliers(non_zero, 3.0);
+        assert_eq!(vec![100, 110, 90, 105, 95], volumes(&filtered));
+
+        // Постоянный объём без разброса: отличающиеся свечи удаляются.
+        let flat: Vec<Candle> = [10, 10, 10, 11, 10]
+            .into_iter()
+            .map(|volume| Candle {
+                volume,
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(
+            vec![10, 10, 10, 10],
+            volumes(&filter_volume_outliers(flat, 3.0))
+        );
+        assert!(filter_volume_outliers(Vec::new(), 3.0).is_empty());
+    }
+
+    #[test]
+    fn fill_gaps() {
+        let mut first = candle(600, 110, 100, 105, 10);
+        first.is_complete = true;
+        let mut last = candle(840, 120, 106, 112, 20);
+        last.is_complete = true;
+        let candles = vec![first, candle(660, 108, 104, 106, 5), last];
+
+        let filled = fill_candle_gaps(&candles, CandleInterval::CandleInterval1Min);
+        let times: Vec<i64> = filled
+            .iter()
+            .map(|candle| candle.time.unwrap().seconds)
+            .collect();
+        assert_eq!(vec![600, 660, 720, 780, 840], times);
+        assert_eq!(first, filled[0]);
+        assert_eq!(last, filled[4]);
+        for synthetic in &filled[2..4] {
+            let close = Some(Quotation {
+                units: 106,
+                nano: 0,
+            });
+            assert_eq!(
+                (close, close, close, close),
+                (
+                    synthetic.open,
+                    synthetic.high,
+                    synthetic.low,
+                    synthetic.close
+                )
+            );
+            assert_eq!(0, synthetic.volume);
+            assert!(!synthetic.is_complete);
+        }
+
+        assert_eq!(
+            candles,
+            fill_candle_gaps(&candles, CandleInterval::Unspecified)
+        );
+        assert_eq!(
+            candles,
+            fill_candle_gaps(&candles, CandleInterval::CandleInterval2Hour)
+        );
+
+        let month = |month| {
+            let time = Utc.with_ymd_and_hms(2024, month, 1, 0, 0, 0).unwrap();
+            candle(time.timestamp(), 110, 100, 105, 10)
+        };
+        let filled = fill_candle_gaps(&[month(1), month(4)], CandleInterval::Month);
+        assert_eq!(
+            vec![month(2).time, month(3).time],
+            filled[1..3]
+                .iter()
+                .map(|candle| candle.time)
+                .collect::<Vec<_>>()
+        );
+    }
+}
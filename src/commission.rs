@@ -0,0 +1,189 @@
+use rust_decimal::Decimal;
+
+use crate::api::{
+    AccountType, GetAccountsRequest, GetInfoRequest, InstrumentIdType, InstrumentRequest,
+    InstrumentType,
+};
+use crate::{TInvestError, TInvestSdk};
+
+/// Тарифный план брокерского обслуживания.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tariff {
+    /// Тариф «Инвестор»
+    Investor,
+    /// Тариф «Трейдер»
+    Trader,
+    /// Тариф «Премиум»
+    Premium,
+    /// Собственная ставка в виде доли от объёма сделки для всех инструментов
+    Custom(Decimal),
+}
+
+impl Tariff {
+    /// Возвращает ставку комиссии в виде доли от объёма сделки.
+    ///
+    /// Ставки соответствуют опубликованным тарифам брокера на момент написания
+    /// и могут меняться. Для точного расчёта используйте [`Tariff::Custom`].
+    pub fn rate(&self, instrument_type: InstrumentType) -> Decimal {
+        let (securities, currency, derivatives) = match self {
+            Tariff::Investor => (Decimal::new(3, 3), Decimal::new(5, 3), Decimal::new(4, 4)),
+            Tariff::Trader => (Decimal::new(5, 4), Decimal::new(5, 4), Decimal::new(25, 5)),
+            Tariff::Premium => (Decimal::new(4, 4), Decimal::new(4, 4), Decimal::new(25, 5)),
+            Tariff::Custom(rate) => return *rate,
+        };
+
+        match instrument_type {
+            InstrumentType::Currency => currency,
+            InstrumentType::Futures | InstrumentType::Option => derivatives,
+            _ => securities,
+        }
+    }
+
+    /// Определяет тариф по названию из `GetInfo`. Неизвестные названия считаются тарифом «Инвестор».
+    fn from_name(name: &str, premium: bool) -> Self {
+        let name = name.to_lowercase();
+        if premium || name.contains("premium") {
+            Tariff::Premium
+        } else if name.contains("trader") {
+            Tariff::Trader
+        } else {
+            Tariff::Investor
+        }
+    }
+}
+
+/// Оценивает комиссию брокера за сделку.
+///
+/// Комиссия рассчитывается как `|quantity| * price * ставка тарифа`.
+/// Для инвесткопилки комиссия не взимается.
+///
+/// # Аргументы
+/// * `account_type` - Тип счёта
+/// * `instrument_type` - Тип инструмента
+/// * `quantity` - Количество инструментов (не лотов)
+/// * `price` - Цена одного инструмента
+/// * `tariff` - Тариф пользователя
+pub fn estimate_commission(
+    account_type: AccountType,
+    instrument_type: InstrumentType,
+    quantity: i64,
+    price: Decimal,
+    tariff: Tariff,
+) -> Decimal {
+    if account_type == AccountType::InvestBox {
+        return Decimal::ZERO;
+    }
+
+    Decimal::from(quantity).abs() * price * tariff.rate(instrument_type)
+}
+
+/// Оценивает комиссию за сделку по счёту пользователя.
+///
+/// Тип счёта и тариф запрашиваются в сервисе пользователей, тип инструмента —
+/// в сервисе инструментов.
+///
+/// # Аргументы
+/// * `sdk` - Экземпляр SDK
+/// * `account_id` - Идентификатор счёта
+/// * `figi` - FIGI инструмента
+/// * `quantity` - Количество инструментов (не лотов)
+/// * `price` - Цена одного инструмента
+pub async fn get_and_estimate_commission(
+    sdk: &TInvestSdk,
+    account_id: &str,
+    figi: &str,
+    quantity: i64,
+    price: Decimal,
+) -> Result<Decimal, TInvestError> {
+    let mut users = sdk.users();
+
+    let account = users
+        .get_accounts(GetAccountsRequest::default())
+        .await?
+        .into_inner()
+        .accounts
+        .into_iter()
+        .find(|account| account.id == account_id)
+        .ok_or_else(|| TInvestError::NotFound(format!("account {}", account_id)))?;
+
+    let info = users.get_info(GetInfoRequest {}).await?.into_inner();
+
+    let instrument = sdk
+        .instruments()
+        .get_instrument_by(InstrumentRequest {
+            id_type: InstrumentIdType::Figi.into(),
+            id: figi.to_string(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .instrument
+        .ok_or_else(|| TInvestError::NotFound(format!("instrument with FIGI {}", figi)))?;
+
+    Ok(estimate_commission(
+        account.r#type(),
+        instrument.instrument_kind(),
+        quantity,
+        price,
+        Tariff::from_name(&info.tariff, info.prem_status),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn tariff_tiers() {
+        let estimate = |instrument_type, tariff| {
+            estimate_commission(AccountType::Tinkoff, instrument_type, 10, dec!(250), tariff)
+        };
+
+        assert_eq!(dec!(7.5), estimate(InstrumentType::Share, Tariff::Investor));
+        assert_eq!(
+            dec!(12.5),
+            estimate(InstrumentType::Currency, Tariff::Investor)
+        );
+        assert_eq!(dec!(1.25), estimate(InstrumentType::Bond, Tariff::Trader));
+        assert_eq!(
+            dec!(0.625),
+            estimate(InstrumentType::Futures, Tariff::Trader)
+        );
+        assert_eq!(dec!(1), estimate(InstrumentType::Etf, Tariff::Premium));
+        assert_eq!(
+            dec!(25),
+            estimate(InstrumentType::Share, Tariff::Custom(dec!(0.01)))
+        );
+        assert_eq!(
+            dec!(7.5),
+            estimate_commission(
+                AccountType::TinkoffIis,
+                InstrumentType::Share,
+                -10,
+                dec!(250),
+                Tariff::Investor
+            )
+        );
+        assert_eq!(
+            Decimal::ZERO,
+            estimate_commission(
+                AccountType::InvestBox,
+                InstrumentType::Etf,
+                10,
+                dec!(250),
+                Tariff::Investor
+            )
+        );
+    }
+
+    #[test]
+    fn tariff_from_name() {
+        assert_eq!(Tariff::Investor, Tariff::from_name("investor", false));
+        assert_eq!(Tariff::Trader, Tariff::from_name("Trader", false));
+        assert_eq!(Tariff::Premium, Tariff::from_name("premium", false));
+        assert_eq!(Tariff::Premium, Tariff::from_name("investor", true));
+        assert_eq!(Tariff::Investor, Tariff::from_name("", false));
+    }
+}
@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::Environment;
+
+/// Конфигурация SDK, загружаемая из TOML файла.
+///
+/// Используется в [`crate::TInvestSdk::new_from_config_file`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct SdkConfig {
+    /// API токен для аутентификации
+    pub token: String,
+    /// Среда для подключения
+    pub environment: Environment,
+    /// Таймаут установки соединения в секундах
+    pub connect_timeout_secs: Option<u64>,
+    /// Таймаут выполнения запроса в секундах
+    pub request_timeout_secs: Option<u64>,
+}
+
+/// Ошибки, которые могут возникнуть при загрузке файла конфигурации.
+///
+/// - `Io`: Файл не удалось прочитать
+/// - `Parse`: Содержимое файла не является корректной конфигурацией
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Can't read config file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Can't parse config: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+impl SdkConfig {
+    /// Читает и разбирает конфигурацию из TOML файла.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        Self::from_toml(&content)
+    }
+
+    /// Разбирает конфигурацию из строки в формате TOML.
+    pub fn from_toml(content: &str) -> Result<Self, ConfigError> {
+        Ok(toml::from_str(content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = SdkConfig::from_toml(
+            r#"
+            token = "t.test"
+            environment = "sandbox"
+            connect_timeout_secs = 10
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!("t.test", config.token);
+        assert!(matches!(config.environment, Environment::Sandbox));
+        assert_eq!(Some(10), config.connect_timeout_secs);
+        assert_eq!(None, config.request_timeout_secs);
+    }
+
+    #[test]
+    fn parse_invalid_config() {
+        assert!(matches!(
+            SdkConfig::from_toml(r#"token = "t.test""#),
+            Err(ConfigError::Parse(_))
+        ));
+
+        assert!(matches!(
+            SdkConfig::from_toml(
+                r#"
+                token = "t.test"
+                environment = "staging"
+                "#
+            ),
+            Err(ConfigError::Parse(_))
+        ));
+
+        assert!(matches!(
+            SdkConfig::from_file(Path::new("/nonexistent/t-invest.toml")),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+}
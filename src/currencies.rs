@@ -0,0 +1,135 @@
+use rust_decimal::Decimal;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    Currency, InstrumentsRequest, instruments_service_client::InstrumentsServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor};
+
+/// Валютная пара, доступная для торговли.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyPair {
+    /// FIGI-идентификатор инструмента
+    pub figi: String,
+    /// Базовая валюта в нижнем регистре, например `usd`
+    pub base_currency: String,
+    /// Котируемая валюта в нижнем регистре, например `rub`
+    pub quote_currency: String,
+    /// Лотность инструмента
+    pub lot_size: i32,
+    /// Шаг цены
+    pub min_price_increment: Decimal,
+}
+
+impl From<Currency> for CurrencyPair {
+    /// Определяет валюты пары по тикеру вида `USDRUB_TOM` или `EUR_RUB__TOM`.
+    ///
+    /// Если тикер не удалось разобрать, базовой валютой считается ISO-код
+    /// инструмента, а котируемой — валюта расчётов.
+    fn from(currency: Currency) -> Self {
+        let (base_currency, quote_currency) = parse_currency_ticker(&currency.ticker)
+            .unwrap_or_else(|| {
+                (
+                    currency.iso_currency_name.to_lowercase(),
+                    currency.currency.to_lowercase(),
+                )
+            });
+
+        Self {
+            figi: currency.figi,
+            base_currency,
+            quote_currency,
+            lot_size: currency.lot,
+            min_price_increment: currency
+                .min_price_increment
+                .map(Decimal::from)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Возвращает валютные пары, доступные для торговли.
+///
+/// # Аргументы
+/// * `client` - Клиент сервиса инструментов
+pub async fn get_currency_pairs(
+    client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+) -> Result<Vec<CurrencyPair>, TInvestError> {
+    let response = client
+        .currencies(InstrumentsRequest::default())
+        .await?
+        .into_inner();
+
+    Ok(response
+        .instruments
+        .into_iter()
+        .map(CurrencyPair::from)
+        .collect())
+}
+
+/// Разбирает тикер валютной пары на базовую и котируемую валюты.
+fn parse_currency_ticker(ticker: &str) -> Option<(String, String)> {
+    let is_code = |part: &str| part.len() == 3 && part.chars().all(|c| c.is_ascii_alphabetic());
+    let mut parts = ticker.split('_').filter(|part| !part.is_empty());
+    let first = parts.next()?;
+
+    let (base, quote) = if first.len() == 6 && first.is_ascii() {
+        first.split_at(3)
+    } else {
+        (first, parts.next()?)
+    };
+
+    if !is_code(base) || !is_code(quote) {
+        return None;
+    }
+
+    Some((base.to_lowercase(), quote.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Quotation;
+
+    #[test]
+    fn parse_ticker() {
+        let pair = |base: &str, quote: &str| Some((base.to_string(), quote.to_string()));
+
+        assert_eq!(pair("usd", "rub"), parse_currency_ticker("USDRUB_TOM"));
+        assert_eq!(pair("cny", "rub"), parse_currency_ticker("CNYRUB_TOD"));
+        assert_eq!(pair("eur", "rub"), parse_currency_ticker("EUR_RUB__TOM"));
+        assert_eq!(None, parse_currency_ticker("USD000UTSTOM"));
+        assert_eq!(None, parse_currency_ticker(""));
+    }
+
+    #[test]
+    fn pair_from_currency() {
+        let currency = |ticker: &str| Currency {
+            figi: "BBG0013HGFT4".to_string(),
+            ticker: ticker.to_string(),
+            lot: 1000,
+            currency: "rub".to_string(),
+            iso_currency_name: "usd".to_string(),
+            min_price_increment: Some(Quotation {
+                units: 0,
+                nano: 2_500_000,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            CurrencyPair {
+                figi: "BBG0013HGFT4".to_string(),
+                base_currency: "usd".to_string(),
+                quote_currency: "rub".to_string(),
+                lot_size: 1000,
+                min_price_increment: Decimal::new(25, 4),
+            },
+            CurrencyPair::from(currency("USDRUB_TOM"))
+        );
+
+        let pair = CurrencyPair::from(currency("USD000UTSTOM"));
+        assert_eq!("usd", pair.base_currency);
+        assert_eq!("rub", pair.quote_currency);
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use prost_types::Timestamp;
+
+/// Преобразует DateTime<Utc> в Timestamp.
+///
+/// Реализовать `From<DateTime<Utc>> for Timestamp` в этом крейте нельзя
+/// (оба типа внешние), поэтому преобразование доступно в виде функции.
+pub fn datetime_utc_to_timestamp(datetime: DateTime<Utc>) -> Timestamp {
+    Timestamp {
+        seconds: datetime.timestamp(),
+        nanos: datetime.timestamp_subsec_nanos() as i32,
+    }
+}
+
+/// Пытается преобразовать Timestamp в DateTime<Utc>.
+///
+/// Возвращает ошибку, если значение выходит за пределы диапазона,
+/// поддерживаемого DateTime, или содержит отрицательные наносекунды.
+pub fn timestamp_to_datetime_utc(timestamp: Timestamp) -> Result<DateTime<Utc>, String> {
+    let nanos = u32::try_from(timestamp.nanos)
+        .map_err(|_| format!("Can't convert timestamp {} to datetime", timestamp))?;
+
+    DateTime::from_timestamp(timestamp.seconds, nanos)
+        .ok_or_else(|| format!("Can't convert timestamp {} to datetime", timestamp))
+}
+
+/// Преобразует NaiveDate в Timestamp, соответствующий началу дня по UTC.
+pub fn naive_date_to_timestamp(date: NaiveDate) -> Timestamp {
+    datetime_utc_to_timestamp(date.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// Пытается преобразовать Timestamp в NaiveDate (дата по UTC).
+pub fn timestamp_to_naive_date(timestamp: Timestamp) -> Result<NaiveDate, String> {
+    timestamp_to_datetime_utc(timestamp).map(|datetime| datetime.date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn datetime_to_timestamp() {
+        assert_eq!(
+            Timestamp {
+                seconds: 0,
+                nanos: 0
+            },
+            datetime_utc_to_timestamp(DateTime::UNIX_EPOCH)
+        );
+
+        assert_eq!(
+            Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 250_000_000
+            },
+            datetime_utc_to_timestamp(
+                DateTime::from_timestamp(1_700_000_000, 250_000_000).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn timestamp_to_datetime() {
+        assert_eq!(
+            Ok(Utc.with_ymd_and_hms(2024, 3, 15, 10, 30, 0).unwrap()),
+            timestamp_to_datetime_utc(Timestamp {
+                seconds: 1_710_498_600,
+                nanos: 0
+            })
+        );
+
+        assert!(
+            timestamp_to_datetime_utc(Timestamp {
+                seconds: 0,
+                nanos: -1
+            })
+            .is_err()
+        );
+
+        assert!(
+            timestamp_to_datetime_utc(Timestamp {
+                seconds: i64::MAX,
+                nanos: 0
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn naive_date_round_trip() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        assert_eq!(
+            Timestamp {
+                seconds: 1_710_460_800,
+                nanos: 0
+            },
+            naive_date_to_timestamp(date)
+        );
+
+        assert_eq!(
+            Ok(date),
+            timestamp_to_naive_date(Timestamp {
+                seconds: 1_710_498_600,
+                nanos: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+/// Расчёт доходностей для `Decimal`.
+///
+/// Изменения и доходности задаются в процентах, как в
+/// [`StressTestScenario`](crate::StressTestScenario). Логарифм и степень
+/// вычисляются через `f64`, поэтому `log_return` и `annualize_return`
+/// приближённые.
+pub trait DecimalExt {
+    /// Рассчитывает изменение от `self` до `new_value` в процентах.
+    ///
+    /// # Возвращает
+    /// `None`, если начальное значение равно нулю
+    fn pct_change(&self, new_value: &Decimal) -> Option<Decimal>;
+
+    /// Рассчитывает логарифмическую доходность `ln(new_value / self)`.
+    ///
+    /// # Возвращает
+    /// `None`, если одно из значений неположительно
+    fn log_return(&self, new_value: &Decimal) -> Option<Decimal>;
+
+    /// Переводит доходность в процентах за `periods` периодов в годовую
+    /// `((1 + self / 100) ^ (periods_per_year / periods) - 1) * 100`.
+    ///
+    /// При `periods == 0` возвращается `0`, при убытке от 100% — `-100`.
+    fn annualize_return(&self, periods: u32, periods_per_year: u32) -> Decimal;
+}
+
+impl DecimalExt for Decimal {
+    fn pct_change(&self, new_value: &Decimal) -> Option<Decimal> {
+        if self.is_zero() {
+            return None;
+        }
+
+        Some((new_value - self) / self * Decimal::ONE_HUNDRED)
+    }
+
+    fn log_return(&self, new_value: &Decimal) -> Option<Decimal> {
+        if *self <= Decimal::ZERO || *new_value <= Decimal::ZERO {
+            return None;
+        }
+
+        Decimal::from_f64((new_value / self).to_f64()?.ln())
+    }
+
+    fn annualize_return(&self, periods: u32, periods_per_year: u32) -> Decimal {
+        if periods == 0 {
+            return Decimal::ZERO;
+        }
+        let growth = Decimal::ONE + self / Decimal::ONE_HUNDRED;
+        if growth <= Decimal::ZERO {
+            return -Decimal::ONE_HUNDRED;
+        }
+
+        let exponent = f64::from(periods_per_year) / f64::from(periods);
+        growth
+            .to_f64()
+            .map(|growth| (growth.powf(exponent) - 1.0) * 100.0)
+            .and_then(Decimal::from_f64)
+            .unwrap_or(Decimal::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn returns() {
+        assert_eq!(Some(dec!(10)), dec!(200).pct_change(&dec!(220)));
+        assert_eq!(Some(dec!(-25)), dec!(200).pct_change(&dec!(150)));
+        assert_eq!(Some(dec!(-200)), dec!(-100).pct_change(&dec!(100)));
+        assert_eq!(None, Decimal::ZERO.pct_change(&dec!(100)));
+
+        assert_eq!(
+            Some(dec!(0.693147)),
+            dec!(100).log_return(&dec!(200)).map(|r| r.round_dp(6))
+        );
+        assert_eq!(Some(Decimal::ZERO), dec!(100).log_return(&dec!(100)));
+        assert_eq!(None, dec!(100).log_return(&Decimal::ZERO));
+        assert_eq!(None, dec!(-1).log_return(&dec!(100)));
+
+        // 1% в месяц: 1.01^12 - 1 = 12.68%
+        assert_eq!(dec!(12.6825), dec!(1).annualize_return(1, 12).round_dp(4));
+        // 21% за два года: 1.21^0.5 - 1 = 10%
+        assert_eq!(dec!(10), dec!(21).annualize_return(2, 1).round_dp(6));
+        assert_eq!(Decimal::ZERO, dec!(5).annualize_return(0, 252));
+        assert_eq!(dec!(-100), dec!(-100).annualize_return(1, 12));
+    }
+}
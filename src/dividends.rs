@@ -0,0 +1,258 @@
+use chrono::{Duration, NaiveDate};
+use rust_decimal::Decimal;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{self, GetDividendsRequest, instruments_service_client::InstrumentsServiceClient};
+use crate::{
+    TInvestError, TInvestInterceptor, naive_date_to_timestamp, timestamp_to_datetime_utc,
+    timestamp_to_naive_date,
+};
+
+/// Тип отменённой выплаты дивидендов.
+const CANCELLED_DIVIDEND_TYPE: &str = "Cancelled";
+
+/// Выплата дивидендов с датами в виде `NaiveDate` и суммами в виде `Decimal`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dividend {
+    /// FIGI-идентификатор инструмента
+    pub figi: String,
+    /// Дата объявления дивидендов
+    pub declared_date: NaiveDate,
+    /// Дата фиксации реестра
+    pub record_date: NaiveDate,
+    /// Дата фактической выплаты
+    pub payment_date: NaiveDate,
+    /// Величина дивиденда на одну бумагу
+    pub dividend_net: Decimal,
+    /// Валюта выплаты
+    pub currency: String,
+    /// Доходность выплаты
+    pub yield_value: Decimal,
+}
+
+impl TryFrom<api::Dividend> for Dividend {
+    type Error = String;
+
+    /// Ответ API не содержит FIGI, поэтому поле `figi` остаётся пустым.
+    ///
+    /// Возвращает ошибку, если не указана одна из дат.
+    fn try_from(dividend: api::Dividend) -> Result<Self, Self::Error> {
+        let date = |timestamp: Option<prost_types::Timestamp>, name: &str| {
+            timestamp
+                .ok_or_else(|| format!("Dividend has no {}", name))
+                .and_then(timestamp_to_naive_date)
+        };
+        let dividend_net = dividend.dividend_net.unwrap_or_default();
+
+        Ok(Self {
+            figi: String::new(),
+            declared_date: date(dividend.declared_date, "declared date")?,
+            record_date: date(dividend.record_date, "record date")?,
+            payment_date: date(dividend.payment_date, "payment date")?,
+            currency: dividend_net.currency.clone(),
+            dividend_net: dividend_net.into(),
+            yield_value: dividend.yield_value.map(Decimal::from).unwrap_or_default(),
+        })
+    }
+}
+
+/// Возвращает дивиденды по инструменту за интервал `[from, to]`.
+///
+/// Выплаты, у которых не указана одна из дат, пропускаются.
+///
+/// # Аргументы
+/// * `client` - Клиент сервиса инструментов
+/// * `figi` - FIGI инструмента
+/// * `from` - Начало интервала
+/// * `to` - Конец интервала (включительно)
+pub async fn get_dividends_typed(
+    client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    figi: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<Dividend>, TInvestError> {
+    let dividends = client
+        .get_dividends(GetDividendsRequest {
+            instrument_id: figi.to_string(),
+            from: Some(naive_date_to_timestamp(from)),
+            to: Some(naive_date_to_timestamp(to + Duration::days(1))),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .dividends;
+
+    Ok(typed_dividends(dividends, figi))
+}
+
+/// Преобразует выплаты API, пропуская выплаты без дат.
+fn typed_dividends(dividends: Vec<api::Dividend>, figi: &str) -> Vec<Dividend> {
+    dividends
+        .into_iter()
+        .filter_map(|dividend| Dividend::try_from(dividend).ok())
+        .map(|dividend| Dividend {
+            figi: figi.to_string(),
+            ..dividend
+        })
+        .collect()
+}
+
+/// Моделирует рост позиции при реинвестировании дивидендов (DRIP).
+///
+/// Дивиденды обрабатываются в порядке даты выплаты. Каждая выплата
+/// `shares_held * dividend_net` за вычетом комиссии реинвестируется по последней
+/// известной на дату выплаты цене из `prices`, допускается дробное количество бумаг.
+/// Отменённые выплаты, выплаты без даты или суммы и выплаты, до даты которых
+/// нет ни одной цены, пропускаются. Налоги не учитываются.
+///
+/// # Аргументы
+/// * `initial_shares` - Начальное количество бумаг
+/// * `dividends` - Выплаты дивидендов на одну бумагу
+/// * `prices` - Цены инструмента по датам
+/// * `commission` - Комиссия за покупку в виде доли от суммы, например `0.003`
+///
+/// # Возвращает
+/// Тройки `(дата выплаты, количество бумаг, стоимость позиции)` после каждого реинвестирования
+pub fn drip_compound_growth(
+    initial_shares: Decimal,
+    dividends: &[api::Dividend],
+    prices: &[(NaiveDate, Decimal)],
+    commission: Decimal,
+) -> Vec<(NaiveDate, Decimal, Decimal)> {
+    let mut payments: Vec<(NaiveDate, Decimal)> = dividends
+        .iter()
+        .filter(|dividend| dividend.dividend_type != CANCELLED_DIVIDEND_TYPE)
+        .filter_map(|dividend| {
+            let date = timestamp_to_datetime_utc(dividend.payment_date?).ok()?;
+            Some((date.date_naive(), dividend.dividend_net.clone()?.into()))
+        })
+        .collect();
+    payments.sort_by_key(|(date, _)| *date);
+
+    let mut prices = prices.to_vec();
+    prices.sort_by_key(|(date, _)| *date);
+
+    let mut shares = initial_shares;
+    payments
+        .into_iter()
+        .filter_map(|(date, amount)| {
+            let (_, price) = prices
+                .iter()
+                .rev()
+                .find(|(price_date, _)| *price_date <= date)?;
+            if price.is_zero() {
+                return None;
+            }
+
+            let reinvested = shares * amount * (Decimal::ONE - commission);
+            shares += reinvested / price;
+            Some((date, shares, shares * price))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, Quotation};
+    use crate::datetime_utc_to_timestamp;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn dividend(month: u32, day: u32, amount: Decimal, dividend_type: &str) -> api::Dividend {
+        let amount = Quotation::try_from(amount).unwrap();
+        api::Dividend {
+            dividend_net: Some(MoneyValue {
+                currency: "rub".to_string(),
+                units: amount.units,
+                nano: amount.nano,
+            }),
+            payment_date: Some(datetime_utc_to_timestamp(
+                Utc.with_ymd_and_hms(2024, month, day, 0, 0, 0).unwrap(),
+            )),
+            dividend_type: dividend_type.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn compound_growth() {
+        let dividends = [
+            dividend(7, 10, dec!(5), "Regular Cash"),
+            dividend(1, 10, dec!(10), "Regular Cash"),
+            dividend(4, 10, dec!(100), "Cancelled"),
+        ];
+        let prices = [
+            (date(6, 1), dec!(110)),
+            (date(1, 1), dec!(100)),
+            (date(7, 10), dec!(120)),
+        ];
+
+        let growth = drip_compound_growth(dec!(100), &dividends, &prices, Decimal::ZERO);
+
+        // 100 + 100 * 10 / 100 = 110; 110 + 110 * 5 / 120 = 114.583...
+        assert_eq!(2, growth.len());
+        assert_eq!((date(1, 10), dec!(110), dec!(11000)), growth[0]);
+        assert_eq!(date(7, 10), growth[1].0);
+        assert_eq!(dec!(114.5833), growth[1].1.round_dp(4));
+        assert_eq!(dec!(13750), growth[1].2.round_dp(4));
+    }
+
+    #[test]
+    fn commission_and_missing_prices() {
+        let dividends = [
+            dividend(1, 10, dec!(10), "Regular Cash"),
+            dividend(2, 10, dec!(10), "Regular Cash"),
+        ];
+        let prices = [(date(2, 1), dec!(100))];
+
+        // 100 * 10 * 0.99 / 100 = 9.9
+        assert_eq!(
+            vec![(date(2, 10), dec!(109.9), dec!(10990))],
+            drip_compound_growth(dec!(100), &dividends, &prices, dec!(0.01))
+        );
+    }
+
+    #[test]
+    fn typed_dividend() {
+        let timestamp = |month, day| Some(naive_date_to_timestamp(date(month, day)));
+        let dividend = api::Dividend {
+            declared_date: timestamp(4, 26),
+            record_date: timestamp(7, 11),
+            last_buy_date: timestamp(7, 10),
+            yield_value: Some(Quotation {
+                units: 10,
+                nano: 680_000_000,
+            }),
+            ..dividend(7, 20, dec!(33.3), "Regular Cash")
+        };
+
+        assert_eq!(
+            Dividend {
+                figi: String::new(),
+                declared_date: date(4, 26),
+                record_date: date(7, 11),
+                payment_date: date(7, 20),
+                dividend_net: dec!(33.3),
+                currency: "rub".to_string(),
+                yield_value: dec!(10.68),
+            },
+            Dividend::try_from(dividend.clone()).unwrap()
+        );
+        let incomplete = api::Dividend {
+            record_date: None,
+            ..dividend.clone()
+        };
+        assert!(Dividend::try_from(incomplete.clone()).is_err());
+
+        let typed = typed_dividends(vec![incomplete, dividend], "BBG004730N88");
+        assert_eq!(1, typed.len());
+        assert_eq!("BBG004730N88", typed[0].figi);
+        assert_eq!(date(7, 20), typed[0].payment_date);
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+
+use crate::api::{CandleInterval, HistoricCandle};
+use crate::{HistoricalCandleFetcher, TInvestError, TInvestSdk, timestamp_to_naive_date};
+
+/// Глубина поиска предыдущего торгового дня в календарных днях.
+const TRADING_DAY_LOOKBACK_DAYS: u64 = 14;
+
+/// Хранилище загруженных свечей.
+///
+/// Реализуется пользователем для сохранения свечей в файл, базу данных и т.п.
+pub trait CandleStorage: Send + Sync {
+    /// Сохраняет свечи инструмента с указанным интервалом.
+    ///
+    /// Ошибки хранилища следует возвращать как `TInvestError::Storage`.
+    fn store(
+        &self,
+        figi: &str,
+        interval: CandleInterval,
+        candles: &[HistoricCandle],
+    ) -> Result<(), TInvestError>;
+}
+
+/// Загрузчик исторических свечей для запуска по расписанию.
+///
+/// Предназначен для ежедневной выгрузки свечей за предыдущий торговый день
+/// по списку инструментов, например из cron задачи.
+#[derive(Clone)]
+pub struct ScheduledDataDownloader {
+    sdk: Arc<TInvestSdk>,
+    storage: Arc<dyn CandleStorage>,
+}
+
+impl ScheduledDataDownloader {
+    /// Создаёт загрузчик, сохраняющий свечи в указанное хранилище.
+    pub fn new(sdk: Arc<TInvestSdk>, storage: Arc<dyn CandleStorage>) -> Self {
+        Self { sdk, storage }
+    }
+
+    /// Загружает и сохраняет свечи за предыдущий торговый день.
+    ///
+    /// Предыдущий торговый день определяется отдельно для каждого инструмента
+    /// как дата последней завершённой дневной свечи до текущих суток (UTC).
+    /// Инструменты без торгов за последние две недели пропускаются.
+    ///
+    /// # Аргументы
+    /// * `figis` - FIGI инструментов
+    /// * `intervals` - Интервалы загружаемых свечей
+    ///
+    /// # Возвращает
+    /// Количество загруженных свечей (по всем интервалам) для каждого FIGI
+    pub async fn download_previous_trading_day(
+        &self,
+        figis: &[&str],
+        intervals: &[CandleInterval],
+    ) -> Result<HashMap<String, usize>, TInvestError> {
+        let today = Utc::now().date_naive();
+        let mut counts = HashMap::new();
+
+        for figi in figis {
+            let Some(day) = self.previous_trading_day(figi, today).await? else {
+                counts.insert(figi.to_string(), 0);
+                continue;
+            };
+
+            let mut count = 0;
+            for interval in intervals {
+                let candles = self
+                    .get_candles(figi, *interval, day_start(day), day_start(next_day(day)))
+                    .await?;
+                self.storage.store(figi, *interval, &candles)?;
+                count += candles.len();
+            }
+
+            counts.insert(figi.to_string(), count);
+        }
+
+        Ok(counts)
+    }
+
+    async fn previous_trading_day(
+        &self,
+        figi: &str,
+        today: NaiveDate,
+    ) -> Result<Option<NaiveDate>, TInvestError> {
+        let from = today
+            .checked_sub_days(Days::new(TRADING_DAY_LOOKBACK_DAYS))
+            .unwrap_or(NaiveDate::MIN);
+        let candles = self
+            .get_candles(figi, CandleInterval::Day, day_start(from), day_start(today))
+            .await?;
+
+        Ok(last_trading_day(&candles, today))
+    }
+
+    async fn get_candles(
+        &self,
+        figi: &str,
+        interval: CandleInterval,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<HistoricCandle>, TInvestError> {
+        HistoricalCandleFetcher::new(self.sdk.market_data(), figi, interval)
+            .fetch(from, to)
+            .await
+    }
+}
+
+/// Возвращает дату последней дневной свечи строго до `today`.
+fn last_trading_day(candles: &[HistoricCandle], today: NaiveDate) -> Option<NaiveDate> {
+    candles
+        .iter()
+        .filter_map(|candle| candle.time)
+        .filter_map(|time| timestamp_to_naive_date(time).ok())
+        .filter(|date| *date < today)
+        .max()
+}
+
+fn day_start(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn next_day(date: NaiveDate) -> NaiveDate {
+    date.succ_opt().unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::naive_date_to_timestamp;
+
+    fn candle(date: NaiveDate) -> HistoricCandle {
+        HistoricCandle {
+            time: Some(naive_date_to_timestamp(date)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn last_trading_day_skips_today_and_gaps() {
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+        let thursday = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+
+        assert_eq!(
+            Some(friday),
+            last_trading_day(&[candle(thursday), candle(friday), candle(monday)], monday)
+        );
+
+        assert_eq!(None, last_trading_day(&[candle(monday)], monday));
+        assert_eq!(None, last_trading_day(&[], monday));
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::api::OrderDirection;
+
+/// Допустимое отклонение суммы весов от единицы.
+const WEIGHT_SUM_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 6);
+
+/// Сделка для приведения портфеля к составу индекса.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebalanceTrade {
+    /// FIGI инструмента
+    pub figi: String,
+    /// Покупка или продажа
+    pub direction: OrderDirection,
+    /// Количество лотов
+    pub quantity: i64,
+}
+
+/// Взвешенная корзина инструментов для репликации индекса или фонда.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquityIndex {
+    components: Vec<(String, Decimal)>,
+}
+
+impl EquityIndex {
+    /// Создаёт корзину из пар `(figi, вес)`.
+    ///
+    /// Веса должны быть неотрицательными, а их сумма — равна `1`
+    /// с точностью до `0.000001`.
+    pub fn new(components: Vec<(String, Decimal)>) -> Result<Self, String> {
+        if let Some((figi, weight)) = components
+            .iter()
+            .find(|(_, weight)| *weight < Decimal::ZERO)
+        {
+            return Err(format!("Weight {} of {} is negative", weight, figi));
+        }
+
+        let sum: Decimal = components.iter().map(|(_, weight)| weight).sum();
+        if (sum - Decimal::ONE).abs() > WEIGHT_SUM_TOLERANCE {
+            return Err(format!("Index weights sum to {}, expected 1", sum));
+        }
+
+        Ok(Self { components })
+    }
+
+    /// Возвращает состав корзины.
+    pub fn components(&self) -> &[(String, Decimal)] {
+        &self.components
+    }
+
+    /// Рассчитывает значение индекса `sum(вес * цена) / divisor`.
+    ///
+    /// # Возвращает
+    /// `None`, если нет цены хотя бы одного инструмента или делитель равен нулю
+    pub fn index_value(
+        &self,
+        prices: &HashMap<String, Decimal>,
+        divisor: Decimal,
+    ) -> Option<Decimal> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let mut value = Decimal::ZERO;
+        for (figi, weight) in &self.components {
+            value += weight * prices.get(figi)?;
+        }
+
+        Some(value / divisor)
+    }
+
+    /// Рассчитывает сделки, приводящие портфель к составу индекса стоимостью `target_value`.
+    ///
+    /// Целевое количество лотов каждого инструмента округляется вниз, чтобы
+    /// стоимость портфеля не превысила `target_value`. Инструменты, которых нет
+    /// в индексе, продаются полностью. Инструменты индекса без цены или размера
+    /// лота пропускаются. Сделки возвращаются в порядке состава индекса, затем
+    /// продажи лишних инструментов по FIGI.
+    ///
+    /// # Аргументы
+    /// * `current_holdings` - Текущее количество лотов по FIGI
+    /// * `target_value` - Целевая стоимость портфеля
+    /// * `prices` - Цены одного инструмента по FIGI
+    /// * `lot_sizes` - Количество инструментов в лоте по FIGI
+    pub fn rebalance_trades(
+        &self,
+        current_holdings: &HashMap<String, i64>,
+        target_value: Decimal,
+        prices: &HashMap<String, Decimal>,
+        lot_sizes: &HashMap<String, i32>,
+    ) -> Vec<RebalanceTrade> {
+        let mut trades = Vec::new();
+        for (figi, weight) in &self.components {
+            let (Some(price), Some(lot_size)) = (prices.get(figi), lot_sizes.get(figi)) else {
+                continue;
+            };
+            let lot_value = price * Decimal::from(*lot_size);
+            if lot_value <= Decimal::ZERO {
+                continue;
+            }
+
+            let target = (target_value * weight / lot_value)
+                .floor()
+                .try_into()
+                .unwrap_or(0);
+            let current = current_holdings.get(figi).copied().unwrap_or(0);
+            trades.extend(trade(figi, target - current));
+        }
+
+        let mut extra: Vec<(&String, i64)> = current_holdings
+            .iter()
+            .filter(|(figi, _)| {
+                !self
+                    .components
+                    .iter()
+                    .any(|(component, _)| component == *figi)
+            })
+            .map(|(figi, quantity)| (figi, *quantity))
+            .collect();
+        extra.sort();
+        trades.extend(
+            extra
+                .into_iter()
+                .filter_map(|(figi, quantity)| trade(figi, -quantity)),
+        );
+
+        trades
+    }
+}
+
+/// Создаёт сделку на изменение позиции на `delta` лотов.
+fn trade(figi: &str, delta: i64) -> Option<RebalanceTrade> {
+    let direction = match delta {
+        0 => return None,
+        delta if delta > 0 => OrderDirection::Buy,
+        _ => OrderDirection::Sell,
+    };
+
+    Some(RebalanceTrade {
+        figi: figi.to_string(),
+        direction,
+        quantity: delta.abs(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn map<T: Copy>(entries: &[(&str, T)]) -> HashMap<String, T> {
+        entries
+            .iter()
+            .map(|(figi, value)| (figi.to_string(), *value))
+            .collect()
+    }
+
+    fn index() -> EquityIndex {
+        EquityIndex::new(vec![
+            ("A".to_string(), dec!(0.5)),
+            ("B".to_string(), dec!(0.3)),
+            ("C".to_string(), dec!(0.2)),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn weights_and_value() {
+        assert!(EquityIndex::new(vec![("A".to_string(), dec!(0.9))]).is_err());
+        assert!(
+            EquityIndex::new(vec![
+                ("A".to_string(), dec!(1.5)),
+                ("B".to_string(), dec!(-0.5))
+            ])
+            .is_err()
+        );
+
+        let prices = map(&[("A", dec!(100)), ("B", dec!(200)), ("C", dec!(50))]);
+        // 0.5 * 100 + 0.3 * 200 + 0.2 * 50 = 120
+        assert_eq!(Some(dec!(12)), index().index_value(&prices, dec!(10)));
+        assert_eq!(None, index().index_value(&prices, Decimal::ZERO));
+        assert_eq!(
+            None,
+            index().index_value(&map(&[("A", dec!(100))]), dec!(1))
+        );
+    }
+
+    #[test]
+    fn rebalance() {
+        let prices = map(&[("A", dec!(100)), ("B", dec!(200)), ("C", dec!(50))]);
+        let lot_sizes = map(&[("A", 10), ("B", 1), ("C", 10)]);
+        let holdings = map(&[("A", 60), ("C", 10), ("X", 3)]);
+
+        // Цели: A 50 000 / 1 000 = 50, B 30 000 / 200 = 150, C 20 000 / 500 = 40.
+        let trades = index().rebalance_trades(&holdings, dec!(100000), &prices, &lot_sizes);
+
+        let expected = [
+            ("A", OrderDirection::Sell, 10),
+            ("B", OrderDirection::Buy, 150),
+            ("C", OrderDirection::Buy, 30),
+            ("X", OrderDirection::Sell, 3),
+        ];
+        assert_eq!(
+            expected
+                .iter()
+                .map(|(figi, direction, quantity)| RebalanceTrade {
+                    figi: figi.to_string(),
+                    direction: *direction,
+                    quantity: *quantity,
+                })
+                .collect::<Vec<_>>(),
+            trades
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::api::{MoneyValue, Operation, OperationType};
+use crate::timestamp_to_datetime_utc;
+
+/// Суммарное исполнение заявки по нескольким сделкам.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateFill {
+    /// Общее количество исполненных инструментов
+    pub total_quantity: i64,
+    /// Средневзвешенная по количеству цена исполнения
+    pub vwap_price: Decimal,
+    /// Сумма комиссий брокера
+    pub total_commission: Decimal,
+    /// Время первой сделки
+    pub first_fill_time: DateTime<Utc>,
+    /// Время последней сделки
+    pub last_fill_time: DateTime<Utc>,
+}
+
+/// Объединяет частичные исполнения заявки в одно исполнение.
+///
+/// Исполнениями считаются сделки операций с идентификатором `operation_id`.
+/// Операция без сделок считается одним исполнением на `quantity - quantity_rest`
+/// по цене операции. Комиссия суммируется по операциям удержания комиссии брокера,
+/// у которых `parent_operation_id` равен `operation_id`.
+///
+/// Записи выгрузки брокерского отчёта (фича `csv`) преобразуются через
+/// `Operation::try_from(&record)`, идентификатор операции задаёт вызывающий код.
+/// Комиссия записи учитывается, если добавить операцию `BrokerFee`
+/// с `parent_operation_id`, равным этому идентификатору.
+///
+/// # Возвращает
+/// `None`, если по заявке нет исполнений с ненулевым количеством и известным временем
+pub fn aggregate_partial_fills(
+    operations: &[Operation],
+    operation_id: &str,
+) -> Option<AggregateFill> {
+    let fills: Vec<(DateTime<Utc>, i64, Decimal)> = operations
+        .iter()
+        .filter(|operation| operation.id == operation_id)
+        .flat_map(operation_fills)
+        .filter(|(_, quantity, _)| *quantity != 0)
+        .collect();
+
+    let first_fill_time = fills.iter().map(|(time, _, _)| *time).min()?;
+    let last_fill_time = fills.iter().map(|(time, _, _)| *time).max()?;
+    let total_quantity: i64 = fills.iter().map(|(_, quantity, _)| quantity).sum();
+    if total_quantity == 0 {
+        return None;
+    }
+
+    let total_amount: Decimal = fills
+        .iter()
+        .map(|(_, quantity, price)| Decimal::from(*quantity) * price)
+        .sum();
+
+    let total_commission = operations
+        .iter()
+        .filter(|operation| {
+            operation.parent_operation_id == operation_id
+                && operation.operation_type() == OperationType::BrokerFee
+        })
+        .filter_map(|operation| operation.payment.clone())
+        .map(|payment| Decimal::from(payment).abs())
+        .sum();
+
+    Some(AggregateFill {
+        total_quantity,
+        vwap_price: total_amount / Decimal::from(total_quantity),
+        total_commission,
+        first_fill_time,
+        last_fill_time,
+    })
+}
+
+/// Возвращает время, количество и цену каждого исполнения операции.
+fn operation_fills(operation: &Operation) -> Vec<(DateTime<Utc>, i64, Decimal)> {
+    if operation.trades.is_empty() {
+        return fill(
+            operation.date,
+            operation.quantity - operation.quantity_rest,
+            operation.price.clone(),
+        )
+        .into_iter()
+        .collect();
+    }
+
+    operation
+        .trades
+        .iter()
+        .filter_map(|trade| fill(trade.date_time, trade.quantity, trade.price.clone()))
+        .collect()
+}
+
+fn fill(
+    time: Option<prost_types::Timestamp>,
+    quantity: i64,
+    price: Option<MoneyValue>,
+) -> Option<(DateTime<Utc>, i64, Decimal)> {
+    let time = timestamp_to_datetime_utc(time?).ok()?;
+    Some((time, quantity, Decimal::from(price?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{OperationTrade, Quotation};
+    use crate::datetime_utc_to_timestamp;
+
+    fn money(value: Decimal) -> MoneyValue {
+        let quotation = Quotation::try_from(value).unwrap();
+        MoneyValue {
+            currency: "rub".to_string(),
+            units: quotation.units,
+            nano: quotation.nano,
+        }
+    }
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 12, 10, minute, 0).unwrap()
+    }
+
+    fn trade(minute: u32, quantity: i64, price: Decimal) -> OperationTrade {
+        OperationTrade {
+            date_time: Some(datetime_utc_to_timestamp(at(minute))),
+            quantity,
+            price: Some(money(price)),
+            ..Default::default()
+        }
+    }
+
+    fn fee(parent: &str, amount: Decimal) -> Operation {
+        Operation {
+            parent_operation_id: parent.to_string(),
+            operation_type: OperationType::BrokerFee.into(),
+            payment: Some(money(amount)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aggregates_trades() {
+        let operations = vec![
+            Operation {
+                id: "order".to_string(),
+                trades: vec![
+                    trade(5, 2, dec!(101)),
+                    trade(1, 1, dec!(100)),
+                    trade(9, 1, dec!(104)),
+                ],
+                ..Default::default()
+            },
+            fee("order", dec!(-1.5)),
+            fee("order", dec!(-0.5)),
+            fee("other", dec!(-10)),
+        ];
+
+        assert_eq!(
+            Some(AggregateFill {
+                total_quantity: 4,
+                vwap_price: dec!(101.5),
+                total_commission: dec!(2),
+                first_fill_time: at(1),
+                last_fill_time: at(9),
+            }),
+            aggregate_partial_fills(&operations, "order")
+        );
+        assert_eq!(None, aggregate_partial_fills(&operations, "other"));
+    }
+
+    #[test]
+    fn operation_without_trades() {
+        let operations = vec![Operation {
+            id: "order".to_string(),
+            quantity: 10,
+            quantity_rest: 4,
+            price: Some(money(dec!(50))),
+            date: Some(datetime_utc_to_timestamp(at(3))),
+            ..Default::default()
+        }];
+
+        let fill = aggregate_partial_fills(&operations, "order").unwrap();
+        assert_eq!(6, fill.total_quantity);
+        assert_eq!(dec!(50), fill.vwap_price);
+        assert_eq!(Decimal::ZERO, fill.total_commission);
+        assert_eq!(at(3), fill.last_fill_time);
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+/// Количество дней в году для расчёта долей года (ACT/365).
+const DAYS_IN_YEAR: f64 = 365.0;
+
+/// Максимальное число итераций поиска доходности.
+const MAX_ITERATIONS: usize = 200;
+
+/// Рассчитывает эффективную доходность к погашению.
+///
+/// Доходность `y` находится из уравнения `dirty_price = Σ CF / (1 + y)^t`,
+/// где `t` — доля года от даты расчётов до выплаты по базису ACT/365.
+/// Выплаты до даты расчётов или в эту дату не учитываются.
+///
+/// # Аргументы
+/// * `dirty_price` - Полная цена облигации (с накопленным купонным доходом)
+/// * `cash_flows` - Будущие выплаты: купоны и погашение номинала
+/// * `settlement_date` - Дата расчётов
+///
+/// # Возвращает
+/// Доходность в виде доли, например `0.12` для 12% годовых, или `None`,
+/// если цена неположительна, будущих выплат нет или решение не найдено.
+pub fn yield_to_maturity(
+    dirty_price: Decimal,
+    cash_flows: &[(NaiveDate, Decimal)],
+    settlement_date: NaiveDate,
+) -> Option<Decimal> {
+    let price = dirty_price.to_f64().filter(|price| *price > 0.0)?;
+    let flows: Vec<(f64, f64)> = cash_flows
+        .iter()
+        .filter(|(date, _)| *date > settlement_date)
+        .map(|(date, amount)| {
+            let years = (*date - settlement_date).num_days() as f64 / DAYS_IN_YEAR;
+            Some((years, amount.to_f64()?))
+        })
+        .collect::<Option<_>>()?;
+    if flows.is_empty() {
+        return None;
+    }
+
+    let present_value = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .map(|(years, amount)| amount / (1.0 + rate).powf(*years))
+            .sum()
+    };
+
+    // Приведённая стоимость убывает с ростом доходности, поэтому корень ищется делением отрезка.
+    let (mut low, mut high) = (-0.99, 10.0);
+    if present_value(low) < price || present_value(high) > price {
+        return None;
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let mid = (low + high) / 2.0;
+        if present_value(mid) > price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Decimal::from_f64((low + high) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn par_bond_yields_coupon_rate() {
+        // Годовой купон 10% с номиналом 1000, куплен по номиналу.
+        let cash_flows = [
+            (date(2025, 1, 1), dec!(100)),
+            (date(2026, 1, 1), dec!(100)),
+            (date(2027, 1, 1), dec!(1100)),
+        ];
+
+        let ytm = yield_to_maturity(dec!(1000), &cash_flows, date(2024, 1, 2)).unwrap();
+        assert_eq!(dec!(0.1), ytm.round_dp(4));
+    }
+
+    #[test]
+    fn zero_coupon_and_errors() {
+        let cash_flows = [(date(2024, 1, 1), dec!(1000))];
+
+        // 1000 / 1.25 = 800
+        let ytm = yield_to_maturity(dec!(800), &cash_flows, date(2023, 1, 1)).unwrap();
+        assert_eq!(dec!(0.25), ytm.round_dp(4));
+
+        assert_eq!(
+            None,
+            yield_to_maturity(dec!(800), &cash_flows, date(2024, 1, 1))
+        );
+        assert_eq!(
+            None,
+            yield_to_maturity(Decimal::ZERO, &cash_flows, date(2023, 1, 1))
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    GetAssetFundamentalsRequest, InstrumentIdType, InstrumentRequest, Share,
+    get_asset_fundamentals_response::StatisticResponse,
+    instruments_service_client::InstrumentsServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor};
+
+/// Возвращает количество акций в обращении.
+///
+/// Значение берётся из поля `issue_size` акции.
+///
+/// # Аргументы
+/// * `client` - Клиент сервиса Instruments
+/// * `figi` - FIGI акции
+///
+/// # Возвращает
+/// `None`, если инструмент не является акцией или размер выпуска неизвестен
+pub async fn shares_outstanding(
+    client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    figi: &str,
+) -> Result<Option<i64>, TInvestError> {
+    let share = match get_share(client, figi).await? {
+        Some(share) => share,
+        None => return Ok(None),
+    };
+
+    Ok(issue_size(&share))
+}
+
+/// Рассчитывает рыночную капитализацию акции.
+///
+/// Капитализация равна количеству акций в обращении, умноженному на цену.
+/// Цены в T-Invest API указываются за 1 инструмент, поэтому лотность в расчёте
+/// не участвует.
+///
+/// # Аргументы
+/// * `client` - Клиент сервиса Instruments
+/// * `figi` - FIGI акции
+/// * `current_price` - Текущая цена одной акции
+///
+/// # Возвращает
+/// `None`, если количество акций в обращении неизвестно
+pub async fn market_cap(
+    client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    figi: &str,
+    current_price: Decimal,
+) -> Result<Option<Decimal>, TInvestError> {
+    let shares = shares_outstanding(client, figi).await?;
+
+    Ok(shares.map(|shares| Decimal::from(shares) * current_price))
+}
+
+/// Максимальное количество активов в одном запросе `GetAssetFundamentals`.
+const MAX_FUNDAMENTALS_ASSETS: usize = 100;
+
+/// Фильтр акций по фундаментальным показателям.
+///
+/// Показатели берутся из `GetAssetFundamentals` и сопоставляются с акциями
+/// по `asset_uid`. Для малоликвидных бумаг часть показателей может отсутствовать
+/// (API возвращает `0`); такие акции не проходят фильтр по соответствующему
+/// критерию. Акции с неположительным P/E (убыточные) не проходят фильтр
+/// по максимальному P/E.
+#[derive(Debug, Clone, Default)]
+pub struct FundamentalScreener {
+    min_market_cap: Option<Decimal>,
+    max_pe_ratio: Option<Decimal>,
+    min_dividend_yield: Option<Decimal>,
+}
+
+impl FundamentalScreener {
+    /// Создаёт фильтр без критериев.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Устанавливает минимальную рыночную капитализацию.
+    pub fn min_market_cap(&mut self, cap: Decimal) -> &mut Self {
+        self.min_market_cap = Some(cap);
+        self
+    }
+
+    /// Устанавливает максимальный P/E.
+    pub fn max_pe_ratio(&mut self, pe: Decimal) -> &mut Self {
+        self.max_pe_ratio = Some(pe);
+        self
+    }
+
+    /// Устанавливает минимальную дивидендную доходность за 12 месяцев в процентах.
+    pub fn min_dividend_yield(&mut self, yield_pct: Decimal) -> &mut Self {
+        self.min_dividend_yield = Some(yield_pct);
+        self
+    }
+
+    /// Загружает фундаментальные показатели для акций.
+    pub async fn fetch_fundamentals(
+        client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+        shares: &[Share],
+    ) -> Result<Vec<StatisticResponse>, TInvestError> {
+        let mut fundamentals = Vec::new();
+
+        for chunk in shares.chunks(MAX_FUNDAMENTALS_ASSETS) {
+            let response = client
+                .get_asset_fundamentals(GetAssetFundamentalsRequest {
+                    assets: chunk.iter().map(|share| share.asset_uid.clone()).collect(),
+                })
+                .await?
+                .into_inner();
+            fundamentals.extend(response.fundamentals);
+        }
+
+        Ok(fundamentals)
+    }
+
+    /// Возвращает акции, удовлетворяющие всем заданным критериям.
+    ///
+    /// # Аргументы
+    /// * `instruments` - Акции для фильтрации
+    /// * `fundamentals` - Фундаментальные показатели, например из [`Self::fetch_fundamentals`]
+    pub fn apply<'a>(
+        &self,
+        instruments: &'a [Share],
+        fundamentals: &[StatisticResponse],
+    ) -> Vec<&'a Share> {
+        let fundamentals: HashMap<&str, &StatisticResponse> = fundamentals
+            .iter()
+            .map(|statistic| (statistic.asset_uid.as_str(), statistic))
+            .collect();
+
+        instruments
+            .iter()
+            .filter(|share| {
+                fundamentals
+                    .get(share.asset_uid.as_str())
+                    .is_some_and(|statistic| self.matches(statistic))
+            })
+            .collect()
+    }
+
+    fn matches(&self, statistic: &StatisticResponse) -> bool {
+        let market_cap = positive(statistic.market_capitalization);
+        let pe_ratio = positive(statistic.pe_ratio_ttm);
+        let dividend_yield = positive(statistic.dividend_yield_daily_ttm);
+
+        self.min_market_cap
+            .is_none_or(|min| market_cap.is_some_and(|value| value >= min))
+            && self
+                .max_pe_ratio
+                .is_none_or(|max| pe_ratio.is_some_and(|value| value <= max))
+            && self
+                .min_dividend_yield
+                .is_none_or(|min| dividend_yield.is_some_and(|value| value >= min))
+    }
+}
+
+/// Рассчитывает среднюю доходность акций каждого сектора с равными весами.
+///
+/// Акции группируются по полю `sector`, доходности берутся из `returns` по FIGI.
+/// Акции без доходности не учитываются.
+///
+/// # Возвращает
+/// Среднюю доходность по названию сектора
+pub fn sector_performance_equal_weighted(
+    shares: &[Share],
+    returns: &HashMap<String, Decimal>,
+) -> HashMap<String, Decimal> {
+    sector_performance(shares, returns, |_| Some(Decimal::ONE))
+}
+
+/// Рассчитывает среднюю доходность акций каждого сектора, взвешенную по капитализации.
+///
+/// Акции без доходности или с неположительной капитализацией в `market_caps`
+/// не учитываются.
+///
+/// # Аргументы
+/// * `shares` - Акции для расчёта
+/// * `returns` - Доходности по FIGI
+/// * `market_caps` - Капитализации по FIGI, например из [`market_cap`]
+pub fn sector_performance_cap_weighted(
+    shares: &[Share],
+    returns: &HashMap<String, Decimal>,
+    market_caps: &HashMap<String, Decimal>,
+) -> HashMap<String, Decimal> {
+    sector_performance(shares, returns, |share| {
+        market_caps
+            .get(&share.figi)
+            .copied()
+            .filter(|cap| cap.is_sign_positive() && !cap.is_zero())
+    })
+}
+
+fn sector_performance(
+    shares: &[Share],
+    returns: &HashMap<String, Decimal>,
+    weight: impl Fn(&Share) -> Option<Decimal>,
+) -> HashMap<String, Decimal> {
+    let mut sectors: HashMap<String, (Decimal, Decimal)> = HashMap::new();
+    for share in shares {
+        let (Some(share_return), Some(weight)) = (returns.get(&share.figi), weight(share)) else {
+            continue;
+        };
+
+        let (weighted_sum, total_weight) = sectors.entry(share.sector.clone()).or_default();
+        *weighted_sum += share_return * weight;
+        *total_weight += weight;
+    }
+
+    sectors
+        .into_iter()
+        .map(|(sector, (weighted_sum, total_weight))| (sector, weighted_sum / total_weight))
+        .collect()
+}
+
+/// Преобразует показатель в Decimal, считая неположительные значения отсутствующими.
+fn positive(value: f64) -> Option<Decimal> {
+    Decimal::from_f64(value).filter(|value| value.is_sign_positive() && !value.is_zero())
+}
+
+/// Загружает акцию по FIGI, возвращая `None`, если акция не найдена.
+async fn get_share(
+    client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    figi: &str,
+) -> Result<Option<Share>, TInvestError> {
+    let response = client
+        .share_by(InstrumentRequest {
+            id_type: InstrumentIdType::Figi as i32,
+            class_code: None,
+            id: figi.to_string(),
+        })
+        .await;
+
+    match response {
+        Ok(response) => Ok(response.into_inner().instrument),
+        Err(status) if status.code() == tonic::Code::NotFound => Ok(None),
+        Err(status) => Err(status.into()),
+    }
+}
+
+fn issue_size(share: &Share) -> Option<i64> {
+    (share.issue_size > 0).then_some(share.issue_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn share(asset_uid: &str) -> Share {
+        Share {
+            asset_uid: asset_uid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn statistic(
+        asset_uid: &str,
+        market_cap: f64,
+        pe: f64,
+        dividend_yield: f64,
+    ) -> StatisticResponse {
+        StatisticResponse {
+            asset_uid: asset_uid.to_string(),
+            market_capitalization: market_cap,
+            pe_ratio_ttm: pe,
+            dividend_yield_daily_ttm: dividend_yield,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fundamental_screener() {
+        let shares = vec![share("a"), share("b"), share("c"), share("d")];
+        let fundamentals = vec![
+            statistic("a", 1_000_000_000.0, 5.0, 10.0),
+            statistic("b", 1_000_000.0, 4.0, 12.0),
+            statistic("c", 5_000_000_000.0, -3.0, 8.0),
+        ];
+
+        let all = FundamentalScreener::new().apply(&shares, &fundamentals);
+        assert_eq!(3, all.len());
+
+        let screened = FundamentalScreener::new()
+            .min_market_cap(dec!(100_000_000))
+            .max_pe_ratio(dec!(10))
+            .apply(&shares, &fundamentals);
+        assert_eq!(
+            vec!["a"],
+            screened
+                .iter()
+                .map(|s| s.asset_uid.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let screened = FundamentalScreener::new()
+            .min_dividend_yield(dec!(9))
+            .apply(&shares, &fundamentals);
+        assert_eq!(
+            vec!["a", "b"],
+            screened
+                .iter()
+                .map(|s| s.asset_uid.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn share_issue_size() {
+        assert_eq!(
+            Some(21_586_948_000),
+            issue_size(&Share {
+                issue_size: 21_586_948_000,
+                ..Default::default()
+            })
+        );
+
+        assert_eq!(None, issue_size(&Share::default()));
+    }
+
+    #[test]
+    fn sector_returns() {
+        let share = |figi: &str, sector: &str| Share {
+            figi: figi.to_string(),
+            sector: sector.to_string(),
+            ..Default::default()
+        };
+        let shares = vec![
+            share("a", "it"),
+            share("b", "it"),
+            share("c", "energy"),
+            share("d", "energy"),
+        ];
+        let returns = HashMap::from([
+            ("a".to_string(), dec!(0.1)),
+            ("b".to_string(), dec!(-0.02)),
+            ("c".to_string(), dec!(0.05)),
+        ]);
+        let caps = HashMap::from([
+            ("a".to_string(), dec!(100)),
+            ("b".to_string(), dec!(300)),
+            ("c".to_string(), dec!(50)),
+            ("d".to_string(), dec!(1000)),
+        ]);
+
+        assert_eq!(
+            HashMap::from([
+                ("it".to_string(), dec!(0.04)),
+                ("energy".to_string(), dec!(0.05)),
+            ]),
+            sector_performance_equal_weighted(&shares, &returns)
+        );
+
+        // (0.1 * 100 - 0.02 * 300) / 400 = 0.01
+        assert_eq!(
+            HashMap::from([
+                ("it".to_string(), dec!(0.01)),
+                ("energy".to_string(), dec!(0.05)),
+            ]),
+            sector_performance_cap_weighted(&shares, &returns, &caps)
+        );
+    }
+}
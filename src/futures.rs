@@ -0,0 +1,219 @@
+use rust_decimal::Decimal;
+
+use crate::api::{
+    Future, GetFuturesMarginRequest, GetFuturesMarginResponse, InstrumentIdType, InstrumentRequest,
+};
+use crate::{TInvestError, TInvestSdk};
+
+/// Гарантийное обеспечение, необходимое для открытия позиции по фьючерсу.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuturesMarginRequirement {
+    /// Начальная маржа на всю позицию
+    pub initial_margin: Decimal,
+    /// Оценка минимальной маржи на всю позицию.
+    ///
+    /// `GetFuturesMargin` не возвращает минимальную маржу, поэтому значение
+    /// рассчитывается из начальной, см. [`get_futures_margin_requirement`].
+    pub maintenance_margin: Decimal,
+    /// Валюта маржи
+    pub currency: String,
+    /// Количество контрактов в лоте
+    pub lot_size: i32,
+}
+
+/// Проверка гарантийного обеспечения перед открытием позиции по фьючерсу.
+///
+/// ```no_run
+/// # async fn example(sdk: &t_invest_sdk::TInvestSdk) -> Result<(), t_invest_sdk::TInvestError> {
+/// use rust_decimal::Decimal;
+/// use t_invest_sdk::FuturesMarginHelper;
+///
+/// let helper = FuturesMarginHelper::fetch(sdk, "FUTSI0624000", 2).await?;
+/// if helper.can_afford(Decimal::from(50_000)) {
+///     // выставить заявку
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuturesMarginHelper {
+    requirement: FuturesMarginRequirement,
+}
+
+impl FuturesMarginHelper {
+    /// Создаёт проверку для уже полученных требований к марже.
+    pub fn new(requirement: FuturesMarginRequirement) -> Self {
+        Self { requirement }
+    }
+
+    /// Запрашивает требования к марже через [`get_futures_margin_requirement`].
+    pub async fn fetch(sdk: &TInvestSdk, figi: &str, quantity: i64) -> Result<Self, TInvestError> {
+        get_futures_margin_requirement(sdk, figi, quantity)
+            .await
+            .map(Self::new)
+    }
+
+    /// Возвращает требования к марже.
+    pub fn requirement(&self) -> &FuturesMarginRequirement {
+        &self.requirement
+    }
+
+    /// Проверяет, достаточно ли свободных средств для открытия позиции.
+    ///
+    /// Средства сравниваются с начальной маржей, валюта должна совпадать
+    /// с [`FuturesMarginRequirement::currency`].
+    pub fn can_afford(&self, available_cash: Decimal) -> bool {
+        available_cash >= self.requirement.initial_margin
+    }
+}
+
+/// Запрашивает гарантийное обеспечение для позиции по фьючерсу.
+///
+/// Начальная маржа берётся из `GetFuturesMargin` для покупки или продажи и
+/// умножается на количество лотов.
+///
+/// Минимальная маржа API не возвращается и является оценкой: начальная маржа
+/// умножается на отношение ставок риска `dlong_min / dlong` (`dshort_min / dshort`
+/// для продажи) фьючерса. Если ставки неизвестны, оценка равна начальной марже.
+/// Фактическое значение определяет брокер.
+///
+/// # Аргументы
+/// * `sdk` - Экземпляр SDK
+/// * `figi` - FIGI фьючерса
+/// * `quantity` - Количество лотов, положительное для покупки и отрицательное для продажи
+pub async fn get_futures_margin_requirement(
+    sdk: &TInvestSdk,
+    figi: &str,
+    quantity: i64,
+) -> Result<FuturesMarginRequirement, TInvestError> {
+    let mut instruments = sdk.instruments();
+
+    let margin = instruments
+        .get_futures_margin(GetFuturesMarginRequest {
+            instrument_id: figi.to_string(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner();
+
+    let future = instruments
+        .future_by(InstrumentRequest {
+            id_type: InstrumentIdType::Figi.into(),
+            id: figi.to_string(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .instrument
+        .ok_or_else(|| TInvestError::NotFound(format!("futures with FIGI {}", figi)))?;
+
+    margin_requirement(&margin, &future, quantity).map_err(TInvestError::Calculation)
+}
+
+fn margin_requirement(
+    margin: &GetFuturesMarginResponse,
+    future: &Future,
+    quantity: i64,
+) -> Result<FuturesMarginRequirement, String> {
+    let (per_lot, rate, min_rate) = if quantity >= 0 {
+        (
+            &margin.initial_margin_on_buy,
+            future.dlong,
+            future.dlong_min,
+        )
+    } else {
+        (
+            &margin.initial_margin_on_sell,
+            future.dshort,
+            future.dshort_min,
+        )
+    };
+    let per_lot = per_lot
+        .clone()
+        .ok_or_else(|| format!("No initial margin for {}", future.figi))?;
+
+    let currency = if per_lot.currency.is_empty() {
+        future.currency.to_lowercase()
+    } else {
+        per_lot.currency.to_lowercase()
+    };
+    let initial_margin = Decimal::from(per_lot) * Decimal::from(quantity.abs());
+    let maintenance_margin = match (rate.map(Decimal::from), min_rate.map(Decimal::from)) {
+        (Some(rate), Some(min_rate)) if !rate.is_zero() => initial_margin * min_rate / rate,
+        _ => initial_margin,
+    };
+
+    Ok(FuturesMarginRequirement {
+        initial_margin,
+        maintenance_margin,
+        currency,
+        lot_size: future.lot,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, Quotation};
+
+    fn money(value: Decimal, currency: &str) -> MoneyValue {
+        let quotation = Quotation::try_from(value).unwrap();
+        MoneyValue {
+            currency: currency.to_string(),
+            units: quotation.units,
+            nano: quotation.nano,
+        }
+    }
+
+    #[test]
+    fn margin_by_quantity() {
+        let margin = GetFuturesMarginResponse {
+            initial_margin_on_buy: Some(money(dec!(15230.5), "RUB")),
+            initial_margin_on_sell: Some(money(dec!(14800), "rub")),
+            ..Default::default()
+        };
+        let future = Future {
+            figi: "FUTSI".to_string(),
+            lot: 1,
+            currency: "usd".to_string(),
+            dlong: Some(Quotation::try_from(dec!(0.2)).unwrap()),
+            dlong_min: Some(Quotation::try_from(dec!(0.1)).unwrap()),
+            ..Default::default()
+        };
+
+        let long = margin_requirement(&margin, &future, 3).unwrap();
+        assert_eq!(
+            FuturesMarginRequirement {
+                initial_margin: dec!(45691.5),
+                maintenance_margin: dec!(22845.75),
+                currency: "rub".to_string(),
+                lot_size: 1,
+            },
+            long
+        );
+        let helper = FuturesMarginHelper::new(long);
+        assert!(helper.can_afford(dec!(45691.5)));
+        assert!(!helper.can_afford(dec!(45000)));
+
+        // Для продажи нет ставок риска, минимальная маржа равна начальной.
+        let short = margin_requirement(&margin, &future, -2).unwrap();
+        assert_eq!(dec!(29600), short.initial_margin);
+        assert_eq!(dec!(29600), short.maintenance_margin);
+
+        let empty = GetFuturesMarginResponse {
+            initial_margin_on_buy: Some(MoneyValue {
+                currency: String::new(),
+                units: 100,
+                nano: 0,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            "usd",
+            margin_requirement(&empty, &future, 1).unwrap().currency
+        );
+        assert!(margin_requirement(&empty, &future, -1).is_err());
+    }
+}
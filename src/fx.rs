@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::api::{
+    Currency, GetLastPricesRequest, InstrumentIdType, InstrumentRequest, InstrumentsRequest,
+    LastPrice, MoneyValue, Quotation,
+};
+use crate::{CurrencyPair, TInvestError, TInvestSdk};
+
+/// Базовая валюта, через которую по умолчанию выполняется пересчёт.
+const DEFAULT_BASE_CURRENCY: &str = "rub";
+
+/// Конвертер денежных сумм между валютами по заданным курсам.
+///
+/// Курс `(from, to) -> rate` означает, что одна единица валюты `from` стоит
+/// `rate` единиц валюты `to`. Обратные курсы вычисляются автоматически.
+/// Если прямого курса нет, пересчёт выполняется через базовую валюту
+/// (по умолчанию `rub`). Коды валют не зависят от регистра.
+#[derive(Debug, Clone)]
+pub struct FXConverter {
+    exchange_rates: HashMap<(String, String), Decimal>,
+    base_currency: String,
+}
+
+impl FXConverter {
+    /// Создаёт конвертер с указанными курсами и базовой валютой `rub`.
+    pub fn new(exchange_rates: HashMap<(String, String), Decimal>) -> Self {
+        Self {
+            exchange_rates: exchange_rates
+                .into_iter()
+                .map(|((from, to), rate)| ((from.to_lowercase(), to.to_lowercase()), rate))
+                .collect(),
+            base_currency: DEFAULT_BASE_CURRENCY.to_string(),
+        }
+    }
+
+    /// Устанавливает базовую валюту для пересчёта через третью валюту.
+    pub fn with_base_currency(mut self, currency: &str) -> Self {
+        self.base_currency = currency.to_lowercase();
+        self
+    }
+
+    /// Загружает курсы валют к рублю по последним ценам валютных инструментов.
+    ///
+    /// Для каждого FIGI запрашивается валюта инструмента, курс рассчитывается
+    /// как последняя цена, делённая на номинал.
+    ///
+    /// # Аргументы
+    /// * `sdk` - Экземпляр SDK
+    /// * `currency_figis` - FIGI валютных инструментов, например `BBG0013HGFT4` для USD/RUB
+    pub async fn load_from_last_prices(
+        sdk: &TInvestSdk,
+        currency_figis: &[&str],
+    ) -> Result<Self, TInvestError> {
+        let last_prices = sdk
+            .market_data()
+            .get_last_prices(GetLastPricesRequest {
+                instrument_id: currency_figis.iter().map(|figi| figi.to_string()).collect(),
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .last_prices;
+
+        let mut instruments = sdk.instruments();
+        let mut exchange_rates = HashMap::new();
+        for last_price in last_prices {
+            let Some(price) = last_price.price else {
+                continue;
+            };
+
+            let currency = instruments
+                .currency_by(InstrumentRequest {
+                    id_type: InstrumentIdType::Figi.into(),
+                    id: last_price.figi.clone(),
+                    ..Default::default()
+                })
+                .await?
+                .into_inner()
+                .instrument
+                .ok_or_else(|| {
+                    TInvestError::NotFound(format!("currency with FIGI {}", last_price.figi))
+                })?;
+
+            let nominal = currency
+                .nominal
+                .map(Decimal::from)
+                .filter(|nominal| !nominal.is_zero())
+                .unwrap_or(Decimal::ONE);
+            exchange_rates.insert(
+                (
+                    currency.iso_currency_name,
+                    DEFAULT_BASE_CURRENCY.to_string(),
+                ),
+                Decimal::from(price) / nominal,
+            );
+        }
+
+        Ok(Self::new(exchange_rates))
+    }
+
+    /// Пересчитывает сумму в указанную валюту.
+    ///
+    /// # Ошибки
+    /// Возвращает ошибку, если курс не найден ни напрямую, ни через базовую валюту,
+    /// или результат не удалось представить в виде `MoneyValue`.
+    pub fn convert(
+        &self,
+        amount: &MoneyValue,
+        target_currency: &str,
+    ) -> Result<MoneyValue, String> {
+        let from = amount.currency.to_lowercase();
+        let to = target_currency.to_lowercase();
+
+        let rate = self
+            .rate(&from, &to)
+            .or_else(|| {
+                Some(self.rate(&from, &self.base_currency)? * self.rate(&self.base_currency, &to)?)
+            })
+            .ok_or_else(|| format!("Exchange rate from {} to {} not found", from, to))?;
+
+        let value = (Decimal::from(amount.clone()) * rate).round_dp(9);
+        let quotation = Quotation::try_from(value)?;
+
+        Ok(MoneyValue {
+            currency: to,
+            units: quotation.units,
+            nano: quotation.nano,
+        })
+    }
+
+    fn rate(&self, from: &str, to: &str) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+
+        let key = |from: &str, to: &str| (from.to_string(), to.to_string());
+        self.exchange_rates
+            .get(&key(from, to))
+            .copied()
+            .or_else(|| {
+                self.exchange_rates
+                    .get(&key(to, from))
+                    .filter(|rate| !rate.is_zero())
+                    .map(|rate| Decimal::ONE / rate)
+            })
+    }
+}
+
+/// Загружает курсы валют относительно базовой валюты.
+///
+/// Курс равен стоимости одной единицы валюты в базовой валюте, например для
+/// базовой валюты `rub` результат может содержать `usd -> 90`. Курсы
+/// рассчитываются по последним ценам валютных инструментов: для пары с котируемой
+/// базовой валютой (прямая котировка, `USDRUB` при базовой `rub`) курс равен цене,
+/// делённой на номинал, а для пары с базовой валютой в качестве базовой валюты пары
+/// (обратная котировка, `USDRUB` при базовой `usd`) — обратной величине.
+/// Пары без базовой валюты пропускаются. Если по валюте есть несколько
+/// инструментов, используется самая свежая цена.
+///
+/// # Аргументы
+/// * `sdk` - Экземпляр SDK
+/// * `base_currency` - Базовая валюта, например `rub`
+pub async fn get_exchange_rates(
+    sdk: &TInvestSdk,
+    base_currency: &str,
+) -> Result<HashMap<String, Decimal>, TInvestError> {
+    let base_currency = base_currency.to_lowercase();
+    let currencies: Vec<Currency> = sdk
+        .instruments()
+        .currencies(InstrumentsRequest::default())
+        .await?
+        .into_inner()
+        .instruments
+        .into_iter()
+        .filter(|currency| {
+            let pair = CurrencyPair::from(currency.clone());
+            pair.base_currency == base_currency || pair.quote_currency == base_currency
+        })
+        .collect();
+    if currencies.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let last_prices = sdk
+        .market_data()
+        .get_last_prices(GetLastPricesRequest {
+            instrument_id: currencies
+                .iter()
+                .map(|currency| currency.figi.clone())
+                .collect(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .last_prices;
+
+    Ok(exchange_rates(currencies, &last_prices, &base_currency))
+}
+
+fn exchange_rates(
+    currencies: Vec<Currency>,
+    last_prices: &[LastPrice],
+    base_currency: &str,
+) -> HashMap<String, Decimal> {
+    let prices: HashMap<&str, &LastPrice> = last_prices
+        .iter()
+        .map(|last_price| (last_price.figi.as_str(), last_price))
+        .collect();
+
+    let mut rates: HashMap<String, (Decimal, Option<prost_types::Timestamp>)> = HashMap::new();
+    for currency in currencies {
+        let Some(last_price) = prices.get(currency.figi.as_str()) else {
+            continue;
+        };
+        let Some(price) = last_price.price.map(Decimal::from).filter(|p| !p.is_zero()) else {
+            continue;
+        };
+        let nominal = currency
+            .nominal
+            .clone()
+            .map(Decimal::from)
+            .filter(|nominal| !nominal.is_zero())
+            .unwrap_or(Decimal::ONE);
+
+        let pair = CurrencyPair::from(currency);
+        let (currency, rate) = if pair.quote_currency == base_currency {
+            (pair.base_currency, price / nominal)
+        } else if pair.base_currency == base_currency {
+            (pair.quote_currency, nominal / price)
+        } else {
+            continue;
+        };
+        if currency == base_currency {
+            continue;
+        }
+
+        let time = last_price.time;
+        let newer = rates.get(&currency).is_none_or(|(_, known)| {
+            known.map(|t| (t.seconds, t.nanos)) < time.map(|t| (t.seconds, t.nanos))
+        });
+        if newer {
+            rates.insert(currency, (rate, time));
+        }
+    }
+
+    rates
+        .into_iter()
+        .map(|(currency, (rate, _))| (currency, rate))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn money(value: Decimal, currency: &str) -> MoneyValue {
+        let quotation = Quotation::try_from(value).unwrap();
+        MoneyValue {
+            currency: currency.to_string(),
+            units: quotation.units,
+            nano: quotation.nano,
+        }
+    }
+
+    fn converter() -> FXConverter {
+        FXConverter::new(HashMap::from([
+            (("USD".to_string(), "RUB".to_string()), dec!(90)),
+            (("eur".to_string(), "rub".to_string()), dec!(100)),
+        ]))
+    }
+
+    #[test]
+    fn direct_and_inverse_rates() {
+        let converter = converter();
+
+        assert_eq!(
+            money(dec!(900), "rub"),
+            converter.convert(&money(dec!(10), "usd"), "rub").unwrap()
+        );
+        assert_eq!(
+            money(dec!(2), "usd"),
+            converter.convert(&money(dec!(180), "rub"), "USD").unwrap()
+        );
+        assert_eq!(
+            money(dec!(5), "usd"),
+            converter.convert(&money(dec!(5), "usd"), "usd").unwrap()
+        );
+    }
+
+    #[test]
+    fn rates_from_last_prices() {
+        let currency = |figi: &str, ticker: &str, nominal: i64| Currency {
+            figi: figi.to_string(),
+            ticker: ticker.to_string(),
+            nominal: Some(money(Decimal::from(nominal), "usd")),
+            ..Default::default()
+        };
+        let last_price = |figi: &str, price: Decimal, seconds: i64| LastPrice {
+            figi: figi.to_string(),
+            price: Some(Quotation::try_from(price).unwrap()),
+            time: Some(prost_types::Timestamp { seconds, nanos: 0 }),
+            ..Default::default()
+        };
+        let currencies = vec![
+            currency("USD_TOM", "USDRUB_TOM", 1),
+            currency("USD_TOD", "USDRUB_TOD", 1),
+            currency("KZT", "KZTRUB_TOM", 100),
+            currency("EUR", "EURRUB_TOM", 1),
+            currency("CNY", "CNYRUB_TOM", 1),
+        ];
+        let last_prices = vec![
+            last_price("USD_TOM", dec!(90), 20),
+            last_price("USD_TOD", dec!(89), 10),
+            last_price("KZT", dec!(18), 20),
+            last_price("EUR", dec!(100), 20),
+        ];
+
+        assert_eq!(
+            HashMap::from([
+                ("usd".to_string(), dec!(90)),
+                ("kzt".to_string(), dec!(0.18)),
+                ("eur".to_string(), dec!(100)),
+            ]),
+            exchange_rates(currencies.clone(), &last_prices, "rub")
+        );
+
+        // Обратная котировка: курс рубля в долларах.
+        let rates = exchange_rates(currencies, &last_prices, "usd");
+        assert_eq!(1, rates.len());
+        assert_eq!(dec!(0.011111), rates["rub"].round_dp(6));
+    }
+
+    #[test]
+    fn chains_through_base_currency() {
+        let converter = converter();
+
+        assert_eq!(
+            money(dec!(90), "eur"),
+            converter.convert(&money(dec!(100), "usd"), "eur").unwrap()
+        );
+        assert!(converter.convert(&money(dec!(1), "usd"), "cny").is_err());
+        assert!(
+            converter
+                .with_base_currency("usd")
+                .convert(&money(dec!(1), "usd"), "eur")
+                .is_err()
+        );
+    }
+}
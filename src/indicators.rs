@@ -0,0 +1,387 @@
+//! Технические индикаторы.
+//!
+//! Функции принимают ряд значений (обычно цен закрытия) и возвращают ряд той же
+//! длины, в котором `None` стоит на позициях, где для расчёта ещё недостаточно данных.
+
+use rust_decimal::Decimal;
+
+pub mod bars;
+pub mod online;
+mod window;
+
+pub use window::RollingWindow;
+
+/// Значение индикатора MACD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdValue {
+    /// Разница быстрой и медленной EMA
+    pub macd: Decimal,
+    /// EMA линии MACD
+    pub signal: Decimal,
+    /// Разница линии MACD и сигнальной линии
+    pub histogram: Decimal,
+}
+
+/// Сигнал по уровням перекупленности и перепроданности RSI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsiSignal {
+    /// RSI не ниже уровня перекупленности
+    Overbought,
+    /// RSI не выше уровня перепроданности
+    Oversold,
+    /// RSI вышел из зоны перекупленности на этом значении
+    NeutralFromOverbought,
+    /// RSI вышел из зоны перепроданности на этом значении
+    NeutralFromOversold,
+    /// RSI между уровнями или ещё не рассчитан
+    Neutral,
+}
+
+/// Рассчитывает простую скользящую среднюю (SMA).
+///
+/// Первые `period - 1` значений равны `None`.
+pub fn simple_moving_average(values: &[Decimal], period: usize) -> Vec<Option<Decimal>> {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return result;
+    }
+
+    let divisor = Decimal::from(period);
+    let mut sum: Decimal = values[..period].iter().sum();
+    result[period - 1] = Some(sum / divisor);
+
+    for i in period..values.len() {
+        sum += values[i] - values[i - period];
+        result[i] = Some(sum / divisor);
+    }
+
+    result
+}
+
+/// Рассчитывает экспоненциальную скользящую среднюю (EMA).
+///
+/// Используется коэффициент сглаживания `k = 2 / (period + 1)`, начальное значение
+/// равно SMA первых `period` значений. Первые `period - 1` значений равны `None`.
+pub fn exponential_moving_average(values: &[Decimal], period: usize) -> Vec<Option<Decimal>> {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return result;
+    }
+
+    let k = Decimal::TWO / Decimal::from(period + 1);
+    let mut ema = values[..period].iter().sum::<Decimal>() / Decimal::from(period);
+    result[period - 1] = Some(ema);
+
+    for i in period..values.len() {
+        ema += (values[i] - ema) * k;
+        result[i] = Some(ema);
+    }
+
+    result
+}
+
+/// Рассчитывает индекс относительной силы (RSI) со сглаживанием Уайлдера.
+///
+/// Для расчёта нужно `period` изменений, поэтому первые `period` значений равны `None`.
+pub fn rsi(values: &[Decimal], period: usize) -> Vec<Option<Decimal>> {
+    let mut result = vec![None; values.len()];
+    if period == 0 || values.len() <= period {
+        return result;
+    }
+
+    let divisor = Decimal::from(period);
+    let (mut avg_gain, mut avg_loss) = (Decimal::ZERO, Decimal::ZERO);
+    for i in 1..=period {
+        let (gain, loss) = gain_loss(values[i - 1], values[i]);
+        avg_gain += gain;
+        avg_loss += loss;
+    }
+    avg_gain /= divisor;
+    avg_loss /= divisor;
+    result[period] = Some(rsi_value(avg_gain, avg_loss));
+
+    for i in period + 1..values.len() {
+        let (gain, loss) = gain_loss(values[i - 1], values[i]);
+        avg_gain = (avg_gain * (divisor - Decimal::ONE) + gain) / divisor;
+        avg_loss = (avg_loss * (divisor - Decimal::ONE) + loss) / divisor;
+        result[i] = Some(rsi_value(avg_gain, avg_loss));
+    }
+
+    result
+}
+
+/// Формирует сигналы по ряду RSI и уровням перекупленности и перепроданности.
+///
+/// Значения не ниже `overbought` дают [`RsiSignal::Overbought`], не выше
+/// `oversold` — [`RsiSignal::Oversold`]. Первое значение между уровнями после
+/// выхода из зоны отмечается `NeutralFromOverbought` или `NeutralFromOversold`,
+/// остальные — `Neutral`. Пропуски `None` дают `Neutral` и не сбрасывают состояние.
+pub fn rsi_overbought_oversold_signals(
+    rsi: &[Option<Decimal>],
+    overbought: Decimal,
+    oversold: Decimal,
+) -> Vec<RsiSignal> {
+    let mut zone = RsiSignal::Neutral;
+
+    rsi.iter()
+        .map(|value| {
+            let Some(value) = value else {
+                return RsiSignal::Neutral;
+            };
+
+            let signal = if *value >= overbought {
+                RsiSignal::Overbought
+            } else if *value <= oversold {
+                RsiSignal::Oversold
+            } else {
+                match zone {
+                    RsiSignal::Overbought => RsiSignal::NeutralFromOverbought,
+                    RsiSignal::Oversold => RsiSignal::NeutralFromOversold,
+                    _ => RsiSignal::Neutral,
+                }
+            };
+            zone = signal;
+            signal
+        })
+        .collect()
+}
+
+/// Рассчитывает MACD.
+///
+/// Линия MACD — разница EMA с периодами `fast` и `slow`, сигнальная линия —
+/// EMA линии MACD с периодом `signal`. Стандартные параметры: 12, 26, 9.
+pub fn macd(values: &[Decimal], fast: usize, slow: usize, signal: usize) -> Vec<Option<MacdValue>> {
+    let fast_ema = exponential_moving_average(values, fast);
+    let slow_ema = exponential_moving_average(values, slow);
+
+    let macd_line: Vec<Option<Decimal>> = fast_ema
+        .iter()
+        .zip(&slow_ema)
+        .map(|(fast, slow)| Some((*fast)? - (*slow)?))
+        .collect();
+
+    let start = macd_line.iter().position(Option::is_some);
+    let mut result = vec![None; values.len()];
+    let Some(start) = start else {
+        return result;
+    };
+
+    let defined: Vec<Decimal> = macd_line[start..].iter().flatten().copied().collect();
+    let signal_line = exponential_moving_average(&defined, signal);
+
+    for (offset, signal) in signal_line.into_iter().enumerate() {
+        if let Some(signal) = signal {
+            let macd = defined[offset];
+            result[start + offset] = Some(MacdValue {
+                macd,
+                signal,
+                histogram: macd - signal,
+            });
+        }
+    }
+
+    result
+}
+
+/// Рассчитывает коэффициент бета актива относительно бенчмарка.
+///
+/// Бета равна наклону регрессии доходностей актива на доходности бенчмарка
+/// методом наименьших квадратов. Возвращает `None`, если длины рядов различаются,
+/// в рядах меньше двух значений или дисперсия бенчмарка равна нулю.
+pub fn beta(asset: &[Decimal], benchmark: &[Decimal]) -> Option<Decimal> {
+    if asset.len() != benchmark.len() || asset.len() < 2 {
+        return None;
+    }
+
+    let asset_mean = mean(asset);
+    let benchmark_mean = mean(benchmark);
+    let (covariance, variance) = asset.iter().zip(benchmark).fold(
+        (Decimal::ZERO, Decimal::ZERO),
+        |(covariance, variance), (asset, benchmark)| {
+            let benchmark_deviation = *benchmark - benchmark_mean;
+            (
+                covariance + (*asset - asset_mean) * benchmark_deviation,
+                variance + benchmark_deviation * benchmark_deviation,
+            )
+        },
+    );
+
+    if variance.is_zero() {
+        return None;
+    }
+
+    Some(covariance / variance)
+}
+
+/// Рассчитывает альфу Дженсена актива относительно бенчмарка в годовом выражении.
+///
+/// # Аргументы
+/// * `asset_returns` - Доходности актива за период
+/// * `benchmark_returns` - Доходности бенчмарка за те же периоды
+/// * `risk_free_rate` - Годовая безрисковая ставка
+/// * `periods_per_year` - Количество периодов в году, например 252 для дневных доходностей
+///
+/// Возвращает `None` в тех же случаях, что и [`beta`].
+pub fn alpha_vs_benchmark(
+    asset_returns: &[Decimal],
+    benchmark_returns: &[Decimal],
+    risk_free_rate: Decimal,
+    periods_per_year: u32,
+) -> Option<Decimal> {
+    if periods_per_year == 0 {
+        return None;
+    }
+
+    let beta = beta(asset_returns, benchmark_returns)?;
+    let periods = Decimal::from(periods_per_year);
+    let risk_free = risk_free_rate / periods;
+    let alpha = mean(asset_returns) - risk_free - beta * (mean(benchmark_returns) - risk_free);
+
+    Some(alpha * periods)
+}
+
+fn mean(values: &[Decimal]) -> Decimal {
+    values.iter().sum::<Decimal>() / Decimal::from(values.len())
+}
+
+fn gain_loss(prev: Decimal, cur: Decimal) -> (Decimal, Decimal) {
+    let change = cur - prev;
+    if change.is_sign_positive() {
+        (change, Decimal::ZERO)
+    } else {
+        (Decimal::ZERO, -change)
+    }
+}
+
+fn rsi_value(avg_gain: Decimal, avg_loss: Decimal) -> Decimal {
+    if avg_loss.is_zero() {
+        return Decimal::ONE_HUNDRED;
+    }
+
+    Decimal::ONE_HUNDRED - Decimal::ONE_HUNDRED / (Decimal::ONE + avg_gain / avg_loss)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn sma() {
+        assert_eq!(
+            vec![None, None, Some(dec!(2)), Some(dec!(3)), Some(dec!(4))],
+            simple_moving_average(&[dec!(1), dec!(2), dec!(3), dec!(4), dec!(5)], 3)
+        );
+
+        assert_eq!(
+            vec![None, None],
+            simple_moving_average(&[dec!(1), dec!(2)], 3)
+        );
+        assert!(simple_moving_average(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn ema() {
+        assert_eq!(
+            vec![
+                None,
+                None,
+                Some(dec!(2)),
+                Some(dec!(3)),
+                Some(dec!(4)),
+                Some(dec!(7))
+            ],
+            exponential_moving_average(&[dec!(1), dec!(2), dec!(3), dec!(4), dec!(5), dec!(10)], 3)
+        );
+    }
+
+    #[test]
+    fn rsi_bounds() {
+        let rising: Vec<Decimal> = (1..=20).map(Decimal::from).collect();
+        let values = rsi(&rising, 14);
+        assert_eq!(None, values[13]);
+        assert_eq!(Some(dec!(100)), values[14]);
+
+        let falling: Vec<Decimal> = (1..=20).rev().map(Decimal::from).collect();
+        assert_eq!(Some(dec!(0)), rsi(&falling, 14)[19]);
+
+        let alternating = [dec!(1), dec!(2), dec!(1), dec!(2), dec!(1)];
+        assert_eq!(Some(dec!(50)), rsi(&alternating, 4)[4]);
+    }
+
+    #[test]
+    fn rsi_signals() {
+        let rsi = [
+            None,
+            Some(dec!(50)),
+            Some(dec!(70)),
+            Some(dec!(75)),
+            None,
+            Some(dec!(65)),
+            Some(dec!(60)),
+            Some(dec!(25)),
+            Some(dec!(30)),
+            Some(dec!(31)),
+            Some(dec!(80)),
+        ];
+
+        assert_eq!(
+            vec![
+                RsiSignal::Neutral,
+                RsiSignal::Neutral,
+                RsiSignal::Overbought,
+                RsiSignal::Overbought,
+                RsiSignal::Neutral,
+                RsiSignal::NeutralFromOverbought,
+                RsiSignal::Neutral,
+                RsiSignal::Oversold,
+                RsiSignal::Oversold,
+                RsiSignal::NeutralFromOversold,
+                RsiSignal::Overbought,
+            ],
+            rsi_overbought_oversold_signals(&rsi, dec!(70), dec!(30))
+        );
+        assert!(rsi_overbought_oversold_signals(&[], dec!(70), dec!(30)).is_empty());
+    }
+
+    #[test]
+    fn macd_histogram() {
+        let rising: Vec<Decimal> = (1..=40).map(Decimal::from).collect();
+        let values = macd(&rising, 12, 26, 9);
+
+        assert!(values[..33].iter().all(Option::is_none));
+        let last = values[39].unwrap();
+        assert_eq!(dec!(7), last.macd.round_dp(6));
+        assert_eq!(last.macd - last.signal, last.histogram);
+    }
+
+    #[test]
+    fn beta_slope() {
+        let benchmark = [dec!(0.01), dec!(-0.02), dec!(0.03), dec!(0.00)];
+        let asset: Vec<Decimal> = benchmark
+            .iter()
+            .map(|r| r * dec!(2) + dec!(0.001))
+            .collect();
+
+        assert_eq!(Some(dec!(2)), beta(&asset, &benchmark));
+        assert_eq!(None, beta(&asset[..1], &benchmark[..1]));
+        assert_eq!(None, beta(&asset, &benchmark[..3]));
+        assert_eq!(None, beta(&asset, &[dec!(0.01); 4]));
+    }
+
+    #[test]
+    fn jensen_alpha() {
+        let benchmark = [dec!(0.01), dec!(-0.02), dec!(0.03), dec!(0.00)];
+        let asset: Vec<Decimal> = benchmark
+            .iter()
+            .map(|r| r * dec!(2) + dec!(0.001))
+            .collect();
+
+        // (0.001 + (beta - 1) * 0.12 / 12) * 12
+        let alpha = alpha_vs_benchmark(&asset, &benchmark, dec!(0.12), 12).unwrap();
+        assert_eq!(dec!(0.132), alpha.round_dp(10));
+
+        assert_eq!(None, alpha_vs_benchmark(&asset, &benchmark, dec!(0.12), 0));
+    }
+}
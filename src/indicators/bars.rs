@@ -0,0 +1,433 @@
+//! Индикаторы по свечам [`OhlcvBar`].
+
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+use crate::OhlcvBar;
+
+/// Максимальное число итераций при вычислении квадратного корня.
+const MAX_SQRT_ITERATIONS: usize = 50;
+
+/// Значение свечи, по которому рассчитывается индикатор.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CandlePriceType {
+    /// Цена открытия
+    Open,
+    /// Максимальная цена
+    High,
+    /// Минимальная цена
+    Low,
+    /// Цена закрытия
+    #[default]
+    Close,
+    /// Объём торгов
+    Volume,
+    /// Типичная цена `(high + low + close) / 3`
+    TypicalPrice,
+    /// Взвешенная цена закрытия `(high + low + 2 * close) / 4`
+    WeightedClose,
+    /// Средняя цена `(high + low) / 2`
+    MedianPrice,
+}
+
+impl CandlePriceType {
+    /// Возвращает значение свечи.
+    pub fn value(&self, bar: &OhlcvBar) -> Decimal {
+        match self {
+            CandlePriceType::Open => bar.open,
+            CandlePriceType::High => bar.high,
+            CandlePriceType::Low => bar.low,
+            CandlePriceType::Close => bar.close,
+            CandlePriceType::Volume => bar.volume,
+            CandlePriceType::TypicalPrice => (bar.high + bar.low + bar.close) / Decimal::from(3),
+            CandlePriceType::WeightedClose => {
+                (bar.high + bar.low + bar.close * Decimal::TWO) / Decimal::from(4)
+            }
+            CandlePriceType::MedianPrice => (bar.high + bar.low) / Decimal::TWO,
+        }
+    }
+}
+
+/// Возвращает ряд значений свечей выбранного типа.
+pub fn extract_price_series(bars: &[OhlcvBar], price_type: CandlePriceType) -> Vec<Decimal> {
+    bars.iter().map(|bar| price_type.value(bar)).collect()
+}
+
+/// Рассчитывает простую скользящую среднюю по значению свечей.
+///
+/// См. [`super::simple_moving_average`].
+pub fn sma(bars: &[OhlcvBar], period: usize, price_type: CandlePriceType) -> Vec<Option<Decimal>> {
+    super::simple_moving_average(&extract_price_series(bars, price_type), period)
+}
+
+/// Рассчитывает экспоненциальную скользящую среднюю по значению свечей.
+///
+/// См. [`super::exponential_moving_average`].
+pub fn ema(bars: &[OhlcvBar], period: usize, price_type: CandlePriceType) -> Vec<Option<Decimal>> {
+    super::exponential_moving_average(&extract_price_series(bars, price_type), period)
+}
+
+/// Рассчитывает RSI по цене свечей.
+///
+/// См. [`super::rsi`].
+pub fn rsi(bars: &[OhlcvBar], period: usize, price_type: CandlePriceType) -> Vec<Option<Decimal>> {
+    super::rsi(&extract_price_series(bars, price_type), period)
+}
+
+/// Рассчитывает MACD по цене свечей.
+///
+/// См. [`super::macd`].
+pub fn macd(
+    bars: &[OhlcvBar],
+    fast: usize,
+    slow: usize,
+    signal: usize,
+    price_type: CandlePriceType,
+) -> Vec<Option<super::MacdValue>> {
+    super::macd(&extract_price_series(bars, price_type), fast, slow, signal)
+}
+
+/// Рассчитывает полосы Боллинджера по ценам закрытия.
+///
+/// Средняя линия — SMA за `period` свечей, верхняя и нижняя отстоят от неё на
+/// `multiplier` стандартных отклонений. Отклонение рассчитывается по генеральной
+/// совокупности (деление на `period`). Первые `period - 1` значений равны `None`.
+///
+/// # Возвращает
+/// Тройки `(верхняя, средняя, нижняя)` для каждой свечи
+pub fn bollinger_bands(
+    bars: &[OhlcvBar],
+    period: usize,
+    multiplier: Decimal,
+) -> Vec<Option<(Decimal, Decimal, Decimal)>> {
+    let closes = extract_price_series(bars, CandlePriceType::Close);
+    let divisor = Decimal::from(period);
+
+    super::simple_moving_average(&closes, period)
+        .into_iter()
+        .enumerate()
+        .map(|(i, middle)| {
+            let middle = middle?;
+            let variance = closes[i + 1 - period..=i]
+                .iter()
+                .map(|close| (close - middle) * (close - middle))
+                .sum::<Decimal>()
+                / divisor;
+            let width = multiplier * sqrt(variance);
+            Some((middle + width, middle, middle - width))
+        })
+        .collect()
+}
+
+/// Рассчитывает ширину полос Боллинджера `(upper - lower) / middle`.
+///
+/// При нулевой средней линии возвращает ноль.
+pub fn bandwidth(upper: Decimal, lower: Decimal, middle: Decimal) -> Decimal {
+    if middle.is_zero() {
+        return Decimal::ZERO;
+    }
+
+    (upper - lower) / middle
+}
+
+/// Рассчитывает положение цены внутри полос `%b = (price - lower) / (upper - lower)`.
+///
+/// `0` соответствует нижней полосе, `1` — верхней. При совпадающих полосах
+/// возвращает `0.5`.
+pub fn percent_b(price: Decimal, upper: Decimal, lower: Decimal) -> Decimal {
+    if upper == lower {
+        return Decimal::new(5, 1);
+    }
+
+    (price - lower) / (upper - lower)
+}
+
+/// Вычисляет квадратный корень методом Ньютона.
+pub(super) fn sqrt(value: Decimal) -> Decimal {
+    if value <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+
+    let mut root = value
+        .to_f64()
+        .and_then(|value| Decimal::from_f64(value.sqrt()))
+        .filter(|root| !root.is_zero())
+        .unwrap_or(value);
+    for _ in 0..MAX_SQRT_ITERATIONS {
+        let next = (root + value / root) / Decimal::TWO;
+        if next == root {
+            break;
+        }
+        root = next;
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    /// Цены закрытия из примера расчёта скользящих средних StockCharts.
+    fn bars() -> Vec<OhlcvBar> {
+        let start = Utc.with_ymd_and_hms(2010, 3, 24, 0, 0, 0).unwrap();
+        [
+            dec!(22.27),
+            dec!(22.19),
+            dec!(22.08),
+            dec!(22.17),
+            dec!(22.18),
+            dec!(22.13),
+            dec!(22.23),
+            dec!(22.43),
+            dec!(22.24),
+            dec!(22.29),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(day, close)| OhlcvBar {
+            time: start + TimeDelta::days(day as i64),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: Decimal::from(day),
+        })
+        .collect()
+    }
+
+    fn rounded(values: Vec<Option<Decimal>>) -> Vec<Option<Decimal>> {
+        values
+            .into_iter()
+            .map(|value| value.map(|value| value.round_dp(4)))
+            .collect()
+    }
+
+    #[test]
+    fn sma_reference_values() {
+        let bars = bars();
+
+        assert_eq!(
+            vec![
+                None,
+                None,
+                Some(dec!(22.18)),
+                Some(dec!(22.1467)),
+                Some(dec!(22.1433)),
+                Some(dec!(22.16)),
+                Some(dec!(22.18)),
+                Some(dec!(22.2633)),
+                Some(dec!(22.3)),
+                Some(dec!(22.32)),
+            ],
+            rounded(sma(&bars, 3, CandlePriceType::Close))
+        );
+        assert_eq!(
+            vec![
+                None,
+                None,
+                None,
+                None,
+                Some(dec!(22.178)),
+                Some(dec!(22.15)),
+                Some(dec!(22.158)),
+                Some(dec!(22.228)),
+                Some(dec!(22.242)),
+                Some(dec!(22.264)),
+            ],
+            rounded(sma(&bars, 5, CandlePriceType::Close))
+        );
+        assert_eq!(Some(dec!(2)), sma(&bars, 5, CandlePriceType::Volume)[4]);
+    }
+
+    #[test]
+    fn ema_reference_values() {
+        let bars = bars();
+
+        assert_eq!(
+            vec![
+                None,
+                None,
+                Some(dec!(22.18)),
+                Some(dec!(22.175)),
+                Some(dec!(22.1775)),
+                Some(dec!(22.1538)),
+                Some(dec!(22.1919)),
+                Some(dec!(22.3109)),
+                Some(dec!(22.2755)),
+                Some(dec!(22.2827)),
+            ],
+            rounded(ema(&bars, 3, CandlePriceType::Close))
+        );
+        assert_eq!(
+            vec![
+                None,
+                None,
+                None,
+                None,
+                Some(dec!(22.178)),
+                Some(dec!(22.162)),
+                Some(dec!(22.1847)),
+                Some(dec!(22.2664)),
+                Some(dec!(22.2576)),
+                Some(dec!(22.2684)),
+            ],
+            rounded(ema(&bars, 5, CandlePriceType::Close))
+        );
+    }
+
+    #[test]
+    fn bollinger_reference_values() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars: Vec<OhlcvBar> = [
+            dec!(86.16),
+            dec!(89.09),
+            dec!(88.78),
+            dec!(90.32),
+            dec!(89.07),
+            dec!(91.15),
+            dec!(89.44),
+            dec!(89.18),
+            dec!(86.93),
+            dec!(87.68),
+            dec!(86.96),
+            dec!(89.43),
+            dec!(89.32),
+            dec!(88.72),
+            dec!(87.45),
+            dec!(87.26),
+            dec!(89.50),
+            dec!(87.90),
+            dec!(89.13),
+            dec!(90.70),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(day, close)| OhlcvBar {
+            time: start + TimeDelta::days(day as i64),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: Decimal::ONE,
+        })
+        .collect();
+
+        let bands: Vec<Option<(Decimal, Decimal, Decimal)>> = bollinger_bands(&bars, 5, dec!(2))
+            .into_iter()
+            .map(|band| band.map(|(u, m, l)| (u.round_dp(4), m.round_dp(4), l.round_dp(4))))
+            .collect();
+
+        assert_eq!(20, bands.len());
+        assert!(bands[..4].iter().all(Option::is_none));
+        assert_eq!(
+            vec![
+                Some((dec!(91.4219), dec!(88.684), dec!(85.9461))),
+                Some((dec!(91.4932), dec!(89.682), dec!(87.8708))),
+                Some((dec!(91.4919), dec!(89.752), dec!(88.0121))),
+                Some((dec!(91.4156), dec!(89.832), dec!(88.2484))),
+                Some((dec!(91.8402), dec!(89.154), dec!(86.4678))),
+                Some((dec!(91.8152), dec!(88.876), dec!(85.9368))),
+                Some((dec!(90.1898), dec!(88.038), dec!(85.8862))),
+                Some((dec!(90.1826), dec!(88.036), dec!(85.8894))),
+                Some((dec!(90.2723), dec!(88.064), dec!(85.8557))),
+                Some((dec!(90.3402), dec!(88.422), dec!(86.5038))),
+                Some((dec!(90.3726), dec!(88.376), dec!(86.3794))),
+                Some((dec!(90.2702), dec!(88.436), dec!(86.6018))),
+                Some((dec!(90.3151), dec!(88.45), dec!(86.5849))),
+                Some((dec!(89.8371), dec!(88.166), dec!(86.4949))),
+                Some((dec!(90.0545), dec!(88.248), dec!(86.4415))),
+                Some((dec!(91.3216), dec!(88.898), dec!(86.4744))),
+            ],
+            bands[4..]
+        );
+
+        assert!(
+            bollinger_bands(&bars[..4], 5, dec!(2))
+                .iter()
+                .all(Option::is_none)
+        );
+        assert!(
+            bollinger_bands(&bars, 0, dec!(2))
+                .iter()
+                .all(Option::is_none)
+        );
+    }
+
+    #[test]
+    fn bandwidth_and_percent_b() {
+        assert_eq!(dec!(0.2), bandwidth(dec!(110), dec!(90), dec!(100)));
+        assert_eq!(Decimal::ZERO, bandwidth(dec!(1), dec!(-1), Decimal::ZERO));
+
+        assert_eq!(dec!(0.75), percent_b(dec!(105), dec!(110), dec!(90)));
+        assert_eq!(Decimal::ZERO, percent_b(dec!(90), dec!(110), dec!(90)));
+        assert_eq!(dec!(1.25), percent_b(dec!(115), dec!(110), dec!(90)));
+        assert_eq!(dec!(0.5), percent_b(dec!(100), dec!(100), dec!(100)));
+
+        assert_eq!(dec!(1.5), sqrt(dec!(2.25)));
+        assert_eq!(Decimal::ZERO, sqrt(Decimal::ZERO));
+    }
+
+    #[test]
+    fn price_types() {
+        let bar = OhlcvBar {
+            time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            open: dec!(10),
+            high: dec!(14),
+            low: dec!(8),
+            close: dec!(12),
+            volume: dec!(100),
+        };
+        let price = |price_type: CandlePriceType| price_type.value(&bar);
+
+        assert_eq!(dec!(10), price(CandlePriceType::Open));
+        assert_eq!(dec!(14), price(CandlePriceType::High));
+        assert_eq!(dec!(8), price(CandlePriceType::Low));
+        assert_eq!(dec!(12), price(CandlePriceType::Close));
+        assert_eq!(dec!(100), price(CandlePriceType::Volume));
+        assert_eq!(
+            dec!(11.3333),
+            price(CandlePriceType::TypicalPrice).round_dp(4)
+        );
+        assert_eq!(dec!(11.5), price(CandlePriceType::WeightedClose));
+        assert_eq!(dec!(11), price(CandlePriceType::MedianPrice));
+        assert_eq!(CandlePriceType::Close, CandlePriceType::default());
+    }
+
+    #[test]
+    fn indicators_by_price_type() {
+        // Максимумы растут, а закрытия падают.
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars: Vec<OhlcvBar> = (0..5)
+            .map(|day| OhlcvBar {
+                time: start + TimeDelta::days(day),
+                open: dec!(10),
+                high: dec!(20) + Decimal::from(day),
+                low: dec!(5),
+                close: dec!(10) - Decimal::from(day),
+                volume: Decimal::ONE,
+            })
+            .collect();
+
+        assert_eq!(
+            vec![dec!(20), dec!(21), dec!(22), dec!(23), dec!(24)],
+            extract_price_series(&bars, CandlePriceType::High)
+        );
+        assert!(extract_price_series(&[], CandlePriceType::Close).is_empty());
+
+        assert_eq!(Some(dec!(100)), rsi(&bars, 4, CandlePriceType::High)[4]);
+        assert_eq!(Some(dec!(0)), rsi(&bars, 4, CandlePriceType::Close)[4]);
+        assert_eq!(
+            crate::indicators::macd(
+                &extract_price_series(&bars, CandlePriceType::MedianPrice),
+                2,
+                3,
+                2
+            ),
+            macd(&bars, 2, 3, 2, CandlePriceType::MedianPrice)
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Индикаторы, пересчитываемые по одному новому значению.
+//!
+//! В отличие от функций модуля [`indicators`](super), которые обрабатывают весь
+//! ряд, индикаторы этого модуля хранят состояние и обновляются за `O(1)` на каждое
+//! значение. Результаты совпадают с пакетными функциями на тех же данных.
+
+use rust_decimal::Decimal;
+
+use super::RollingWindow;
+use super::bars::sqrt;
+
+/// Индикатор, обновляемый по одному значению.
+pub trait OnlineIndicator {
+    /// Значение индикатора
+    type Output;
+
+    /// Учитывает новое значение ряда.
+    ///
+    /// # Возвращает
+    /// Текущее значение индикатора или `None`, пока данных недостаточно
+    fn update(&mut self, value: Decimal) -> Option<Self::Output>;
+
+    /// Сбрасывает состояние к начальному.
+    fn reset(&mut self);
+}
+
+/// Простая скользящая средняя, см. [`super::simple_moving_average`].
+#[derive(Debug, Clone)]
+pub struct OnlineSma {
+    window: RollingWindow<Decimal>,
+    sum: Decimal,
+}
+
+impl OnlineSma {
+    /// Создаёт SMA с периодом `period`.
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: RollingWindow::new(period),
+            sum: Decimal::ZERO,
+        }
+    }
+}
+
+impl OnlineIndicator for OnlineSma {
+    type Output = Decimal;
+
+    fn update(&mut self, value: Decimal) -> Option<Decimal> {
+        if self.window.capacity() == 0 {
+            return None;
+        }
+
+        self.sum += value;
+        if let Some(evicted) = self.window.push(value) {
+            self.sum -= evicted;
+        }
+
+        self.window
+            .is_full()
+            .then(|| self.sum / Decimal::from(self.window.len()))
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = Decimal::ZERO;
+    }
+}
+
+/// Экспоненциальная скользящая средняя, см. [`super::exponential_moving_average`].
+#[derive(Debug, Clone)]
+pub struct OnlineEma {
+    seed: OnlineSma,
+    k: Decimal,
+    ema: Option<Decimal>,
+}
+
+impl OnlineEma {
+    /// Создаёт EMA с периодом `period`.
+    pub fn new(period: usize) -> Self {
+        Self {
+            seed: OnlineSma::new(period),
+            k: Decimal::TWO / Decimal::from(period + 1),
+            ema: None,
+        }
+    }
+}
+
+impl OnlineIndicator for OnlineEma {
+    type Output = Decimal;
+
+    fn update(&mut self, value: Decimal) -> Option<Decimal> {
+        let ema = match self.ema {
+            Some(ema) => ema + (value - ema) * self.k,
+            None => self.seed.update(value)?,
+        };
+        self.ema = Some(ema);
+
+        Some(ema)
+    }
+
+    fn reset(&mut self) {
+        self.seed.reset();
+        self.ema = None;
+    }
+}
+
+/// Индекс относительной силы со сглаживанием Уайлдера, см. [`super::rsi`].
+#[derive(Debug, Clone)]
+pub struct OnlineRsi {
+    period: usize,
+    prev: Option<Decimal>,
+    changes: usize,
+    avg_gain: Decimal,
+    avg_loss: Decimal,
+}
+
+impl OnlineRsi {
+    /// Создаёт RSI с периодом `period`.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prev: None,
+            changes: 0,
+            avg_gain: Decimal::ZERO,
+            avg_loss: Decimal::ZERO,
+        }
+    }
+}
+
+impl OnlineIndicator for OnlineRsi {
+    type Output = Decimal;
+
+    fn update(&mut self, value: Decimal) -> Option<Decimal> {
+        let prev = self.prev.replace(value)?;
+        if self.period == 0 {
+            return None;
+        }
+
+        let (gain, loss) = super::gain_loss(prev, value);
+        let divisor = Decimal::from(self.period);
+        self.changes += 1;
+        if self.changes < self.period {
+            self.avg_gain += gain;
+            self.avg_loss += loss;
+            return None;
+        }
+
+        if self.changes == self.period {
+            self.avg_gain = (self.avg_gain + gain) / divisor;
+            self.avg_loss = (self.avg_loss + loss) / divisor;
+        } else {
+            self.avg_gain = (self.avg_gain * (divisor - Decimal::ONE) + gain) / divisor;
+            self.avg_loss = (self.avg_loss * (divisor - Decimal::ONE) + loss) / divisor;
+        }
+
+        Some(super::rsi_value(self.avg_gain, self.avg_loss))
+    }
+
+    fn reset(&mut self) {
+        self.prev = None;
+        self.changes = 0;
+        self.avg_gain = Decimal::ZERO;
+        self.avg_loss = Decimal::ZERO;
+    }
+}
+
+/// Полосы Боллинджера, см. [`super::bars::bollinger_bands`].
+///
+/// Дисперсия рассчитывается по накопленным суммам значений и их квадратов,
+/// поэтому может отличаться от пакетного расчёта в последних знаках.
+#[derive(Debug, Clone)]
+pub struct OnlineBollingerBands {
+    window: RollingWindow<Decimal>,
+    multiplier: Decimal,
+    sum: Decimal,
+    sum_of_squares: Decimal,
+}
+
+impl OnlineBollingerBands {
+    /// Создаёт полосы с периодом `period` и шириной `multiplier` стандартных отклонений.
+    pub fn new(period: usize, multiplier: Decimal) -> Self {
+        Self {
+            window: RollingWindow::new(period),
+            multiplier,
+            sum: Decimal::ZERO,
+            sum_of_squares: Decimal::ZERO,
+        }
+    }
+}
+
+impl OnlineIndicator for OnlineBollingerBands {
+    /// Тройка `(верхняя, средняя, нижняя)`
+    type Output = (Decimal, Decimal, Decimal);
+
+    fn update(&mut self, value: Decimal) -> Option<Self::Output> {
+        if self.window.capacity() == 0 {
+            return None;
+        }
+
+        self.sum += value;
+        self.sum_of_squares += value * value;
+        if let Some(evicted) = self.window.push(value) {
+            self.sum -= evicted;
+            self.sum_of_squares -= evicted * evicted;
+        }
+        if !self.window.is_full() {
+            return None;
+        }
+
+        let count = Decimal::from(self.window.len());
+        let middle = self.sum / count;
+        let variance = self.sum_of_squares / count - middle * middle;
+        let width = self.multiplier * sqrt(variance);
+
+        Some((middle + width, middle, middle - width))
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = Decimal::ZERO;
+        self.sum_of_squares = Decimal::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::OhlcvBar;
+
+    fn closes() -> Vec<Decimal> {
+        [
+            dec!(44.34),
+            dec!(44.09),
+            dec!(44.15),
+            dec!(43.61),
+            dec!(44.33),
+            dec!(44.83),
+            dec!(45.10),
+            dec!(45.42),
+            dec!(45.84),
+            dec!(46.08),
+            dec!(45.89),
+            dec!(46.03),
+            dec!(45.61),
+            dec!(46.28),
+            dec!(46.28),
+            dec!(46.00),
+            dec!(46.03),
+            dec!(46.41),
+            dec!(46.22),
+            dec!(45.64),
+        ]
+        .to_vec()
+    }
+
+    fn run<I: OnlineIndicator>(indicator: &mut I, values: &[Decimal]) -> Vec<Option<I::Output>> {
+        values
+            .iter()
+            .map(|value| indicator.update(*value))
+            .collect()
+    }
+
+    #[test]
+    fn matches_batch_indicators() {
+        let closes = closes();
+
+        for period in [0, 1, 5, 14, 25] {
+            assert_eq!(
+                crate::indicators::simple_moving_average(&closes, period),
+                run(&mut OnlineSma::new(period), &closes)
+            );
+            assert_eq!(
+                crate::indicators::exponential_moving_average(&closes, period),
+                run(&mut OnlineEma::new(period), &closes)
+            );
+            assert_eq!(
+                crate::indicators::rsi(&closes, period),
+                run(&mut OnlineRsi::new(period), &closes)
+            );
+        }
+
+        let bars: Vec<OhlcvBar> = closes
+            .iter()
+            .map(|close| OhlcvBar {
+                time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                volume: Decimal::ONE,
+            })
+            .collect();
+        let round = |band: Option<(Decimal, Decimal, Decimal)>| {
+            band.map(|(u, m, l)| (u.round_dp(8), m.round_dp(8), l.round_dp(8)))
+        };
+        for period in [0, 1, 5, 20] {
+            let batch: Vec<_> = crate::indicators::bars::bollinger_bands(&bars, period, dec!(2))
+                .into_iter()
+                .map(round)
+                .collect();
+            let online: Vec<_> = run(&mut OnlineBollingerBands::new(period, dec!(2)), &closes)
+                .into_iter()
+                .map(round)
+                .collect();
+            assert_eq!(batch, online);
+        }
+    }
+
+    #[test]
+    fn reset_restarts_warmup() {
+        let closes = closes();
+        let mut sma = OnlineSma::new(3);
+        let mut ema = OnlineEma::new(3);
+        let mut rsi = OnlineRsi::new(3);
+        let mut bands = OnlineBollingerBands::new(3, dec!(2));
+        run(&mut sma, &closes);
+        run(&mut ema, &closes);
+        run(&mut rsi, &closes);
+        run(&mut bands, &closes);
+
+        sma.reset();
+        ema.reset();
+        rsi.reset();
+        bands.reset();
+        assert_eq!(
+            crate::indicators::simple_moving_average(&closes[..5], 3),
+            run(&mut sma, &closes[..5])
+        );
+        assert_eq!(
+            crate::indicators::exponential_moving_average(&closes[..5], 3),
+            run(&mut ema, &closes[..5])
+        );
+        assert_eq!(
+            crate::indicators::rsi(&closes[..5], 3),
+            run(&mut rsi, &closes[..5])
+        );
+        assert_eq!(None, bands.update(dec!(1)));
+        assert_eq!(None, bands.update(dec!(1)));
+        assert_eq!(Some((dec!(1), dec!(1), dec!(1))), bands.update(dec!(1)));
+    }
+}
@@ -0,0 +1,131 @@
+//! Скользящее окно последних значений.
+
+use std::collections::VecDeque;
+
+/// Окно из последних `capacity` значений ряда.
+///
+/// При добавлении значения в заполненное окно самое старое значение вытесняется.
+/// Добавление выполняется за `O(1)`, значения хранятся в кольцевом буфере
+/// и переупорядочиваются в непрерывный срез только в [`Self::as_slice`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollingWindow<T> {
+    capacity: usize,
+    data: VecDeque<T>,
+}
+
+impl<T> RollingWindow<T> {
+    /// Создаёт пустое окно на `capacity` значений.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            data: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Добавляет значение.
+    ///
+    /// # Возвращает
+    /// Вытесненное значение, если окно было заполнено
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.capacity == 0 {
+            return Some(value);
+        }
+
+        let evicted = if self.is_full() {
+            self.data.pop_front()
+        } else {
+            None
+        };
+        self.data.push_back(value);
+
+        evicted
+    }
+
+    /// Возвращает итератор по значениям окна от старого к новому.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.data.iter()
+    }
+
+    /// Возвращает значения окна от старого к новому двумя частями кольцевого буфера.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.data.as_slices()
+    }
+
+    /// Возвращает значения окна от старого к новому одним срезом.
+    ///
+    /// Если буфер перешёл через границу, значения сдвигаются за `O(capacity)`.
+    pub fn as_slice(&mut self) -> &[T] {
+        self.data.make_contiguous()
+    }
+
+    /// Возвращает самое новое значение.
+    pub fn last(&self) -> Option<&T> {
+        self.data.back()
+    }
+
+    /// Проверяет, заполнено ли окно.
+    pub fn is_full(&self) -> bool {
+        self.data.len() == self.capacity
+    }
+
+    /// Возвращает количество значений в окне.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Проверяет, пусто ли окно.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Возвращает размер окна.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Удаляет все значения.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slides_over_values() {
+        let mut window = RollingWindow::new(3);
+        assert!(window.is_empty());
+        assert_eq!(None, window.last());
+
+        assert_eq!(None, window.push(1));
+        assert_eq!(None, window.push(2));
+        assert!(!window.is_full());
+        assert_eq!(None, window.push(3));
+        assert!(window.is_full());
+        assert_eq!(&[1, 2, 3], window.as_slice());
+
+        for value in 4..=10 {
+            assert_eq!(Some(value - 3), window.push(value));
+            assert_eq!(
+                vec![value - 2, value - 1, value],
+                window.iter().copied().collect::<Vec<_>>()
+            );
+        }
+        let (front, back) = window.as_slices();
+        assert_eq!(vec![8, 9, 10], [front, back].concat());
+        assert_eq!(&[8, 9, 10], window.as_slice());
+        assert_eq!((&[8, 9, 10][..], &[][..]), window.as_slices());
+        assert_eq!(3, window.len());
+        assert_eq!(Some(&10), window.last());
+
+        window.clear();
+        assert!(window.as_slice().is_empty());
+
+        let mut empty = RollingWindow::new(0);
+        assert_eq!(Some(1), empty.push(1));
+        assert!(empty.is_full());
+        assert!(empty.as_slice().is_empty());
+    }
+}
@@ -0,0 +1,381 @@
+use std::future::Future;
+
+use tonic::codegen::{Body, Bytes, StdError};
+
+use crate::TInvestError;
+use crate::api::{
+    FindInstrumentRequest, Instrument, InstrumentIdType, InstrumentRequest, InstrumentShort,
+    InstrumentType, instruments_service_client::InstrumentsServiceClient,
+};
+
+/// Типизированный поиск инструментов для [`InstrumentsServiceClient`].
+///
+/// Если API не вернул ни одного подходящего инструмента, методы возвращают
+/// [`TInvestError::NotFound`].
+///
+/// ```no_run
+/// # use t_invest_sdk::{InstrumentsExt, TInvestSdk};
+/// # async fn run(sdk: TInvestSdk) -> Result<(), t_invest_sdk::TInvestError> {
+/// let instrument = sdk.instruments().find_by_figi("BBG004730N88").await?;
+/// println!("{} {}", instrument.ticker, instrument.name);
+/// # Ok(())
+/// # }
+/// ```
+pub trait InstrumentsExt {
+    /// Ищет инструменты по точному совпадению тикера.
+    ///
+    /// Если указан `class_code`, возвращается инструмент только этого режима торгов.
+    fn find_by_ticker(
+        &mut self,
+        ticker: &str,
+        class_code: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<Instrument>, TInvestError>> + Send;
+
+    /// Ищет инструмент по ISIN. Если инструментов несколько, возвращается первый найденный.
+    fn find_by_isin(
+        &mut self,
+        isin: &str,
+    ) -> impl Future<Output = Result<Instrument, TInvestError>> + Send;
+
+    /// Возвращает инструмент по FIGI.
+    fn find_by_figi(
+        &mut self,
+        figi: &str,
+    ) -> impl Future<Output = Result<Instrument, TInvestError>> + Send;
+
+    /// Возвращает инструмент по UID.
+    fn find_by_uid(
+        &mut self,
+        uid: &str,
+    ) -> impl Future<Output = Result<Instrument, TInvestError>> + Send;
+}
+
+impl<T> InstrumentsExt for InstrumentsServiceClient<T>
+where
+    T: tonic::client::GrpcService<tonic::body::Body> + Send,
+    T::Future: Send,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    async fn find_by_ticker(
+        &mut self,
+        ticker: &str,
+        class_code: Option<&str>,
+    ) -> Result<Vec<Instrument>, TInvestError> {
+        if let Some(class_code) = class_code {
+            let instrument = instrument_by(
+                self,
+                InstrumentIdType::Ticker,
+                ticker,
+                Some(class_code.to_string()),
+            )
+            .await?;
+            return Ok(vec![instrument]);
+        }
+
+        let found = find(self, ticker, |instrument| instrument.ticker == ticker).await?;
+        if found.is_empty() {
+            return Err(TInvestError::NotFound(format!(
+                "instrument with ticker {}",
+                ticker
+            )));
+        }
+
+        let mut instruments = Vec::with_capacity(found.len());
+        for instrument in found {
+            instruments
+                .push(instrument_by(self, InstrumentIdType::Uid, &instrument.uid, None).await?);
+        }
+
+        Ok(instruments)
+    }
+
+    async fn find_by_isin(&mut self, isin: &str) -> Result<Instrument, TInvestError> {
+        let found = find(self, isin, |instrument| instrument.isin == isin).await?;
+        let instrument = found
+            .first()
+            .ok_or_else(|| TInvestError::NotFound(format!("instrument with ISIN {}", isin)))?;
+
+        instrument_by(self, InstrumentIdType::Uid, &instrument.uid, None).await
+    }
+
+    async fn find_by_figi(&mut self, figi: &str) -> Result<Instrument, TInvestError> {
+        instrument_by(self, InstrumentIdType::Figi, figi, None).await
+    }
+
+    async fn find_by_uid(&mut self, uid: &str) -> Result<Instrument, TInvestError> {
+        instrument_by(self, InstrumentIdType::Uid, uid, None).await
+    }
+}
+
+/// Выполняет полнотекстовый поиск и оставляет инструменты, подходящие под `filter`.
+async fn find<T>(
+    client: &mut InstrumentsServiceClient<T>,
+    query: &str,
+    filter: impl Fn(&InstrumentShort) -> bool,
+) -> Result<Vec<InstrumentShort>, TInvestError>
+where
+    T: tonic::client::GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    let response = client
+        .find_instrument(FindInstrumentRequest {
+            query: query.to_string(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner();
+
+    Ok(response
+        .instruments
+        .into_iter()
+        .filter(|instrument| filter(instrument))
+        .collect())
+}
+
+/// Запрашивает инструмент по идентификатору, преобразуя отсутствие результата в `NotFound`.
+async fn instrument_by<T>(
+    client: &mut InstrumentsServiceClient<T>,
+    id_type: InstrumentIdType,
+    id: &str,
+    class_code: Option<String>,
+) -> Result<Instrument, TInvestError>
+where
+    T: tonic::client::GrpcService<tonic::body::Body>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    let not_found = || TInvestError::NotFound(not_found_message(id_type, id));
+
+    let response = client
+        .get_instrument_by(InstrumentRequest {
+            id_type: id_type.into(),
+            class_code,
+            id: id.to_string(),
+        })
+        .await
+        .map_err(|status| match status.code() {
+            tonic::Code::NotFound => not_found(),
+            _ => status.into(),
+        })?;
+
+    response.into_inner().instrument.ok_or_else(not_found)
+}
+
+fn not_found_message(id_type: InstrumentIdType, id: &str) -> String {
+    let kind = match id_type {
+        InstrumentIdType::Figi => "FIGI",
+        InstrumentIdType::Ticker => "ticker",
+        InstrumentIdType::Uid => "UID",
+        InstrumentIdType::PositionUid => "position UID",
+        InstrumentIdType::Id | InstrumentIdType::InstrumentIdUnspecified => "id",
+    };
+
+    format!("instrument with {} {}", kind, id)
+}
+
+/// Фильтр инструментов на стороне клиента.
+///
+/// Валюта и биржа сравниваются без учёта регистра, тикер — по префиксу.
+/// Условия проверяются по порядку, и проверка инструмента прекращается
+/// на первом невыполненном условии.
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentFilter {
+    currency: Option<String>,
+    exchange: Option<String>,
+    instrument_type: Option<InstrumentType>,
+    api_trade_available_flag: Option<bool>,
+    buy_available_flag: Option<bool>,
+    sell_available_flag: Option<bool>,
+    ticker_prefix: Option<String>,
+}
+
+impl InstrumentFilter {
+    /// Создаёт фильтр без условий.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Оставляет инструменты с валютой расчётов.
+    pub fn currency(&mut self, currency: &str) -> &mut Self {
+        self.currency = Some(currency.to_string());
+        self
+    }
+
+    /// Оставляет инструменты с биржей.
+    pub fn exchange(&mut self, exchange: &str) -> &mut Self {
+        self.exchange = Some(exchange.to_string());
+        self
+    }
+
+    /// Оставляет инструменты указанного типа.
+    pub fn instrument_type(&mut self, instrument_type: InstrumentType) -> &mut Self {
+        self.instrument_type = Some(instrument_type);
+        self
+    }
+
+    /// Оставляет инструменты с признаком доступности для торговли через API.
+    pub fn api_trade_available_flag(&mut self, value: bool) -> &mut Self {
+        self.api_trade_available_flag = Some(value);
+        self
+    }
+
+    /// Оставляет инструменты с признаком доступности для покупки.
+    pub fn buy_available_flag(&mut self, value: bool) -> &mut Self {
+        self.buy_available_flag = Some(value);
+        self
+    }
+
+    /// Оставляет инструменты с признаком доступности для продажи.
+    pub fn sell_available_flag(&mut self, value: bool) -> &mut Self {
+        self.sell_available_flag = Some(value);
+        self
+    }
+
+    /// Оставляет инструменты, тикер которых начинается с префикса.
+    pub fn ticker_starts_with(&mut self, prefix: &str) -> &mut Self {
+        self.ticker_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Возвращает инструменты, удовлетворяющие всем условиям.
+    pub fn apply(&self, instruments: Vec<Instrument>) -> Vec<Instrument> {
+        instruments
+            .into_iter()
+            .filter(|instrument| self.matches(instrument))
+            .collect()
+    }
+
+    /// Считает инструменты, удовлетворяющие всем условиям.
+    pub fn count(&self, instruments: &[Instrument]) -> usize {
+        instruments
+            .iter()
+            .filter(|instrument| self.matches(instrument))
+            .count()
+    }
+
+    /// Проверяет, удовлетворяет ли инструмент всем условиям.
+    pub fn matches(&self, instrument: &Instrument) -> bool {
+        self.currency
+            .as_ref()
+            .is_none_or(|currency| instrument.currency.eq_ignore_ascii_case(currency))
+            && self
+                .exchange
+                .as_ref()
+                .is_none_or(|exchange| instrument.exchange.eq_ignore_ascii_case(exchange))
+            && self
+                .instrument_type
+                .is_none_or(|instrument_type| instrument.instrument_kind() == instrument_type)
+            && self
+                .api_trade_available_flag
+                .is_none_or(|value| instrument.api_trade_available_flag == value)
+            && self
+                .buy_available_flag
+                .is_none_or(|value| instrument.buy_available_flag == value)
+            && self
+                .sell_available_flag
+                .is_none_or(|value| instrument.sell_available_flag == value)
+            && self
+                .ticker_prefix
+                .as_ref()
+                .is_none_or(|prefix| instrument.ticker.starts_with(prefix.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_found_messages() {
+        assert_eq!(
+            "Not found: instrument with FIGI BBG004730N88",
+            TInvestError::NotFound(not_found_message(InstrumentIdType::Figi, "BBG004730N88"))
+                .to_string()
+        );
+        assert_eq!(
+            "instrument with ticker SBER",
+            not_found_message(InstrumentIdType::Ticker, "SBER")
+        );
+    }
+
+    fn instrument(ticker: &str, currency: &str, kind: InstrumentType, buy: bool) -> Instrument {
+        Instrument {
+            ticker: ticker.to_string(),
+            currency: currency.to_string(),
+            exchange: "MOEX".to_string(),
+            instrument_kind: kind.into(),
+            api_trade_available_flag: true,
+            buy_available_flag: buy,
+            sell_available_flag: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn filter_instruments() {
+        let instruments = vec![
+            instrument("SBER", "rub", InstrumentType::Share, true),
+            instrument("SBERP", "RUB", InstrumentType::Share, false),
+            instrument("SU26238", "rub", InstrumentType::Bond, true),
+            instrument("AAPL", "usd", InstrumentType::Share, true),
+            Instrument {
+                exchange: "SPB".to_string(),
+                api_trade_available_flag: false,
+                ..instrument("SBERX", "rub", InstrumentType::Etf, true)
+            },
+        ];
+
+        assert_eq!(5, InstrumentFilter::new().count(&instruments));
+        assert_eq!(
+            4,
+            InstrumentFilter::new().currency("rub").count(&instruments)
+        );
+        assert_eq!(
+            2,
+            InstrumentFilter::new()
+                .currency("RUB")
+                .instrument_type(InstrumentType::Share)
+                .count(&instruments)
+        );
+        assert_eq!(
+            1,
+            InstrumentFilter::new()
+                .exchange("spb")
+                .api_trade_available_flag(false)
+                .count(&instruments)
+        );
+        assert_eq!(
+            0,
+            InstrumentFilter::new()
+                .sell_available_flag(false)
+                .count(&instruments)
+        );
+
+        let tickers = |instruments: Vec<Instrument>| {
+            instruments
+                .into_iter()
+                .map(|instrument| instrument.ticker)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["SBER", "SBERX"],
+            tickers(
+                InstrumentFilter::new()
+                    .ticker_starts_with("SBER")
+                    .buy_available_flag(true)
+                    .apply(instruments.clone())
+            )
+        );
+        assert!(
+            InstrumentFilter::new()
+                .ticker_starts_with("sber")
+                .apply(instruments)
+                .is_empty()
+        );
+    }
+}
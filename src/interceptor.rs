@@ -0,0 +1,264 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tonic::service::Interceptor;
+
+/// Способ получения значения заголовка `x-tracking-id` для каждого запроса.
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use t_invest_sdk::{TInvestInterceptor, TrackingIdStrategy};
+///
+/// let counter = Arc::new(AtomicU64::new(0));
+/// let interceptor = TInvestInterceptor::new("t.xxxxxx").with_tracking_id(
+///     TrackingIdStrategy::Custom(Arc::new(move || {
+///         format!("req-{}", counter.fetch_add(1, Ordering::Relaxed))
+///     })),
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub enum TrackingIdStrategy {
+    /// Случайный UUID v4
+    #[default]
+    RandomUuid,
+    /// Значение, возвращаемое функцией, например идентификатор из W3C `traceparent`
+    Custom(Arc<dyn Fn() -> String + Send + Sync>),
+    /// Одно и то же значение для всех запросов, удобно в тестах
+    Fixed(String),
+}
+
+impl TrackingIdStrategy {
+    /// Возвращает идентификатор для очередного запроса.
+    pub fn next_id(&self) -> String {
+        match self {
+            TrackingIdStrategy::RandomUuid => uuid::Uuid::new_v4().to_string(),
+            TrackingIdStrategy::Custom(generate) => generate(),
+            TrackingIdStrategy::Fixed(id) => id.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for TrackingIdStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackingIdStrategy::RandomUuid => f.write_str("RandomUuid"),
+            TrackingIdStrategy::Custom(_) => f.write_str("Custom(..)"),
+            TrackingIdStrategy::Fixed(id) => f.debug_tuple("Fixed").field(id).finish(),
+        }
+    }
+}
+
+/// Перехватчик, подсчитывающий отправленные запросы.
+///
+/// Оборачивает другой перехватчик (обычно [`crate::TInvestInterceptor`]) и считает
+/// запросы, успешно подготовленные вложенным перехватчиком. Перехватчик не видит
+/// ответов, поэтому ошибки API не подсчитываются.
+/// Счётчик общий для всех клонов, поэтому для подсчёта по отдельному сервису
+/// следует создавать отдельный экземпляр для каждого клиента.
+///
+/// Это лёгкая альтернатива полноценным метрикам для приложений,
+/// которым не нужен Prometheus.
+#[derive(Debug, Clone)]
+pub struct MessageCounterInterceptor<I> {
+    inner: I,
+    sent: Arc<AtomicU64>,
+}
+
+impl<I> MessageCounterInterceptor<I> {
+    /// Создаёт перехватчик с нулевым счётчиком.
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            sent: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Возвращает количество отправленных запросов.
+    pub fn sent_count(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    /// Сбрасывает счётчик.
+    pub fn reset(&self) {
+        self.sent.store(0, Ordering::Relaxed);
+    }
+}
+
+impl<I: Interceptor> Interceptor for MessageCounterInterceptor<I> {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        let request = self.inner.call(request)?;
+        self.sent.fetch_add(1, Ordering::Relaxed);
+
+        Ok(request)
+    }
+}
+
+/// Перехватчик, последовательно вызывающий два перехватчика.
+///
+/// Запрос сначала обрабатывается `first`, затем результат передаётся `second`.
+/// Если `first` вернул ошибку, `second` не вызывается. Для цепочки из большего
+/// числа перехватчиков используйте макрос [`crate::compose_interceptors`].
+#[derive(Debug, Clone)]
+pub struct ComposedInterceptor<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Interceptor, B: Interceptor> Interceptor for ComposedInterceptor<A, B> {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        let request = self.first.call(request)?;
+        self.second.call(request)
+    }
+}
+
+/// Объединяет два перехватчика: `a` вызывается первым, `b` — вторым.
+///
+/// Полученный перехватчик можно передать в [`crate::TInvestSdk::with_interceptor`].
+pub fn compose<A: Interceptor, B: Interceptor>(a: A, b: B) -> ComposedInterceptor<A, B> {
+    ComposedInterceptor {
+        first: a,
+        second: b,
+    }
+}
+
+/// Объединяет несколько перехватчиков, вызываемых в порядке перечисления.
+///
+/// `compose_interceptors!(a, b, c)` равносильно `compose(a, compose(b, c))`.
+///
+/// # Пример
+///
+/// ```no_run
+/// # fn example(sdk: &t_invest_sdk::TInvestSdk) {
+/// use t_invest_sdk::api::orders_service_client::OrdersServiceClient;
+/// use t_invest_sdk::compose_interceptors;
+///
+/// let with_header = |mut request: tonic::Request<()>| {
+///     request.metadata_mut().insert("x-strategy", "momentum".parse().unwrap());
+///     Ok(request)
+/// };
+/// let with_source = |mut request: tonic::Request<()>| {
+///     request.metadata_mut().insert("x-source", "backtest".parse().unwrap());
+///     Ok(request)
+/// };
+///
+/// let interceptor = compose_interceptors!(sdk.interceptor(), with_header, with_source);
+/// let client = OrdersServiceClient::new(sdk.with_interceptor(interceptor));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! compose_interceptors {
+    ($interceptor:expr $(,)?) => {
+        $interceptor
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::compose($first, $crate::compose_interceptors!($($rest),+))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TInvestInterceptor;
+
+    #[test]
+    fn counts_requests() {
+        let counter = MessageCounterInterceptor::new(TInvestInterceptor::new("t.test"));
+        let mut service_counter = counter.clone();
+
+        service_counter.call(tonic::Request::new(())).unwrap();
+        service_counter.call(tonic::Request::new(())).unwrap();
+        assert_eq!(2, counter.sent_count());
+
+        let mut invalid = MessageCounterInterceptor::new(TInvestInterceptor::new("t.\n"));
+        assert!(invalid.call(tonic::Request::new(())).is_err());
+        assert_eq!(0, invalid.sent_count());
+
+        counter.reset();
+        assert_eq!(0, counter.sent_count());
+    }
+
+    fn header(
+        name: &'static str,
+        value: &'static str,
+    ) -> impl FnMut(tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> + Clone {
+        move |mut request: tonic::Request<()>| {
+            request.metadata_mut().insert(name, value.parse().unwrap());
+            Ok(request)
+        }
+    }
+
+    #[test]
+    fn composed_headers() {
+        let mut composed = compose(
+            TInvestInterceptor::new("t.test"),
+            header("x-strategy", "momentum"),
+        );
+
+        let request = composed.call(tonic::Request::new(())).unwrap();
+        assert_eq!(
+            "bearer t.test",
+            request.metadata().get("authorization").unwrap()
+        );
+        assert_eq!("momentum", request.metadata().get("x-strategy").unwrap());
+
+        let mut chain = crate::compose_interceptors!(
+            header("x-first", "1"),
+            header("x-second", "2"),
+            header("x-first", "3"),
+        );
+        let request = chain.call(tonic::Request::new(())).unwrap();
+        assert_eq!("3", request.metadata().get("x-first").unwrap());
+        assert_eq!("2", request.metadata().get("x-second").unwrap());
+    }
+
+    #[test]
+    fn composed_stops_on_error() {
+        let mut composed = compose(
+            |_: tonic::Request<()>| Err(tonic::Status::unauthenticated("no token")),
+            MessageCounterInterceptor::new(header("x-second", "2")),
+        );
+
+        assert!(composed.call(tonic::Request::new(())).is_err());
+        assert_eq!(0, composed.second.sent_count());
+    }
+
+    #[test]
+    fn tracking_id_strategies() {
+        let mut fixed = TInvestInterceptor::new("t.test")
+            .with_tracking_id(TrackingIdStrategy::Fixed("test-id".to_string()));
+        for _ in 0..2 {
+            let request = fixed.call(tonic::Request::new(())).unwrap();
+            assert_eq!("test-id", request.metadata().get("x-tracking-id").unwrap());
+        }
+
+        let counter = Arc::new(AtomicU64::new(0));
+        let calls = counter.clone();
+        let mut custom =
+            TInvestInterceptor::new("t.test").with_tracking_id(TrackingIdStrategy::Custom(
+                Arc::new(move || format!("req-{}", calls.fetch_add(1, Ordering::Relaxed))),
+            ));
+        custom.call(tonic::Request::new(())).unwrap();
+        let request = custom.call(tonic::Request::new(())).unwrap();
+        assert_eq!("req-1", request.metadata().get("x-tracking-id").unwrap());
+        assert_eq!(2, counter.load(Ordering::Relaxed));
+
+        let mut random = TInvestInterceptor {
+            token: "t.test".to_string(),
+            ..Default::default()
+        };
+        let request = random.call(tonic::Request::new(())).unwrap();
+        let id = request
+            .metadata()
+            .get("x-tracking-id")
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(uuid::Uuid::parse_str(id).is_ok());
+
+        let mut invalid = TInvestInterceptor::new("t.test")
+            .with_tracking_id(TrackingIdStrategy::Fixed("bad\nid".to_string()));
+        assert!(invalid.call(tonic::Request::new(())).is_err());
+    }
+}
@@ -0,0 +1,494 @@
+//! Работа с данными в формате CSV.
+//!
+//! Файл свечей содержит заголовок `time,open,high,low,close,volume`, время
+//! записывается в формате RFC 3339 по UTC, например `2024-01-15T10:00:00Z`.
+//!
+//! Операции импортируются из выгрузки брокерского отчёта, см. [`OperationsImporter`].
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use thiserror::Error;
+
+use crate::api::{MoneyValue, Operation, OperationState, OperationType, Quotation};
+use crate::{OhlcvBar, datetime_utc_to_timestamp};
+
+/// Колонки CSV файла в порядке записи.
+const CSV_HEADERS: [&str; 6] = ["time", "open", "high", "low", "close", "volume"];
+
+/// Обязательные колонки выгрузки операций.
+const OPERATION_HEADERS: [&str; 7] = [
+    "Дата",
+    "Тип операции",
+    "Тикер",
+    "Количество",
+    "Цена",
+    "Комиссия",
+    "Сумма",
+];
+
+/// Необязательная колонка валюты выгрузки операций.
+const OPERATION_CURRENCY_HEADER: &str = "Валюта";
+
+/// Формат даты и времени операции в выгрузке.
+const OPERATION_DATE_FORMAT: &str = "%d.%m.%Y %H:%M:%S";
+
+/// Смещение московского времени от UTC в секундах.
+const MOSCOW_UTC_OFFSET_SECS: i32 = 3 * 3600;
+
+/// Ошибки, которые могут возникнуть при экспорте и импорте свечей.
+///
+/// - `Csv`: Ошибка чтения, записи или разбора CSV
+/// - `Header`: Заголовок файла не совпадает с `time,open,high,low,close,volume`
+/// - `Timestamp`: Время свечи не является корректной датой RFC 3339
+/// - `Decimal`: Цена или объём не являются корректным числом
+#[derive(Error, Debug)]
+pub enum CandleIoError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("Unexpected CSV header: {0}")]
+    Header(String),
+    #[error("Can't parse timestamp {value:?} on line {line}: {source}")]
+    Timestamp {
+        line: u64,
+        value: String,
+        source: chrono::ParseError,
+    },
+    #[error("Can't parse {column} {value:?} on line {line}: {source}")]
+    Decimal {
+        line: u64,
+        column: &'static str,
+        value: String,
+        source: rust_decimal::Error,
+    },
+}
+
+/// Записывает свечи в CSV.
+pub fn export_candles_to_csv<W: Write>(
+    candles: &[OhlcvBar],
+    writer: W,
+) -> Result<(), CandleIoError> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(CSV_HEADERS)?;
+
+    for candle in candles {
+        writer.write_record([
+            candle.time.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            candle.open.to_string(),
+            candle.high.to_string(),
+            candle.low.to_string(),
+            candle.close.to_string(),
+            candle.volume.to_string(),
+        ])?;
+    }
+
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+/// Читает свечи из CSV, записанного [`export_candles_to_csv`].
+pub fn import_candles_from_csv<R: Read>(reader: R) -> Result<Vec<OhlcvBar>, CandleIoError> {
+    let mut reader = csv::Reader::from_reader(reader);
+
+    let headers = reader.headers()?;
+    if headers.iter().ne(CSV_HEADERS) {
+        return Err(CandleIoError::Header(
+            headers.iter().collect::<Vec<_>>().join(","),
+        ));
+    }
+
+    let mut candles = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let decimal = |index: usize| {
+            Decimal::from_str(&record[index]).map_err(|source| CandleIoError::Decimal {
+                line,
+                column: CSV_HEADERS[index],
+                value: record[index].to_string(),
+                source,
+            })
+        };
+
+        let time = DateTime::parse_from_rfc3339(&record[0])
+            .map_err(|source| CandleIoError::Timestamp {
+                line,
+                value: record[0].to_string(),
+                source,
+            })?
+            .with_timezone(&Utc);
+
+        candles.push(OhlcvBar {
+            time,
+            open: decimal(1)?,
+            high: decimal(2)?,
+            low: decimal(3)?,
+            close: decimal(4)?,
+            volume: decimal(5)?,
+        });
+    }
+
+    Ok(candles)
+}
+
+/// Операция из выгрузки брокерского отчёта.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationRecord {
+    /// Дата и время операции, как в выгрузке (московское время)
+    pub date: NaiveDateTime,
+    /// Тип операции, как в выгрузке, например `Покупка`
+    pub operation_type: String,
+    /// Тикер инструмента, пустой для операций без инструмента
+    pub ticker: String,
+    /// Количество бумаг, ноль для операций без инструмента
+    pub quantity: Decimal,
+    /// Цена одной бумаги
+    pub price: Option<Quotation>,
+    /// Комиссия брокера
+    pub commission: Decimal,
+    /// Сумма операции со знаком: списания отрицательны
+    pub payment: Decimal,
+    /// Валюта операции в нижнем регистре
+    pub currency: String,
+}
+
+impl OperationRecord {
+    /// Определяет тип операции API по названию из выгрузки.
+    ///
+    /// Неизвестные названия дают `OperationType::Unspecified`.
+    pub fn api_operation_type(&self) -> OperationType {
+        match self.operation_type.to_lowercase().as_str() {
+            "покупка" => OperationType::Buy,
+            "продажа" => OperationType::Sell,
+            "пополнение" | "пополнение брокерского счёта" | "пополнение брокерского счета" => {
+                OperationType::Input
+            }
+            "вывод" | "вывод денежных средств" => OperationType::Output,
+            "дивиденды" | "выплата дивидендов" => OperationType::Dividend,
+            "купоны" | "выплата купонов" => OperationType::Coupon,
+            "налог" | "удержание налога" => OperationType::Tax,
+            "налог на дивиденды" | "удержание налога по дивидендам" => {
+                OperationType::DividendTax
+            }
+            "комиссия брокера" | "удержание комиссии за операцию" => {
+                OperationType::BrokerFee
+            }
+            "комиссия за обслуживание" | "плата за обслуживание" => {
+                OperationType::ServiceFee
+            }
+            _ => OperationType::Unspecified,
+        }
+    }
+}
+
+/// Преобразует запись выгрузки в операцию API.
+///
+/// Выгрузка не содержит идентификатора операции и FIGI, поэтому поля `id` и `figi`
+/// остаются пустыми и заполняются вызывающим кодом, например FIGI по тикеру из
+/// [`crate::InstrumentsExt::find_by_ticker`]. Время переводится из московского
+/// в UTC, количество берётся по модулю. Комиссия в операцию не переносится:
+/// API возвращает её отдельной операцией `BrokerFee`.
+///
+/// # Ошибки
+/// Возвращает ошибку, если количество дробное или не помещается в `i64`,
+/// либо цена или сумма не представимы в `MoneyValue`
+impl TryFrom<&OperationRecord> for Operation {
+    type Error = String;
+
+    fn try_from(record: &OperationRecord) -> Result<Self, Self::Error> {
+        let quantity = record.quantity.abs();
+        if !quantity.fract().is_zero() {
+            return Err(format!("Fractional quantity {}", record.quantity));
+        }
+        let quantity = quantity
+            .to_i64()
+            .ok_or_else(|| format!("Quantity {} is out of range", record.quantity))?;
+
+        let date = FixedOffset::east_opt(MOSCOW_UTC_OFFSET_SECS)
+            .and_then(|offset| record.date.and_local_timezone(offset).single())
+            .ok_or_else(|| format!("Invalid Moscow time {}", record.date))?
+            .with_timezone(&Utc);
+        let money = |value: Decimal| {
+            Quotation::try_from(value).map(|quotation| MoneyValue {
+                currency: record.currency.clone(),
+                units: quotation.units,
+                nano: quotation.nano,
+            })
+        };
+
+        Ok(Operation {
+            currency: record.currency.clone(),
+            payment: Some(money(record.payment)?),
+            price: record.price.map(|price| money(price.into())).transpose()?,
+            state: OperationState::Executed.into(),
+            quantity,
+            date: Some(datetime_utc_to_timestamp(date)),
+            r#type: record.operation_type.clone(),
+            operation_type: record.api_operation_type().into(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Импорт операций из CSV выгрузки брокерского отчёта.
+///
+/// Ожидается файл с разделителем `;` и заголовком, содержащим колонки
+/// `Дата;Тип операции;Тикер;Количество;Цена;Комиссия;Сумма` в любом порядке и,
+/// необязательно, `Валюта`. Дата записывается как `15.01.2024 10:30:00`, числа —
+/// в русской локали: `1 500,25`. Пустые количество и комиссия считаются нулём,
+/// пустая цена — отсутствующей.
+pub struct OperationsImporter;
+
+impl OperationsImporter {
+    /// Читает операции из файла.
+    pub fn from_csv_file(path: &Path) -> Result<Vec<OperationRecord>, String> {
+        let file =
+            File::open(path).map_err(|err| format!("Can't open {}: {}", path.display(), err))?;
+
+        Self::from_reader(file)
+    }
+
+    /// Читает операции из CSV.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Vec<OperationRecord>, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(reader);
+
+        let headers = reader.headers().map_err(|err| err.to_string())?.clone();
+        let column = |name: &str| headers.iter().position(|header| header.trim() == name);
+        let mut columns = [0; OPERATION_HEADERS.len()];
+        for (index, name) in OPERATION_HEADERS.iter().enumerate() {
+            columns[index] = column(name).ok_or_else(|| format!("Missing column {:?}", name))?;
+        }
+        let [
+            date,
+            operation_type,
+            ticker,
+            quantity,
+            price,
+            commission,
+            payment,
+        ] = columns;
+        let currency = column(OPERATION_CURRENCY_HEADER);
+
+        let mut operations = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|err| err.to_string())?;
+            let line = record.position().map_or(0, |position| position.line());
+            let field = |index: usize| record.get(index).unwrap_or_default().trim();
+            let decimal = |index: usize| -> Result<Decimal, String> {
+                if field(index).is_empty() {
+                    return Ok(Decimal::ZERO);
+                }
+                Quotation::from_price_string_ru(field(index))
+                    .map(Decimal::from)
+                    .map_err(|err| format!("Line {}: {}", line, err))
+            };
+
+            let date = NaiveDateTime::parse_from_str(field(date), OPERATION_DATE_FORMAT).map_err(
+                |err| format!("Line {}: can't parse date {:?}: {}", line, field(date), err),
+            )?;
+            let price = match field(price) {
+                "" => None,
+                value => Some(
+                    Quotation::from_price_string_ru(value)
+                        .map_err(|err| format!("Line {}: {}", line, err))?,
+                ),
+            };
+
+            operations.push(OperationRecord {
+                date,
+                operation_type: field(operation_type).to_string(),
+                ticker: field(ticker).to_string(),
+                quantity: decimal(quantity)?,
+                price,
+                commission: decimal(commission)?,
+                payment: decimal(payment)?,
+                currency: currency.map(field).unwrap_or_default().to_lowercase(),
+            });
+        }
+
+        Ok(operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn bars(count: i64) -> Vec<OhlcvBar> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 7, 0, 0).unwrap();
+        (0..count)
+            .map(|i| {
+                let close = dec!(250.5) + Decimal::new(i * 7 % 50, 1);
+                OhlcvBar {
+                    time: start + Duration::minutes(i),
+                    open: close - dec!(0.3),
+                    high: close + dec!(1.25),
+                    low: close - dec!(1.000000001),
+                    close,
+                    volume: Decimal::from(i * 13 % 1000),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let candles = bars(100);
+        let mut buffer = Vec::new();
+        export_candles_to_csv(&candles, &mut buffer).unwrap();
+
+        let content = String::from_utf8(buffer.clone()).unwrap();
+        let mut lines = content.lines();
+        assert_eq!(Some("time,open,high,low,close,volume"), lines.next());
+        assert_eq!(
+            Some("2024-01-15T07:00:00Z,250.2,251.75,249.499999999,250.5,0"),
+            lines.next()
+        );
+        assert_eq!(99, lines.count());
+
+        assert_eq!(candles, import_candles_from_csv(buffer.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn csv_import_errors() {
+        let header = "time,open,high,low,close,volume\n";
+
+        let reordered = "time,open,low,high,close,volume\n";
+        assert!(matches!(
+            import_candles_from_csv(reordered.as_bytes()),
+            Err(CandleIoError::Header(_))
+        ));
+
+        let bad_time = format!("{}2024-01-15 07:00,1,1,1,1,1\n", header);
+        assert!(matches!(
+            import_candles_from_csv(bad_time.as_bytes()),
+            Err(CandleIoError::Timestamp { line: 2, .. })
+        ));
+
+        let bad_close = format!("{}2024-01-15T07:00:00+03:00,1,1,1,x,1\n", header);
+        assert!(matches!(
+            import_candles_from_csv(bad_close.as_bytes()),
+            Err(CandleIoError::Decimal {
+                line: 2,
+                column: "close",
+                ..
+            })
+        ));
+
+        let short = format!("{}2024-01-15T07:00:00Z,1,1\n", header);
+        assert!(matches!(
+            import_candles_from_csv(short.as_bytes()),
+            Err(CandleIoError::Csv(_))
+        ));
+    }
+
+    #[test]
+    fn import_operations() {
+        let content = "\
+Дата;Тип операции;Тикер;Количество;Цена;Комиссия;Сумма;Валюта
+15.01.2024 10:30:00;Покупка;SBER;10;270,50;1,35;-2 705,00;RUB
+16.01.2024 18:45:12;Продажа;SBER;5;280;0,70;1400;rub
+20.01.2024 00:00:00;Пополнение;;;;;10 000,00;RUB
+";
+        let operations = OperationsImporter::from_reader(content.as_bytes()).unwrap();
+
+        assert_eq!(3, operations.len());
+        assert_eq!(
+            OperationRecord {
+                date: NaiveDateTime::parse_from_str("15.01.2024 10:30:00", OPERATION_DATE_FORMAT)
+                    .unwrap(),
+                operation_type: "Покупка".to_string(),
+                ticker: "SBER".to_string(),
+                quantity: dec!(10),
+                price: Some("270.5".parse().unwrap()),
+                commission: dec!(1.35),
+                payment: dec!(-2705),
+                currency: "rub".to_string(),
+            },
+            operations[0]
+        );
+        assert_eq!(OperationType::Buy, operations[0].api_operation_type());
+        assert_eq!(OperationType::Sell, operations[1].api_operation_type());
+        assert_eq!(Some(Quotation::from(280)), operations[1].price);
+        assert_eq!(OperationType::Input, operations[2].api_operation_type());
+        assert_eq!(None, operations[2].price);
+        assert_eq!(Decimal::ZERO, operations[2].quantity);
+        assert_eq!(dec!(10000), operations[2].payment);
+    }
+
+    #[test]
+    fn operation_from_record() {
+        let content = "\
+Дата;Тип операции;Тикер;Количество;Цена;Комиссия;Сумма;Валюта
+15.01.2024 10:30:00;Покупка;SBER;10;270,50;1,35;-2 705,00;RUB
+20.01.2024 00:00:00;Пополнение;;;;;10 000,00;RUB
+";
+        let records = OperationsImporter::from_reader(content.as_bytes()).unwrap();
+
+        let buy = Operation::try_from(&records[0]).unwrap();
+        assert_eq!(OperationType::Buy, buy.operation_type());
+        assert_eq!(OperationState::Executed, buy.state());
+        assert_eq!("", buy.figi);
+        assert_eq!(10, buy.quantity);
+        assert_eq!(Some(dec!(270.5)), buy.price.clone().map(Decimal::from));
+        assert_eq!(Some(dec!(-2705)), buy.payment.clone().map(Decimal::from));
+        assert_eq!(
+            Some(datetime_utc_to_timestamp(
+                DateTime::parse_from_rfc3339("2024-01-15T07:30:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            buy.date
+        );
+
+        let input = Operation::try_from(&records[1]).unwrap();
+        assert_eq!(OperationType::Input, input.operation_type());
+        assert_eq!(None, input.price);
+        assert_eq!(0, input.quantity);
+
+        let fractional = OperationRecord {
+            quantity: dec!(1.5),
+            ..records[0].clone()
+        };
+        assert!(Operation::try_from(&fractional).is_err());
+        let huge = OperationRecord {
+            quantity: Decimal::MAX,
+            ..records[0].clone()
+        };
+        assert!(Operation::try_from(&huge).is_err());
+    }
+
+    #[test]
+    fn import_operations_errors() {
+        assert!(
+            OperationsImporter::from_reader("Дата;Тикер\n".as_bytes())
+                .unwrap_err()
+                .contains("Тип операции")
+        );
+
+        let header = "Дата;Тип операции;Тикер;Количество;Цена;Комиссия;Сумма\n";
+        let bad_date = format!("{}2024-01-15;Покупка;SBER;1;1;0;-1\n", header);
+        assert!(
+            OperationsImporter::from_reader(bad_date.as_bytes())
+                .unwrap_err()
+                .starts_with("Line 2")
+        );
+
+        let bad_price = format!("{}15.01.2024 10:30:00;Покупка;SBER;1;abc;0;-1\n", header);
+        assert!(OperationsImporter::from_reader(bad_price.as_bytes()).is_err());
+
+        assert!(
+            OperationsImporter::from_csv_file(Path::new("/nonexistent/operations.csv")).is_err()
+        );
+    }
+}
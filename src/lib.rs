@@ -12,16 +12,156 @@ use api::{
 };
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
+#[cfg(feature = "config-file")]
+use std::path::Path;
+use std::time::Duration;
 use thiserror::Error;
-use tonic::transport::ClientTlsConfig;
+use tonic::transport::{ClientTlsConfig, Endpoint};
 use tonic::{
     service::{Interceptor, interceptor::InterceptedService},
     transport::Channel,
 };
 
+#[allow(
+    clippy::large_enum_variant,
+    reason = "oneof enums in the generated prost code embed large messages"
+)]
 pub mod api;
+pub mod backtest;
+mod bonds;
+mod builder;
+mod cache;
+mod calendar;
+#[cfg(feature = "serde")]
+mod candle_interval;
+mod candles;
+mod commission;
+#[cfg(feature = "config-file")]
+mod config;
+mod currencies;
+mod datetime;
+mod decimal;
+mod dividends;
+mod downloader;
+mod equity_index;
+mod fills;
+mod fixed_income;
+mod fundamentals;
+mod futures;
+mod fx;
 #[path = "google.api.rs"]
 pub mod google_api;
+pub mod indicators;
+mod instruments;
+mod interceptor;
+#[cfg(feature = "csv")]
+mod io;
+mod money;
+mod multiplexer;
+mod operations;
+mod options;
+mod order_book;
+mod orders;
+mod pnl;
+pub mod portfolio;
+mod portfolio_stream;
+pub mod position_math;
+mod quotation;
+mod sandbox;
+mod screener;
+mod shortcuts;
+mod signals;
+mod sizing;
+mod stream;
+mod subscription_manager;
+pub mod subscriptions;
+mod timeseries;
+#[cfg(feature = "tracing")]
+mod trace;
+mod users;
+mod vwap;
+mod wash_sale;
+#[cfg(feature = "serde")]
+mod watchlist;
+
+pub use bonds::{Coupon, get_bond_ytm, get_coupon_schedule, total_cash_flow};
+pub use builder::TInvestSdkBuilder;
+pub use cache::MarketDataCache;
+pub use calendar::TradingCalendar;
+pub use candles::{
+    CandleConversionError, CandleError, CandleQualityReport, HistoricalCandleFetcher, OhlcvBar,
+    bars_from_candles, candle_quality_report, candle_to_heikin_ashi, fill_candle_gaps,
+    filter_volume_outliers, filter_zero_volume, max_candles_request_range,
+    transform_to_heikin_ashi, validate_candle, validate_candle_sequence,
+};
+pub use commission::{Tariff, estimate_commission, get_and_estimate_commission};
+#[cfg(feature = "config-file")]
+pub use config::{ConfigError, SdkConfig};
+pub use currencies::{CurrencyPair, get_currency_pairs};
+pub use datetime::{
+    datetime_utc_to_timestamp, naive_date_to_timestamp, timestamp_to_datetime_utc,
+    timestamp_to_naive_date,
+};
+pub use decimal::DecimalExt;
+pub use dividends::{Dividend, drip_compound_growth, get_dividends_typed};
+pub use downloader::{CandleStorage, ScheduledDataDownloader};
+pub use equity_index::{EquityIndex, RebalanceTrade};
+pub use fills::{AggregateFill, aggregate_partial_fills};
+pub use fixed_income::yield_to_maturity;
+pub use fundamentals::{
+    FundamentalScreener, market_cap, sector_performance_cap_weighted,
+    sector_performance_equal_weighted, shares_outstanding,
+};
+pub use futures::{FuturesMarginHelper, FuturesMarginRequirement, get_futures_margin_requirement};
+pub use fx::{FXConverter, get_exchange_rates};
+pub use instruments::{InstrumentFilter, InstrumentsExt};
+pub use interceptor::{
+    ComposedInterceptor, MessageCounterInterceptor, TrackingIdStrategy, compose,
+};
+#[cfg(feature = "csv")]
+pub use io::{
+    CandleIoError, OperationRecord, OperationsImporter, export_candles_to_csv,
+    import_candles_from_csv,
+};
+pub use money::{CurrencyPrecision, MoneyValueConversionError};
+pub use multiplexer::MarketDataMultiplexer;
+pub use operations::{
+    BuilderError, GetOperationsByCursorRequestBuilder, OperationsAggregator,
+    OperationsRequestBuilder,
+};
+pub use options::{OptionChainSnapshot, SpreadCalculator, StrikeOptions};
+pub use order_book::OrderBookSnapshot;
+pub use orders::{
+    BracketOrderIds, CancelAllResult, IdempotentOrderPlacer, OrderReconciler, OrdersExt,
+    ReconciliationResult, StopOrderPair, StopOrderTypeExt, cancel_all_orders,
+    cancel_all_stop_orders, cancel_stop_pair, place_stop_pair, wait_for_order_completion,
+    wait_for_order_completion_stream,
+};
+pub use pnl::RealTimePnL;
+pub use portfolio::{
+    GetPortfolioExt, IndexedPortfolio, PortfolioAnalyzer, PortfolioPositionRecord,
+    StressTestScenario,
+};
+pub use portfolio_stream::PortfolioValueStream;
+pub use quotation::QuotationParseError;
+pub use sandbox::{SandboxScenario, ScenarioReport};
+pub use screener::TechnicalScreener;
+pub use shortcuts::PortfolioStatistics;
+pub use signals::{SignalBroker, SignalFilter, SignalHandler};
+pub use sizing::{PositionSizer, kelly_optimal_fraction, kelly_position_size, max_affordable_lots};
+pub use stream::{
+    EventSource, MarketDataEvent, StreamHealthExt, StreamHealthMonitor, candles_into_stream,
+    next_event, vec_into_stream,
+};
+pub use subscription_manager::SubscriptionManager;
+pub use timeseries::TimeSeries;
+#[cfg(feature = "tracing")]
+pub use trace::{TracingBody, TracingInterceptor, TracingLayer, TracingService};
+pub use users::{UserContext, get_user_context};
+pub use vwap::{SessionVwap, VwapCalculator};
+pub use wash_sale::{WashSaleChecker, WashSaleViolation};
+#[cfg(feature = "serde")]
+pub use watchlist::WatchlistManager;
 
 /// Перехватчик для запросов T-Invest API.
 ///
@@ -30,9 +170,30 @@ pub mod google_api;
 /// - Аутентификацию с использованием предоставленного токена
 /// - ID отслеживания запроса
 /// - Имя приложения
-#[derive(Debug, Clone)]
+///
+/// Создаётся через [`TInvestInterceptor::new`] или литералом структуры
+/// с `..Default::default()`, по умолчанию `x-tracking-id` — случайный UUID.
+#[derive(Debug, Clone, Default)]
 pub struct TInvestInterceptor {
     pub token: String,
+    /// Способ получения `x-tracking-id`
+    pub tracking_id: TrackingIdStrategy,
+}
+
+impl TInvestInterceptor {
+    /// Создаёт перехватчик для токена со случайными `x-tracking-id`.
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            tracking_id: TrackingIdStrategy::default(),
+        }
+    }
+
+    /// Задаёт способ получения `x-tracking-id`.
+    pub fn with_tracking_id(mut self, tracking_id: TrackingIdStrategy) -> Self {
+        self.tracking_id = tracking_id;
+        self
+    }
 }
 
 /// Ошибки, которые могут возникнуть при взаимодействии с T-Invest API.
@@ -40,12 +201,166 @@ pub struct TInvestInterceptor {
 /// Это перечисление представляет возможные типы ошибок, которые могут возникнуть:
 /// - `Transport`: Ошибки, связанные с сетевым подключением или транспортным уровнем
 /// - `Status`: Ошибки, возвращаемые самим API сервисом
+/// - `Config`: Ошибки чтения файла конфигурации, например `ConfigError` (фича `config-file`)
+/// - `Storage`: Ошибки пользовательского хранилища данных
+/// - `StreamStall`: В стриме не было сообщений дольше допустимого времени
+/// - `StreamClosed`: Сервер закрыл стрим раньше, чем пришло ожидаемое сообщение
+/// - `NotFound`: Запрос выполнен, но API не вернул ни одного подходящего результата
+/// - `Timeout`: Ожидание результата превысило заданное время
+/// - `InvalidArgument`: Аргументы или данные не прошли проверку на стороне клиента
+/// - `Calculation`: Расчёт на стороне клиента не удался, например из-за неполных данных
+/// - `AssertionFailed`: Проверка состояния не прошла, например проверка портфеля
+///   в сценарии песочницы
+/// - `Step`: Ошибка шага сценария песочницы с номером и описанием шага
+///
+/// Тип реализует `std::error::Error + Send + Sync + 'static`, поэтому ошибку можно
+/// передавать между потоками и упаковывать в `Box<dyn Error + Send + Sync>`
+/// или `anyhow::Error`:
+///
+/// ```
+/// use t_invest_sdk::TInvestError;
+///
+/// let _: Box<dyn std::error::Error + Send + Sync> =
+///     Box::new(TInvestError::Status(tonic::Status::not_found("x")));
+/// ```
+///
+/// Перечисление помечено `#[non_exhaustive]`: новые варианты могут добавляться
+/// без смены мажорной версии, поэтому при сопоставлении нужна ветка `_`.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum TInvestError {
     #[error(transparent)]
     Transport(#[from] tonic::transport::Error),
-    #[error(transparent)]
+    #[error("{}: {}", status_description(.0.code()), .0.message())]
     Status(#[from] tonic::Status),
+    #[error(transparent)]
+    Config(Box<dyn std::error::Error + Send + Sync>),
+    #[error("Storage error: {0}")]
+    Storage(Box<dyn std::error::Error + Send + Sync>),
+    #[error("Stream stalled: no messages for {0:?}")]
+    StreamStall(Duration),
+    #[error("Stream closed before the expected message")]
+    StreamClosed,
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Calculation failed: {0}")]
+    Calculation(String),
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+    #[error("Step {} ({}): {}", .index + 1, .description, .source)]
+    Step {
+        /// Номер шага, начиная с нуля
+        index: usize,
+        /// Описание шага
+        description: String,
+        /// Исходная ошибка
+        source: Box<TInvestError>,
+    },
+}
+
+#[cfg(feature = "config-file")]
+impl From<ConfigError> for TInvestError {
+    fn from(err: ConfigError) -> Self {
+        TInvestError::Config(Box::new(err))
+    }
+}
+
+impl TInvestError {
+    /// Возвращает HTTP-эквивалент кода ошибки API.
+    ///
+    /// Полезно для проброса ошибок SDK в ответы веб-фреймворков.
+    ///
+    /// # Возвращает
+    /// Код для ошибок `Status`, `NotFound`, `Timeout` и `InvalidArgument`,
+    /// для `Step` — код исходной ошибки, `None` для остальных вариантов
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            TInvestError::Step { source, .. } => source.status_code(),
+            TInvestError::Status(status) => Some(http_status_code(status.code())),
+            TInvestError::NotFound(_) => Some(404),
+            TInvestError::InvalidArgument(_) => Some(400),
+            TInvestError::Timeout(_) => Some(504),
+            _ => None,
+        }
+    }
+
+    /// Проверяет, является ли ошибка временной.
+    ///
+    /// Временными считаются ошибки сети, зависания стрима, превышение лимита
+    /// запросов, таймауты и недоступность сервиса. Такие запросы имеет смысл повторить.
+    /// Для `Step` проверяется исходная ошибка.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            TInvestError::Step { source, .. } => source.is_transient(),
+            TInvestError::Transport(_)
+            | TInvestError::StreamStall(_)
+            | TInvestError::Timeout(_) => true,
+            TInvestError::Status(status) => matches!(
+                status.code(),
+                tonic::Code::Unavailable
+                    | tonic::Code::ResourceExhausted
+                    | tonic::Code::DeadlineExceeded
+                    | tonic::Code::Aborted
+            ),
+            _ => false,
+        }
+    }
+
+    /// Преобразует ошибку в `anyhow::Error` (фича `anyhow`).
+    ///
+    /// Оператор `?` в функциях, возвращающих `anyhow::Result`, выполняет то же
+    /// преобразование автоматически.
+    #[cfg(feature = "anyhow")]
+    pub fn into_anyhow(self) -> anyhow::Error {
+        anyhow::Error::new(self)
+    }
+}
+
+/// Возвращает понятное описание кода ошибки gRPC.
+fn status_description(code: tonic::Code) -> &'static str {
+    match code {
+        tonic::Code::Ok => "Success",
+        tonic::Code::Cancelled => "Request cancelled",
+        tonic::Code::Unknown => "Unknown error",
+        tonic::Code::InvalidArgument => "Invalid request argument",
+        tonic::Code::DeadlineExceeded => "Request timed out",
+        tonic::Code::NotFound => "Not found",
+        tonic::Code::AlreadyExists => "Already exists",
+        tonic::Code::PermissionDenied => "Permission denied – check your API token access level",
+        tonic::Code::ResourceExhausted => "Rate limit exceeded",
+        tonic::Code::FailedPrecondition => "Operation is not allowed in the current state",
+        tonic::Code::Aborted => "Request aborted",
+        tonic::Code::OutOfRange => "Value out of range",
+        tonic::Code::Unimplemented => "Method is not implemented",
+        tonic::Code::Internal => "Internal server error",
+        tonic::Code::Unavailable => "Service unavailable",
+        tonic::Code::DataLoss => "Data loss",
+        tonic::Code::Unauthenticated => "Authentication failed – check your API token",
+    }
+}
+
+/// Возвращает HTTP-эквивалент кода ошибки gRPC.
+fn http_status_code(code: tonic::Code) -> u16 {
+    match code {
+        tonic::Code::Ok => 200,
+        tonic::Code::Cancelled => 499,
+        tonic::Code::Unknown | tonic::Code::Internal | tonic::Code::DataLoss => 500,
+        tonic::Code::InvalidArgument
+        | tonic::Code::FailedPrecondition
+        | tonic::Code::OutOfRange => 400,
+        tonic::Code::DeadlineExceeded => 504,
+        tonic::Code::NotFound => 404,
+        tonic::Code::AlreadyExists | tonic::Code::Aborted => 409,
+        tonic::Code::PermissionDenied => 403,
+        tonic::Code::ResourceExhausted => 429,
+        tonic::Code::Unimplemented => 501,
+        tonic::Code::Unavailable => 503,
+        tonic::Code::Unauthenticated => 401,
+    }
 }
 
 /// Представляет среду для подключения к T-Invest API.
@@ -54,6 +369,11 @@ pub enum TInvestError {
 /// - `Production`: Живая продакшн среда с реальными счетами и данными
 /// - `Sandbox`: Тестовая среда, которая симулирует продакшн API
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Environment {
     Production,
     Sandbox,
@@ -77,7 +397,7 @@ impl Interceptor for TInvestInterceptor {
     ///
     /// Эта реализация добавляет следующие заголовки к каждому запросу:
     /// - `authorization`: Bearer токен для аутентификации
-    /// - `x-tracking-id`: Уникальный UUID для отслеживания запроса
+    /// - `x-tracking-id`: Идентификатор для отслеживания запроса, см. [`TrackingIdStrategy`]
     /// - `x-app-name`: Идентификатор приложения
     ///
     /// # Аргументы
@@ -98,8 +418,8 @@ impl Interceptor for TInvestInterceptor {
 
         request.metadata_mut().append(
             "x-tracking-id",
-            uuid::Uuid::new_v4()
-                .to_string()
+            self.tracking_id
+                .next_id()
                 .parse()
                 .map_err(|_| tonic::Status::internal("Invalid x-tracking-id"))?,
         );
@@ -170,15 +490,66 @@ impl TInvestSdk {
     /// - Не удалось настроить TLS конфигурацию
     /// - Невозможно установить соединение с каналом
     pub async fn new(token: &str, environment: Environment) -> Result<Self, TInvestError> {
+        Self::connect(
+            TInvestInterceptor::new(token),
+            Channel::from_static(environment.api_url()),
+        )
+        .await
+    }
+
+    /// Создаёт новый экземпляр SDK из TOML файла конфигурации.
+    ///
+    /// Файл должен содержать ключи `token` и `environment` (`"production"` или `"sandbox"`),
+    /// а также может содержать `connect_timeout_secs` и `request_timeout_secs`:
+    ///
+    /// ```toml
+    /// token = "t.xxxxxx"
+    /// environment = "sandbox"
+    /// connect_timeout_secs = 10
+    /// request_timeout_secs = 30
+    /// ```
+    ///
+    /// # Аргументы
+    /// * `path` - Путь к файлу конфигурации
+    ///
+    /// # Возвращает
+    /// Result, содержащий либо инициализированный SDK, либо TInvestError
+    ///
+    /// # Ошибки
+    /// Возвращает ошибку, если:
+    /// - Файл не удалось прочитать или разобрать
+    /// - Невозможно установить соединение с каналом
+    #[cfg(feature = "config-file")]
+    pub async fn new_from_config_file(path: &Path) -> Result<Self, TInvestError> {
+        let config = SdkConfig::from_file(path)?;
+
+        let mut builder = Self::builder(&config.token, config.environment);
+        if let Some(secs) = config.connect_timeout_secs {
+            builder.connect_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = config.request_timeout_secs {
+            builder.request_timeout(Duration::from_secs(secs));
+        }
+
+        builder.connect().await
+    }
+
+    /// Создаёт построитель SDK для настройки таймаутов и keepalive соединения.
+    ///
+    /// См. [`TInvestSdkBuilder`].
+    pub fn builder(token: &str, environment: Environment) -> TInvestSdkBuilder {
+        TInvestSdkBuilder::new(token, environment)
+    }
+
+    /// Устанавливает TLS соединение с указанной точкой подключения
+    /// и настраивает перехватчик аутентификации.
+    async fn connect(
+        interceptor: TInvestInterceptor,
+        endpoint: Endpoint,
+    ) -> Result<Self, TInvestError> {
         let tls = ClientTlsConfig::new().with_webpki_roots();
 
-        let channel = Channel::from_static(environment.api_url())
-            .tls_config(tls)?
-            .connect()
-            .await?;
-        let interceptor = TInvestInterceptor {
-            token: String::from(token),
-        };
+        let channel = endpoint.tls_config(tls)?.connect().await?;
 
         Ok(Self {
             channel,
@@ -186,6 +557,31 @@ impl TInvestSdk {
         })
     }
 
+    /// Возвращает канал, используемый SDK.
+    ///
+    /// Позволяет создавать клиенты сервисов с собственными слоями
+    /// и перехватчиками поверх того же соединения.
+    pub fn channel(&self) -> Channel {
+        self.channel.clone()
+    }
+
+    /// Возвращает перехватчик аутентификации, используемый SDK.
+    pub fn interceptor(&self) -> TInvestInterceptor {
+        self.interceptor.clone()
+    }
+
+    /// Оборачивает канал SDK собственным перехватчиком.
+    ///
+    /// Результат можно передать в `new` любого клиента сервиса. Перехватчик
+    /// заменяет стандартный, поэтому для авторизации его следует объединить
+    /// с [`Self::interceptor`] через [`compose`] или [`compose_interceptors!`].
+    pub fn with_interceptor<I: Interceptor>(
+        &self,
+        interceptor: I,
+    ) -> InterceptedService<Channel, I> {
+        InterceptedService::new(self.channel.clone(), interceptor)
+    }
+
     /// Возвращает клиент для сервиса Instruments.
     ///
     /// Этот сервис предоставляет методы для работы с финансовыми инструментами,
@@ -398,6 +794,30 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn error_is_send_sync() {
+        fn assert_error<T: std::error::Error + Send + Sync + 'static>() {}
+        assert_error::<TInvestError>();
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn error_into_anyhow() {
+        fn not_found() -> anyhow::Result<()> {
+            Err(TInvestError::NotFound("account".to_string()))?
+        }
+
+        let err = not_found().unwrap_err();
+        assert_eq!("Not found: account", err.to_string());
+        assert!(matches!(
+            err.downcast_ref::<TInvestError>(),
+            Some(TInvestError::NotFound(_))
+        ));
+
+        let err = TInvestError::Timeout(Duration::from_secs(5)).into_anyhow();
+        assert!(err.is::<TInvestError>());
+    }
+
     #[test]
     fn quotation_to_decimal() {
         assert_eq!(dec!(0), Quotation { units: 0, nano: 0 }.into());
@@ -549,6 +969,131 @@ mod tests {
         );
     }
 
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            "Authentication failed – check your API token: invalid token",
+            TInvestError::Status(tonic::Status::unauthenticated("invalid token")).to_string()
+        );
+
+        assert_eq!(
+            "Rate limit exceeded: 80002",
+            TInvestError::Status(tonic::Status::resource_exhausted("80002")).to_string()
+        );
+
+        assert_eq!(
+            "Stream stalled: no messages for 5s",
+            TInvestError::StreamStall(Duration::from_secs(5)).to_string()
+        );
+        assert_eq!(
+            "Stream closed before the expected message",
+            TInvestError::StreamClosed.to_string()
+        );
+
+        assert_eq!(
+            "Not found: instrument with ticker SBER",
+            TInvestError::NotFound("instrument with ticker SBER".to_string()).to_string()
+        );
+
+        assert_eq!(
+            "Invalid argument: quantity is zero",
+            TInvestError::InvalidArgument("quantity is zero".to_string()).to_string()
+        );
+        assert_eq!(
+            "Calculation failed: no yield for bond",
+            TInvestError::Calculation("no yield for bond".to_string()).to_string()
+        );
+        assert_eq!(
+            "Assertion failed: expected 10 x FIGI in portfolio, found 5",
+            TInvestError::AssertionFailed("expected 10 x FIGI in portfolio, found 5".to_string())
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn error_status_code() {
+        assert_eq!(
+            Some(401),
+            TInvestError::Status(tonic::Status::unauthenticated("")).status_code()
+        );
+        assert_eq!(
+            Some(403),
+            TInvestError::Status(tonic::Status::permission_denied("")).status_code()
+        );
+        assert_eq!(
+            Some(404),
+            TInvestError::Status(tonic::Status::not_found("")).status_code()
+        );
+        assert_eq!(
+            Some(400),
+            TInvestError::Status(tonic::Status::invalid_argument("")).status_code()
+        );
+        assert_eq!(
+            Some(429),
+            TInvestError::Status(tonic::Status::resource_exhausted("")).status_code()
+        );
+        assert_eq!(
+            Some(500),
+            TInvestError::Status(tonic::Status::internal("")).status_code()
+        );
+        assert_eq!(
+            Some(503),
+            TInvestError::Status(tonic::Status::unavailable("")).status_code()
+        );
+        assert_eq!(
+            Some(504),
+            TInvestError::Status(tonic::Status::deadline_exceeded("")).status_code()
+        );
+        assert_eq!(
+            Some(404),
+            TInvestError::NotFound(String::new()).status_code()
+        );
+        assert_eq!(
+            Some(504),
+            TInvestError::Timeout(Duration::from_secs(5)).status_code()
+        );
+        assert_eq!(
+            Some(400),
+            TInvestError::InvalidArgument(String::new()).status_code()
+        );
+        assert_eq!(None, TInvestError::Calculation(String::new()).status_code());
+        assert_eq!(
+            None,
+            TInvestError::AssertionFailed(String::new()).status_code()
+        );
+        assert_eq!(
+            None,
+            TInvestError::StreamStall(Duration::from_secs(5)).status_code()
+        );
+        assert_eq!(None, TInvestError::StreamClosed.status_code());
+        assert_eq!(
+            None,
+            TInvestError::Storage("disk full".into()).status_code()
+        );
+        assert_eq!(
+            None,
+            TInvestError::Config("missing token".into()).status_code()
+        );
+    }
+
+    #[test]
+    fn error_is_transient() {
+        assert!(TInvestError::Status(tonic::Status::unavailable("")).is_transient());
+        assert!(TInvestError::Status(tonic::Status::resource_exhausted("")).is_transient());
+        assert!(TInvestError::Status(tonic::Status::deadline_exceeded("")).is_transient());
+        assert!(TInvestError::StreamStall(Duration::from_secs(5)).is_transient());
+        assert!(TInvestError::Timeout(Duration::from_secs(5)).is_transient());
+
+        assert!(!TInvestError::Status(tonic::Status::unauthenticated("")).is_transient());
+        assert!(!TInvestError::Status(tonic::Status::invalid_argument("")).is_transient());
+        assert!(!TInvestError::Storage("disk full".into()).is_transient());
+        assert!(!TInvestError::Config("missing token".into()).is_transient());
+        assert!(!TInvestError::StreamClosed.is_transient());
+        assert!(!TInvestError::InvalidArgument(String::new()).is_transient());
+        assert!(!TInvestError::Calculation(String::new()).is_transient());
+        assert!(!TInvestError::AssertionFailed(String::new()).is_transient());
+    }
+
     #[test]
     fn decimal_to_quotation() {
         assert_eq!(Ok(Quotation { units: 0, nano: 0 }), dec!(0).try_into());
@@ -0,0 +1,171 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::api::{MoneyValue, Quotation};
+
+/// Максимальное количество знаков после запятой, представимое в MoneyValue.
+const MAX_SCALE: u32 = 9;
+
+/// Ошибки преобразования Decimal в MoneyValue.
+///
+/// - `InvalidCurrencyCode`: Код валюты не состоит из трёх латинских букв
+/// - `ValueTooLarge`: Целая часть не помещается в `units`
+/// - `TooManyDecimalPlaces`: Знаков после запятой больше, чем допускает валюта
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MoneyValueConversionError {
+    #[error("Invalid currency code {0:?}")]
+    InvalidCurrencyCode(String),
+    #[error("Value {0} is too large for MoneyValue")]
+    ValueTooLarge(Decimal),
+    #[error("Value {value} has more than {max_places} decimal places")]
+    TooManyDecimalPlaces { value: Decimal, max_places: u32 },
+}
+
+/// Количество знаков после запятой в суммах валют по ISO 4217.
+pub struct CurrencyPrecision;
+
+impl CurrencyPrecision {
+    /// Валюты, количество знаков которых отличается от двух.
+    const EXCEPTIONS: [(&'static str, u32); 14] = [
+        ("bhd", 3),
+        ("clp", 0),
+        ("iqd", 3),
+        ("isk", 0),
+        ("jod", 3),
+        ("jpy", 0),
+        ("krw", 0),
+        ("kwd", 3),
+        ("lyd", 3),
+        ("omr", 3),
+        ("pyg", 0),
+        ("tnd", 3),
+        ("ugx", 0),
+        ("vnd", 0),
+    ];
+
+    /// Возвращает количество знаков после запятой для валюты.
+    ///
+    /// Код валюты не зависит от регистра. Для валют, отсутствующих в таблице
+    /// исключений, возвращается `2`.
+    ///
+    /// # Возвращает
+    /// `None`, если код не состоит из трёх латинских букв
+    pub fn max_decimal_places(currency: &str) -> Option<u32> {
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let currency = currency.to_ascii_lowercase();
+        let places = Self::EXCEPTIONS
+            .iter()
+            .find(|(code, _)| *code == currency)
+            .map_or(2, |(_, places)| *places);
+
+        Some(places)
+    }
+}
+
+/// Пытается преобразовать сумму и код валюты в MoneyValue.
+///
+/// Код валюты сохраняется в нижнем регистре, как его возвращает API.
+/// Незначащие нули дробной части не учитываются при проверке точности.
+impl TryFrom<(Decimal, &str)> for MoneyValue {
+    type Error = MoneyValueConversionError;
+
+    fn try_from((value, currency): (Decimal, &str)) -> Result<Self, Self::Error> {
+        let max_places = CurrencyPrecision::max_decimal_places(currency)
+            .ok_or_else(|| MoneyValueConversionError::InvalidCurrencyCode(currency.to_string()))?
+            .min(MAX_SCALE);
+        if value.normalize().scale() > max_places {
+            return Err(MoneyValueConversionError::TooManyDecimalPlaces { value, max_places });
+        }
+
+        let Quotation { units, nano } = Quotation::try_from(value)
+            .map_err(|_| MoneyValueConversionError::ValueTooLarge(value))?;
+
+        Ok(MoneyValue {
+            currency: currency.to_ascii_lowercase(),
+            units,
+            nano,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn money(value: Decimal, currency: &str) -> Result<MoneyValue, MoneyValueConversionError> {
+        MoneyValue::try_from((value, currency))
+    }
+
+    #[test]
+    fn decimal_to_money_value() {
+        assert_eq!(
+            Ok(MoneyValue {
+                currency: "rub".to_string(),
+                units: 1500,
+                nano: 750_000_000,
+            }),
+            money(dec!(1500.75), "RUB")
+        );
+        assert_eq!(
+            Ok(MoneyValue {
+                currency: "usd".to_string(),
+                units: -12,
+                nano: -10_000_000,
+            }),
+            money(dec!(-12.0100), "usd")
+        );
+        assert_eq!(
+            Err(MoneyValueConversionError::TooManyDecimalPlaces {
+                value: dec!(0.001),
+                max_places: 2,
+            }),
+            money(dec!(0.001), "usd")
+        );
+
+        assert_eq!(
+            Ok(MoneyValue {
+                currency: "jpy".to_string(),
+                units: 5000,
+                nano: 0,
+            }),
+            money(dec!(5000.00), "JPY")
+        );
+        assert!(matches!(
+            money(dec!(5000.5), "jpy"),
+            Err(MoneyValueConversionError::TooManyDecimalPlaces { max_places: 0, .. })
+        ));
+
+        assert_eq!(
+            Ok(MoneyValue {
+                currency: "kwd".to_string(),
+                units: 3,
+                nano: 125_000_000,
+            }),
+            money(dec!(3.125), "kwd")
+        );
+        assert!(matches!(
+            money(dec!(3.1255), "KWD"),
+            Err(MoneyValueConversionError::TooManyDecimalPlaces { max_places: 3, .. })
+        ));
+
+        assert_eq!(
+            Err(MoneyValueConversionError::InvalidCurrencyCode(
+                "рубль".to_string()
+            )),
+            money(dec!(1), "рубль")
+        );
+        assert!(matches!(
+            money(dec!(1), "us"),
+            Err(MoneyValueConversionError::InvalidCurrencyCode(_))
+        ));
+        assert_eq!(
+            Err(MoneyValueConversionError::ValueTooLarge(Decimal::MAX)),
+            money(Decimal::MAX, "rub")
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::sync::{Arc, Mutex};
+
+use futures::{Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::TInvestError;
+use crate::api::MarketDataResponse;
+
+/// Количество сообщений, которое может накопиться у отстающего получателя.
+///
+/// При переполнении получатель пропускает старые сообщения и получает
+/// `RecvError::Lagged`.
+const RECEIVER_CAPACITY: usize = 1024;
+
+type Filter = Box<dyn Fn(&MarketDataResponse) -> bool + Send>;
+
+type Receivers = Arc<Mutex<Vec<(Filter, broadcast::Sender<MarketDataResponse>)>>>;
+
+/// Раздаёт сообщения одного стрима рыночных данных нескольким получателям.
+///
+/// Стрим читается фоновой задачей tokio, каждое сообщение отправляется всем
+/// получателям, фильтр которых его пропускает. Когда стрим заканчивается или
+/// возвращает ошибку, задача завершается и получатели закрываются, после чего
+/// стрим можно открыть заново и создать новый мультиплексор. Ошибку стрима
+/// возвращает [`MarketDataMultiplexer::join`].
+///
+/// ```no_run
+/// # use t_invest_sdk::{MarketDataMultiplexer, TInvestSdk};
+/// # use t_invest_sdk::api::market_data_response::Payload;
+/// # async fn run(sdk: TInvestSdk, requests: futures::stream::Pending<t_invest_sdk::api::MarketDataRequest>) -> Result<(), t_invest_sdk::TInvestError> {
+/// let stream = sdk
+///     .market_data_stream()
+///     .market_data_stream(requests)
+///     .await?
+///     .into_inner();
+/// let multiplexer = MarketDataMultiplexer::new(stream);
+///
+/// let mut candles = multiplexer.register(|response| {
+///     matches!(response.payload, Some(Payload::Candle(_)))
+/// });
+/// while let Ok(response) = candles.recv().await {
+///     println!("{:?}", response);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct MarketDataMultiplexer {
+    receivers: Receivers,
+    task: Option<JoinHandle<Result<(), TInvestError>>>,
+}
+
+impl MarketDataMultiplexer {
+    /// Запускает чтение стрима в фоновой задаче.
+    ///
+    /// Должен вызываться внутри runtime tokio.
+    pub fn new<S, E>(mut stream: S) -> Self
+    where
+        S: Stream<Item = Result<MarketDataResponse, E>> + Send + Unpin + 'static,
+        E: Into<TInvestError>,
+    {
+        let receivers = Receivers::default();
+        let shared = receivers.clone();
+
+        let task = tokio::spawn(async move {
+            let result = loop {
+                let response = match stream.next().await {
+                    Some(Ok(response)) => response,
+                    Some(Err(err)) => break Err(err.into()),
+                    None => break Ok(()),
+                };
+
+                let mut receivers = shared.lock().unwrap_or_else(|err| err.into_inner());
+                receivers.retain(|(_, sender)| sender.receiver_count() > 0);
+                for (filter, sender) in receivers.iter() {
+                    if filter(&response) {
+                        let _ = sender.send(response.clone());
+                    }
+                }
+            };
+            shared.lock().unwrap_or_else(|err| err.into_inner()).clear();
+
+            result
+        });
+
+        Self {
+            receivers,
+            task: Some(task),
+        }
+    }
+
+    /// Регистрирует получателя сообщений, для которых `filter` возвращает `true`.
+    ///
+    /// Получатель получает только сообщения, пришедшие после регистрации.
+    pub fn register(
+        &self,
+        filter: impl Fn(&MarketDataResponse) -> bool + Send + 'static,
+    ) -> broadcast::Receiver<MarketDataResponse> {
+        let (sender, receiver) = broadcast::channel(RECEIVER_CAPACITY);
+        let mut receivers = self.receivers.lock().unwrap_or_else(|err| err.into_inner());
+        if self.task.as_ref().is_some_and(|task| !task.is_finished()) {
+            receivers.push((Box::new(filter), sender));
+        }
+
+        receiver
+    }
+
+    /// Ожидает завершения чтения стрима.
+    ///
+    /// # Возвращает
+    /// Ошибку, которой завершился стрим. Если стрим закончился, чтение остановлено
+    /// через [`Self::close`] или результат уже получен, возвращается `Ok(())`
+    pub async fn join(&mut self) -> Result<(), TInvestError> {
+        let Some(task) = self.task.take() else {
+            return Ok(());
+        };
+
+        match task.await {
+            Ok(result) => result,
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Останавливает чтение стрима и закрывает всех получателей.
+    pub fn close(&self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+        self.receivers
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+    }
+}
+
+impl Drop for MarketDataMultiplexer {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::mpsc;
+    use tokio::sync::broadcast::error::RecvError;
+
+    use super::*;
+    use crate::api::market_data_response::Payload;
+    use crate::api::{Candle, LastPrice};
+
+    fn candle(figi: &str) -> MarketDataResponse {
+        MarketDataResponse {
+            payload: Some(Payload::Candle(Candle {
+                figi: figi.to_string(),
+                ..Default::default()
+            })),
+        }
+    }
+
+    fn last_price(figi: &str) -> MarketDataResponse {
+        MarketDataResponse {
+            payload: Some(Payload::LastPrice(LastPrice {
+                figi: figi.to_string(),
+                ..Default::default()
+            })),
+        }
+    }
+
+    #[tokio::test]
+    async fn fans_out_by_filter() {
+        let (sender, stream) = mpsc::unbounded::<Result<MarketDataResponse, tonic::Status>>();
+        let multiplexer = MarketDataMultiplexer::new(stream);
+
+        let mut candles =
+            multiplexer.register(|response| matches!(response.payload, Some(Payload::Candle(_))));
+        let mut prices = multiplexer
+            .register(|response| matches!(response.payload, Some(Payload::LastPrice(_))));
+
+        for response in [candle("A"), last_price("A"), candle("B"), last_price("B")] {
+            sender.unbounded_send(Ok(response)).unwrap();
+        }
+
+        assert_eq!(candle("A"), candles.recv().await.unwrap());
+        assert_eq!(candle("B"), candles.recv().await.unwrap());
+        assert_eq!(last_price("A"), prices.recv().await.unwrap());
+        assert_eq!(last_price("B"), prices.recv().await.unwrap());
+
+        multiplexer.close();
+        assert_eq!(Err(RecvError::Closed), candles.recv().await);
+        assert_eq!(Err(RecvError::Closed), prices.recv().await);
+    }
+
+    #[tokio::test]
+    async fn closes_receivers_on_stream_error() {
+        let (sender, stream) = mpsc::unbounded::<Result<MarketDataResponse, tonic::Status>>();
+        let mut multiplexer = MarketDataMultiplexer::new(stream);
+        let mut all = multiplexer.register(|_| true);
+
+        sender.unbounded_send(Ok(candle("A"))).unwrap();
+        sender
+            .unbounded_send(Err(tonic::Status::unavailable("closed")))
+            .unwrap();
+
+        assert_eq!(candle("A"), all.recv().await.unwrap());
+        assert_eq!(Err(RecvError::Closed), all.recv().await);
+        assert!(matches!(
+            multiplexer.join().await,
+            Err(TInvestError::Status(status)) if status.code() == tonic::Code::Unavailable
+        ));
+        assert!(multiplexer.join().await.is_ok());
+        assert!(multiplexer.register(|_| true).recv().await.is_err());
+
+        let (sender, stream) = mpsc::unbounded::<Result<MarketDataResponse, tonic::Status>>();
+        let mut multiplexer = MarketDataMultiplexer::new(stream);
+        drop(sender);
+        assert!(multiplexer.join().await.is_ok());
+    }
+}
@@ -0,0 +1,529 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::api::{
+    GetOperationsByCursorRequest, Operation, OperationState, OperationType, OperationsRequest,
+};
+use crate::datetime_utc_to_timestamp;
+use crate::portfolio::trade_sign;
+
+/// Ошибки построения запроса операций.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuilderError {
+    #[error("Period start {from} is not before end {to}")]
+    InvalidPeriod {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+}
+
+/// Построитель `OperationsRequest`.
+///
+/// Дата окончания, заданная через [`Self::to_date`], включается в период.
+#[derive(Debug, Clone)]
+pub struct OperationsRequestBuilder {
+    account_id: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    figi: Option<String>,
+    state: Option<OperationState>,
+}
+
+impl OperationsRequestBuilder {
+    /// Создаёт построитель запроса операций по счёту.
+    pub fn new(account_id: &str) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            from: None,
+            to: None,
+            figi: None,
+            state: None,
+        }
+    }
+
+    /// Устанавливает начало периода: начало дня по UTC.
+    pub fn from_date(&mut self, date: NaiveDate) -> &mut Self {
+        self.from = Some(start_of_day(date));
+        self
+    }
+
+    /// Устанавливает окончание периода: конец дня по UTC.
+    pub fn to_date(&mut self, date: NaiveDate) -> &mut Self {
+        self.to = Some(start_of_day(date) + TimeDelta::days(1));
+        self
+    }
+
+    /// Устанавливает начало периода.
+    pub fn from_datetime(&mut self, datetime: DateTime<Utc>) -> &mut Self {
+        self.from = Some(datetime);
+        self
+    }
+
+    /// Устанавливает окончание периода.
+    pub fn to_datetime(&mut self, datetime: DateTime<Utc>) -> &mut Self {
+        self.to = Some(datetime);
+        self
+    }
+
+    /// Оставляет операции по инструменту.
+    pub fn figi(&mut self, figi: &str) -> &mut Self {
+        self.figi = Some(figi.to_string());
+        self
+    }
+
+    /// Оставляет операции в статусе.
+    pub fn state(&mut self, state: OperationState) -> &mut Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Формирует запрос.
+    ///
+    /// # Возвращает
+    /// [`BuilderError::InvalidPeriod`], если начало периода не раньше окончания
+    pub fn build(&self) -> Result<OperationsRequest, BuilderError> {
+        validate_period(self.from, self.to)?;
+
+        Ok(OperationsRequest {
+            account_id: self.account_id.clone(),
+            from: self.from.map(datetime_utc_to_timestamp),
+            to: self.to.map(datetime_utc_to_timestamp),
+            state: self.state.map(|state| state as i32),
+            figi: self.figi.clone(),
+        })
+    }
+}
+
+/// Построитель `GetOperationsByCursorRequest` для постраничной загрузки операций.
+///
+/// Дата окончания, заданная через [`Self::to_date`], включается в период.
+#[derive(Debug, Clone)]
+pub struct GetOperationsByCursorRequestBuilder {
+    account_id: String,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    instrument_id: Option<String>,
+    operation_types: Vec<OperationType>,
+    state: Option<OperationState>,
+    cursor: Option<String>,
+    limit: Option<i32>,
+    without_commissions: Option<bool>,
+    without_trades: Option<bool>,
+}
+
+impl GetOperationsByCursorRequestBuilder {
+    /// Создаёт построитель запроса операций по счёту.
+    pub fn new(account_id: &str) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            from: None,
+            to: None,
+            instrument_id: None,
+            operation_types: Vec::new(),
+            state: None,
+            cursor: None,
+            limit: None,
+            without_commissions: None,
+            without_trades: None,
+        }
+    }
+
+    /// Устанавливает начало периода: начало дня по UTC.
+    pub fn from_date(&mut self, date: NaiveDate) -> &mut Self {
+        self.from = Some(start_of_day(date));
+        self
+    }
+
+    /// Устанавливает окончание периода: конец дня по UTC.
+    pub fn to_date(&mut self, date: NaiveDate) -> &mut Self {
+        self.to = Some(start_of_day(date) + TimeDelta::days(1));
+        self
+    }
+
+    /// Устанавливает начало периода.
+    pub fn from_datetime(&mut self, datetime: DateTime<Utc>) -> &mut Self {
+        self.from = Some(datetime);
+        self
+    }
+
+    /// Устанавливает окончание периода.
+    pub fn to_datetime(&mut self, datetime: DateTime<Utc>) -> &mut Self {
+        self.to = Some(datetime);
+        self
+    }
+
+    /// Оставляет операции по инструменту (FIGI или UID).
+    pub fn instrument_id(&mut self, instrument_id: &str) -> &mut Self {
+        self.instrument_id = Some(instrument_id.to_string());
+        self
+    }
+
+    /// Оставляет операции перечисленных типов.
+    pub fn operation_types(&mut self, types: Vec<OperationType>) -> &mut Self {
+        self.operation_types = types;
+        self
+    }
+
+    /// Оставляет операции в статусе.
+    pub fn state(&mut self, state: OperationState) -> &mut Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Устанавливает курсор страницы из `next_cursor` предыдущего ответа.
+    pub fn cursor(&mut self, cursor: &str) -> &mut Self {
+        self.cursor = Some(cursor.to_string());
+        self
+    }
+
+    /// Устанавливает максимальное количество операций на странице.
+    pub fn limit(&mut self, limit: i32) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Исключает операции удержания комиссий.
+    pub fn without_commissions(&mut self, without_commissions: bool) -> &mut Self {
+        self.without_commissions = Some(without_commissions);
+        self
+    }
+
+    /// Исключает сделки.
+    pub fn without_trades(&mut self, without_trades: bool) -> &mut Self {
+        self.without_trades = Some(without_trades);
+        self
+    }
+
+    /// Формирует запрос.
+    ///
+    /// # Возвращает
+    /// [`BuilderError::InvalidPeriod`], если начало периода не раньше окончания
+    pub fn build(&self) -> Result<GetOperationsByCursorRequest, BuilderError> {
+        validate_period(self.from, self.to)?;
+
+        Ok(GetOperationsByCursorRequest {
+            account_id: self.account_id.clone(),
+            instrument_id: self.instrument_id.clone(),
+            from: self.from.map(datetime_utc_to_timestamp),
+            to: self.to.map(datetime_utc_to_timestamp),
+            cursor: self.cursor.clone(),
+            limit: self.limit,
+            operation_types: self
+                .operation_types
+                .iter()
+                .map(|operation_type| *operation_type as i32)
+                .collect(),
+            state: self.state.map(|state| state as i32),
+            without_commissions: self.without_commissions,
+            without_trades: self.without_trades,
+            without_overnights: None,
+        })
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn validate_period(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<(), BuilderError> {
+    match (from, to) {
+        (Some(from), Some(to)) if from >= to => Err(BuilderError::InvalidPeriod { from, to }),
+        _ => Ok(()),
+    }
+}
+
+/// Сводка по операциям счёта.
+///
+/// Денежные итоги рассчитываются по полю `payment` исполненных операций,
+/// операции в других статусах учитываются только в [`OperationsAggregator::operations_by_type`].
+#[derive(Debug, Clone, Default)]
+pub struct OperationsAggregator {
+    operations: Vec<Operation>,
+}
+
+impl OperationsAggregator {
+    /// Создаёт сводку по операциям.
+    pub fn from_operations(ops: Vec<Operation>) -> Self {
+        Self { operations: ops }
+    }
+
+    /// Возвращает сумму уплаченных комиссий по валютам.
+    ///
+    /// Комиссии списываются со счёта, поэтому сумма положительна,
+    /// а возвраты комиссий её уменьшают.
+    pub fn total_commission_by_currency(&self) -> HashMap<String, Decimal> {
+        self.sum_by_currency(is_commission)
+            .into_iter()
+            .map(|(currency, amount)| (currency, -amount))
+            .collect()
+    }
+
+    /// Возвращает сумму полученных дивидендов по валютам.
+    pub fn total_dividends_by_currency(&self) -> HashMap<String, Decimal> {
+        self.sum_by_currency(|operation_type| {
+            matches!(
+                operation_type,
+                OperationType::Dividend | OperationType::DivExt
+            )
+        })
+    }
+
+    /// Возвращает денежный результат сделок по FIGI: выручку от продаж за вычетом
+    /// затрат на покупки. Комиссии не учитываются.
+    ///
+    /// Для открытых позиций результат включает стоимость ещё не проданных бумаг
+    /// со знаком минус.
+    pub fn trading_pnl_by_figi(&self) -> HashMap<String, Decimal> {
+        let mut pnl: HashMap<String, Decimal> = HashMap::new();
+        for operation in self.executed() {
+            if trade_sign(operation.operation_type).is_none() {
+                continue;
+            }
+            *pnl.entry(operation.figi.clone()).or_default() += payment(operation);
+        }
+
+        pnl
+    }
+
+    /// Группирует операции по типу.
+    ///
+    /// Операции с неизвестным типом попадают в группу `OperationType::Unspecified`.
+    pub fn operations_by_type(&self) -> HashMap<OperationType, Vec<Operation>> {
+        let mut groups: HashMap<OperationType, Vec<Operation>> = HashMap::new();
+        for operation in &self.operations {
+            groups
+                .entry(operation.operation_type())
+                .or_default()
+                .push(operation.clone());
+        }
+
+        groups
+    }
+
+    fn executed(&self) -> impl Iterator<Item = &Operation> {
+        self.operations
+            .iter()
+            .filter(|operation| operation.state == OperationState::Executed as i32)
+    }
+
+    fn sum_by_currency(&self, filter: impl Fn(OperationType) -> bool) -> HashMap<String, Decimal> {
+        let mut sums: HashMap<String, Decimal> = HashMap::new();
+        for operation in self.executed() {
+            if !filter(operation.operation_type()) {
+                continue;
+            }
+            let currency = operation
+                .payment
+                .as_ref()
+                .map(|payment| payment.currency.as_str())
+                .filter(|currency| !currency.is_empty())
+                .unwrap_or(&operation.currency);
+            *sums.entry(currency.to_lowercase()).or_default() += payment(operation);
+        }
+
+        sums
+    }
+}
+
+/// Проверяет, является ли операция удержанием комиссии.
+fn is_commission(operation_type: OperationType) -> bool {
+    matches!(
+        operation_type,
+        OperationType::BrokerFee
+            | OperationType::ServiceFee
+            | OperationType::MarginFee
+            | OperationType::SuccessFee
+            | OperationType::TrackMfee
+            | OperationType::TrackPfee
+            | OperationType::CashFee
+            | OperationType::OutFee
+            | OperationType::OutputPenalty
+            | OperationType::AdviceFee
+            | OperationType::OverCom
+            | OperationType::OtherFee
+    )
+}
+
+fn payment(operation: &Operation) -> Decimal {
+    operation
+        .payment
+        .clone()
+        .map(Decimal::from)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, Quotation};
+
+    fn operation(
+        figi: &str,
+        operation_type: OperationType,
+        payment: Decimal,
+        currency: &str,
+    ) -> Operation {
+        let quotation = Quotation::try_from(payment).unwrap();
+        Operation {
+            figi: figi.to_string(),
+            state: OperationState::Executed as i32,
+            operation_type: operation_type as i32,
+            payment: Some(MoneyValue {
+                currency: currency.to_string(),
+                units: quotation.units,
+                nano: quotation.nano,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aggregate_mixed_operations() {
+        let mut canceled = operation("A", OperationType::Sell, dec!(1000), "rub");
+        canceled.state = OperationState::Canceled as i32;
+
+        let aggregator = OperationsAggregator::from_operations(vec![
+            operation("A", OperationType::Buy, dec!(-1000), "rub"),
+            operation("A", OperationType::BrokerFee, dec!(-3), "rub"),
+            operation("A", OperationType::Sell, dec!(1200.5), "rub"),
+            operation("A", OperationType::BrokerFee, dec!(-3.6), "rub"),
+            operation("B", OperationType::Buy, dec!(-50), "usd"),
+            operation("B", OperationType::BrokerFee, dec!(-0.15), "usd"),
+            operation("", OperationType::ServiceFee, dec!(-290), "RUB"),
+            operation("A", OperationType::Dividend, dec!(87), "rub"),
+            operation("B", OperationType::Dividend, dec!(1.2), "usd"),
+            operation("B", OperationType::DividendTax, dec!(-0.12), "usd"),
+            operation("", OperationType::Input, dec!(10000), "rub"),
+            canceled,
+        ]);
+
+        assert_eq!(
+            HashMap::from([
+                ("rub".to_string(), dec!(296.6)),
+                ("usd".to_string(), dec!(0.15))
+            ]),
+            aggregator.total_commission_by_currency()
+        );
+        assert_eq!(
+            HashMap::from([
+                ("rub".to_string(), dec!(87)),
+                ("usd".to_string(), dec!(1.2))
+            ]),
+            aggregator.total_dividends_by_currency()
+        );
+        assert_eq!(
+            HashMap::from([("A".to_string(), dec!(200.5)), ("B".to_string(), dec!(-50))]),
+            aggregator.trading_pnl_by_figi()
+        );
+
+        let by_type = aggregator.operations_by_type();
+        assert_eq!(3, by_type[&OperationType::BrokerFee].len());
+        assert_eq!(2, by_type[&OperationType::Sell].len());
+        assert_eq!(1, by_type[&OperationType::Input].len());
+        assert!(!by_type.contains_key(&OperationType::Coupon));
+    }
+
+    #[test]
+    fn operations_request_fields() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let request = OperationsRequestBuilder::new("2000123456")
+            .from_date(date(1))
+            .to_date(date(31))
+            .figi("BBG004730N88")
+            .state(OperationState::Executed)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            OperationsRequest {
+                account_id: "2000123456".to_string(),
+                from: Some(datetime_utc_to_timestamp(start_of_day(date(1)))),
+                to: Some(datetime_utc_to_timestamp(start_of_day(
+                    NaiveDate::from_ymd_opt(2024, 4, 1).unwrap()
+                ))),
+                state: Some(OperationState::Executed as i32),
+                figi: Some("BBG004730N88".to_string()),
+            },
+            request
+        );
+        assert_eq!(
+            OperationsRequest {
+                account_id: "2000123456".to_string(),
+                ..Default::default()
+            },
+            OperationsRequestBuilder::new("2000123456").build().unwrap()
+        );
+
+        let from = start_of_day(date(10));
+        assert_eq!(
+            Err(BuilderError::InvalidPeriod { from, to: from }),
+            OperationsRequestBuilder::new("2000123456")
+                .from_datetime(from)
+                .to_datetime(from)
+                .build()
+        );
+        assert!(
+            OperationsRequestBuilder::new("2000123456")
+                .from_date(date(10))
+                .to_date(date(9))
+                .build()
+                .is_err()
+        );
+        assert!(
+            OperationsRequestBuilder::new("2000123456")
+                .from_date(date(10))
+                .to_date(date(10))
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn cursor_request_fields() {
+        let from = start_of_day(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        let to = from + TimeDelta::hours(12);
+        let request = GetOperationsByCursorRequestBuilder::new("2000123456")
+            .from_datetime(from)
+            .to_datetime(to)
+            .instrument_id("e6123145-9665-43e0-8413-cd61b8aa9b13")
+            .operation_types(vec![OperationType::Buy, OperationType::Sell])
+            .state(OperationState::Executed)
+            .cursor("next")
+            .limit(100)
+            .without_commissions(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            GetOperationsByCursorRequest {
+                account_id: "2000123456".to_string(),
+                instrument_id: Some("e6123145-9665-43e0-8413-cd61b8aa9b13".to_string()),
+                from: Some(datetime_utc_to_timestamp(from)),
+                to: Some(datetime_utc_to_timestamp(to)),
+                cursor: Some("next".to_string()),
+                limit: Some(100),
+                operation_types: vec![OperationType::Buy as i32, OperationType::Sell as i32],
+                state: Some(OperationState::Executed as i32),
+                without_commissions: Some(true),
+                without_trades: None,
+                without_overnights: None,
+            },
+            request
+        );
+        assert_eq!(
+            Err(BuilderError::InvalidPeriod { from: to, to: from }),
+            GetOperationsByCursorRequestBuilder::new("2000123456")
+                .from_datetime(to)
+                .to_datetime(from)
+                .build()
+        );
+    }
+}
@@ -0,0 +1,468 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    self, FilterOptionsRequest, OptionDirection, OrderDirection,
+    instruments_service_client::InstrumentsServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor, timestamp_to_datetime_utc};
+
+/// Опционы колл и пут с одним страйком.
+pub type StrikeOptions = (Option<api::Option>, Option<api::Option>);
+
+/// Опционная доска по базовому активу.
+///
+/// Опционы сгруппированы по дате экспирации и страйку. Для каждого страйка
+/// хранится пара `(колл, пут)`, сторона отсутствует, если такого опциона нет.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OptionChainSnapshot {
+    /// FIGI-идентификатор базового актива
+    pub underlying_figi: String,
+    /// Опционы по дате экспирации и страйку
+    pub expirations: BTreeMap<NaiveDate, BTreeMap<Decimal, StrikeOptions>>,
+}
+
+impl OptionChainSnapshot {
+    /// Загружает все опционы на базовый актив.
+    ///
+    /// # Аргументы
+    /// * `client` - Клиент сервиса инструментов
+    /// * `underlying_figi` - FIGI базового актива
+    pub async fn load(
+        client: &mut InstrumentsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+        underlying_figi: &str,
+    ) -> Result<Self, TInvestError> {
+        let options = client
+            .options_by(FilterOptionsRequest {
+                basic_instrument_id: Some(underlying_figi.to_string()),
+                ..Default::default()
+            })
+            .await?
+            .into_inner()
+            .instruments;
+
+        Ok(Self::from_options(underlying_figi, options))
+    }
+
+    /// Строит опционную доску из списка опционов.
+    ///
+    /// Опционы без даты экспирации, страйка или направления пропускаются.
+    pub fn from_options(underlying_figi: &str, options: Vec<api::Option>) -> Self {
+        let mut expirations: BTreeMap<NaiveDate, BTreeMap<Decimal, StrikeOptions>> =
+            BTreeMap::new();
+
+        for option in options {
+            let Some(expiration) = option
+                .expiration_date
+                .and_then(|date| timestamp_to_datetime_utc(date).ok())
+                .map(|date| date.date_naive())
+            else {
+                continue;
+            };
+            let Some(strike) = option.strike_price.clone().map(Decimal::from) else {
+                continue;
+            };
+
+            let (call, put) = expirations
+                .entry(expiration)
+                .or_default()
+                .entry(strike.normalize())
+                .or_default();
+            match option.direction() {
+                OptionDirection::Call => *call = Some(option),
+                OptionDirection::Put => *put = Some(option),
+                OptionDirection::Unspecified => {}
+            }
+        }
+
+        expirations.retain(|_, strikes| {
+            strikes.retain(|_, (call, put)| call.is_some() || put.is_some());
+            !strikes.is_empty()
+        });
+
+        Self {
+            underlying_figi: underlying_figi.to_string(),
+            expirations,
+        }
+    }
+
+    /// Возвращает страйки, ближайшие к цене базового актива.
+    ///
+    /// Для каждой даты экспирации выбирается страйк с минимальным отклонением
+    /// от `spot` (при равном отклонении — оба). Результат отсортирован и не содержит повторов.
+    pub fn at_the_money_strikes(&self, spot: Decimal) -> Vec<Decimal> {
+        let mut strikes: Vec<Decimal> = self
+            .expirations
+            .values()
+            .flat_map(|chain| {
+                let distance = chain.keys().map(|strike| (strike - spot).abs()).min();
+                chain
+                    .keys()
+                    .filter(move |strike| Some((*strike - spot).abs()) == distance)
+                    .copied()
+            })
+            .collect();
+
+        strikes.sort();
+        strikes.dedup();
+        strikes
+    }
+}
+
+/// Нога опционной стратегии.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Leg {
+    direction: OrderDirection,
+    option_type: OptionDirection,
+    strike: Decimal,
+    premium: Decimal,
+    quantity: i64,
+}
+
+impl Leg {
+    /// Возвращает `1` для покупки и `-1` для продажи опциона.
+    fn sign(&self) -> Decimal {
+        match self.direction {
+            OrderDirection::Sell => Decimal::NEGATIVE_ONE,
+            _ => Decimal::ONE,
+        }
+    }
+
+    fn payoff_at(&self, spot: Decimal) -> Decimal {
+        let intrinsic = match self.option_type {
+            OptionDirection::Call => (spot - self.strike).max(Decimal::ZERO),
+            OptionDirection::Put => (self.strike - spot).max(Decimal::ZERO),
+            OptionDirection::Unspecified => Decimal::ZERO,
+        };
+
+        self.sign() * (intrinsic - self.premium) * Decimal::from(self.quantity)
+    }
+}
+
+/// Расчёт профиля доходности опционных спредов на дату экспирации.
+///
+/// Доходность ноги равна `(внутренняя стоимость - премия) * количество` для
+/// купленного опциона и противоположна для проданного. Премия и доходность
+/// указываются на один базовый актив, размер контракта не учитывается.
+/// Цена базового актива считается неотрицательной.
+///
+/// ```
+/// # use rust_decimal_macros::dec;
+/// # use t_invest_sdk::SpreadCalculator;
+/// # use t_invest_sdk::api::{OptionDirection, OrderDirection};
+/// // Бычий колл-спред 100/110
+/// let mut spread = SpreadCalculator::new();
+/// spread
+///     .add_leg(OrderDirection::Buy, OptionDirection::Call, dec!(100), dec!(5), 1)
+///     .add_leg(OrderDirection::Sell, OptionDirection::Call, dec!(110), dec!(2), 1);
+///
+/// assert_eq!(vec![dec!(103)], spread.breakeven_prices());
+/// assert_eq!(dec!(7), spread.max_profit());
+/// assert_eq!(dec!(3), spread.max_loss());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SpreadCalculator {
+    legs: Vec<Leg>,
+}
+
+impl SpreadCalculator {
+    /// Создаёт стратегию без ног.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет ногу стратегии.
+    ///
+    /// # Аргументы
+    /// * `direction` - Покупка или продажа опциона
+    /// * `option_type` - Колл или пут
+    /// * `strike` - Цена исполнения
+    /// * `premium` - Премия за один опцион
+    /// * `quantity` - Количество опционов
+    pub fn add_leg(
+        &mut self,
+        direction: OrderDirection,
+        option_type: OptionDirection,
+        strike: Decimal,
+        premium: Decimal,
+        quantity: i64,
+    ) -> &mut Self {
+        self.legs.push(Leg {
+            direction,
+            option_type,
+            strike,
+            premium,
+            quantity,
+        });
+        self
+    }
+
+    /// Возвращает доходность стратегии при цене базового актива `spot` на экспирации.
+    pub fn payoff_at(&self, spot: Decimal) -> Decimal {
+        self.legs.iter().map(|leg| leg.payoff_at(spot)).sum()
+    }
+
+    /// Возвращает цены безубыточности в порядке возрастания.
+    pub fn breakeven_prices(&self) -> Vec<Decimal> {
+        if self.legs.is_empty() {
+            return Vec::new();
+        }
+
+        let points = self.critical_points();
+        let mut prices = Vec::new();
+
+        for window in points.windows(2) {
+            let (from, to) = (window[0], window[1]);
+            let (from_payoff, to_payoff) = (self.payoff_at(from), self.payoff_at(to));
+
+            if from_payoff.is_zero() {
+                prices.push(from);
+            } else if from_payoff.is_sign_negative() != to_payoff.is_sign_negative()
+                && !to_payoff.is_zero()
+            {
+                prices.push(from + (to - from) * from_payoff / (from_payoff - to_payoff));
+            }
+        }
+
+        let last = *points.last().unwrap_or(&Decimal::ZERO);
+        let last_payoff = self.payoff_at(last);
+        let slope = self.tail_slope();
+        if last_payoff.is_zero() {
+            prices.push(last);
+        } else if !slope.is_zero() && last_payoff.is_sign_negative() != slope.is_sign_negative() {
+            prices.push(last - last_payoff / slope);
+        }
+
+        prices.dedup();
+        prices
+    }
+
+    /// Возвращает максимальную доходность стратегии.
+    ///
+    /// Для стратегий с неограниченной доходностью возвращается `Decimal::MAX`.
+    pub fn max_profit(&self) -> Decimal {
+        if self.tail_slope() > Decimal::ZERO {
+            return Decimal::MAX;
+        }
+
+        self.critical_points()
+            .into_iter()
+            .map(|spot| self.payoff_at(spot))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Возвращает максимальный убыток стратегии в виде положительного числа.
+    ///
+    /// Для стратегий с неограниченным убытком возвращается `Decimal::MAX`.
+    pub fn max_loss(&self) -> Decimal {
+        if self.tail_slope() < Decimal::ZERO {
+            return Decimal::MAX;
+        }
+
+        self.critical_points()
+            .into_iter()
+            .map(|spot| -self.payoff_at(spot))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Возвращает ноль и страйки ног в порядке возрастания.
+    ///
+    /// Профиль доходности линеен между соседними точками.
+    fn critical_points(&self) -> Vec<Decimal> {
+        let mut points: Vec<Decimal> = std::iter::once(Decimal::ZERO)
+            .chain(
+                self.legs
+                    .iter()
+                    .map(|leg| leg.strike)
+                    .filter(|strike| strike.is_sign_positive()),
+            )
+            .collect();
+        points.sort();
+        points.dedup();
+        points
+    }
+
+    /// Возвращает наклон профиля доходности правее максимального страйка.
+    fn tail_slope(&self) -> Decimal {
+        self.legs
+            .iter()
+            .filter(|leg| leg.option_type == OptionDirection::Call)
+            .map(|leg| leg.sign() * Decimal::from(leg.quantity))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, Quotation};
+    use crate::datetime_utc_to_timestamp;
+
+    fn option(day: u32, strike: Decimal, direction: OptionDirection) -> api::Option {
+        let strike = Quotation::try_from(strike).unwrap();
+        api::Option {
+            uid: format!("{}-{}-{:?}", day, strike.units, direction),
+            direction: direction.into(),
+            strike_price: Some(MoneyValue {
+                currency: "rub".to_string(),
+                units: strike.units,
+                nano: strike.nano,
+            }),
+            expiration_date: Some(datetime_utc_to_timestamp(
+                Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap(),
+            )),
+            ..Default::default()
+        }
+    }
+
+    fn chain() -> OptionChainSnapshot {
+        OptionChainSnapshot::from_options(
+            "BBG004730N88",
+            vec![
+                option(20, dec!(300), OptionDirection::Call),
+                option(20, dec!(300), OptionDirection::Put),
+                option(20, dec!(310), OptionDirection::Call),
+                option(20, dec!(320), OptionDirection::Put),
+                option(27, dec!(290), OptionDirection::Call),
+                option(27, dec!(330), OptionDirection::Put),
+                option(27, dec!(400), OptionDirection::Unspecified),
+                api::Option::default(),
+            ],
+        )
+    }
+
+    #[test]
+    fn groups_by_expiry_and_strike() {
+        let chain = chain();
+        let june_20 = NaiveDate::from_ymd_opt(2024, 6, 20).unwrap();
+        let june_27 = NaiveDate::from_ymd_opt(2024, 6, 27).unwrap();
+
+        assert_eq!(
+            vec![june_20, june_27],
+            chain.expirations.keys().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![dec!(300), dec!(310), dec!(320)],
+            chain.expirations[&june_20]
+                .keys()
+                .copied()
+                .collect::<Vec<_>>()
+        );
+
+        let (call, put) = &chain.expirations[&june_20][&dec!(300)];
+        assert_eq!(OptionDirection::Call, call.as_ref().unwrap().direction());
+        assert_eq!(OptionDirection::Put, put.as_ref().unwrap().direction());
+
+        let (call, put) = &chain.expirations[&june_20][&dec!(320)];
+        assert!(call.is_none());
+        assert!(put.is_some());
+
+        assert_eq!(2, chain.expirations[&june_27].len());
+    }
+
+    #[test]
+    fn at_the_money() {
+        let chain = chain();
+
+        assert_eq!(
+            vec![dec!(290), dec!(310)],
+            chain.at_the_money_strikes(dec!(308))
+        );
+        assert_eq!(
+            vec![dec!(290), dec!(310), dec!(330)],
+            chain.at_the_money_strikes(dec!(310))
+        );
+        assert!(
+            OptionChainSnapshot::default()
+                .at_the_money_strikes(dec!(100))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn iron_condor() {
+        let mut condor = SpreadCalculator::new();
+        condor
+            .add_leg(
+                OrderDirection::Buy,
+                OptionDirection::Put,
+                dec!(90),
+                dec!(1),
+                1,
+            )
+            .add_leg(
+                OrderDirection::Sell,
+                OptionDirection::Put,
+                dec!(95),
+                dec!(3),
+                1,
+            )
+            .add_leg(
+                OrderDirection::Sell,
+                OptionDirection::Call,
+                dec!(105),
+                dec!(3),
+                1,
+            )
+            .add_leg(
+                OrderDirection::Buy,
+                OptionDirection::Call,
+                dec!(110),
+                dec!(1),
+                1,
+            );
+
+        assert_eq!(dec!(4), condor.payoff_at(dec!(100)));
+        assert_eq!(dec!(-1), condor.payoff_at(dec!(50)));
+        assert_eq!(dec!(-1), condor.payoff_at(dec!(200)));
+        assert_eq!(vec![dec!(91), dec!(109)], condor.breakeven_prices());
+        assert_eq!(dec!(4), condor.max_profit());
+        assert_eq!(dec!(1), condor.max_loss());
+    }
+
+    #[test]
+    fn unbounded_payoffs() {
+        let mut long_call = SpreadCalculator::new();
+        long_call.add_leg(
+            OrderDirection::Buy,
+            OptionDirection::Call,
+            dec!(100),
+            dec!(5),
+            2,
+        );
+        assert_eq!(dec!(10), long_call.payoff_at(dec!(110)));
+        assert_eq!(vec![dec!(105)], long_call.breakeven_prices());
+        assert_eq!(Decimal::MAX, long_call.max_profit());
+        assert_eq!(dec!(10), long_call.max_loss());
+
+        let mut short_straddle = SpreadCalculator::new();
+        short_straddle
+            .add_leg(
+                OrderDirection::Sell,
+                OptionDirection::Call,
+                dec!(100),
+                dec!(4),
+                1,
+            )
+            .add_leg(
+                OrderDirection::Sell,
+                OptionDirection::Put,
+                dec!(100),
+                dec!(6),
+                1,
+            );
+        assert_eq!(vec![dec!(90), dec!(110)], short_straddle.breakeven_prices());
+        assert_eq!(dec!(10), short_straddle.max_profit());
+        assert_eq!(Decimal::MAX, short_straddle.max_loss());
+
+        assert!(SpreadCalculator::new().breakeven_prices().is_empty());
+    }
+}
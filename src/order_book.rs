@@ -0,0 +1,166 @@
+use prost_types::Timestamp;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::api::{GetOrderBookResponse, Order, Quotation};
+
+/// Снимок биржевого стакана.
+///
+/// Заявки на покупку и продажу хранятся в порядке, в котором их возвращает API:
+/// от лучшей цены к худшей.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBookSnapshot {
+    /// FIGI-идентификатор инструмента
+    pub figi: String,
+    /// UID инструмента
+    pub instrument_uid: String,
+    /// Глубина стакана
+    pub depth: i32,
+    /// Заявки на покупку
+    pub bids: Vec<Order>,
+    /// Заявки на продажу
+    pub asks: Vec<Order>,
+    /// Время формирования стакана на бирже
+    pub time: Option<Timestamp>,
+}
+
+impl OrderBookSnapshot {
+    /// Возвращает лучшую цену покупки.
+    pub fn best_bid(&self) -> Option<Quotation> {
+        best_price(&self.bids)
+    }
+
+    /// Возвращает лучшую цену продажи.
+    pub fn best_ask(&self) -> Option<Quotation> {
+        best_price(&self.asks)
+    }
+
+    /// Возвращает среднюю цену между лучшими ценами покупки и продажи.
+    ///
+    /// Возвращает `None`, если одна из сторон стакана пуста.
+    pub fn mid_price(&self) -> Option<Decimal> {
+        let (bid, ask) = self.best_prices()?;
+        Some((bid + ask) / Decimal::TWO)
+    }
+
+    /// Возвращает разницу между лучшими ценами продажи и покупки.
+    ///
+    /// Возвращает `None`, если одна из сторон стакана пуста.
+    pub fn spread(&self) -> Option<Decimal> {
+        let (bid, ask) = self.best_prices()?;
+        Some(ask - bid)
+    }
+
+    /// Возвращает объём в лотах на первых `levels` уровнях покупки.
+    pub fn bid_volume(&self, levels: usize) -> Decimal {
+        volume(&self.bids, levels)
+    }
+
+    /// Возвращает объём в лотах на первых `levels` уровнях продажи.
+    pub fn ask_volume(&self, levels: usize) -> Decimal {
+        volume(&self.asks, levels)
+    }
+
+    /// Возвращает долю объёма покупки в общем объёме стакана.
+    ///
+    /// Значение больше 0.5 означает перевес покупателей. Для пустого стакана возвращается 0.5.
+    pub fn imbalance(&self) -> f64 {
+        let bid_volume = self.bid_volume(self.bids.len());
+        let total = bid_volume + self.ask_volume(self.asks.len());
+        if total.is_zero() {
+            return 0.5;
+        }
+
+        (bid_volume / total).to_f64().unwrap_or(0.5)
+    }
+
+    fn best_prices(&self) -> Option<(Decimal, Decimal)> {
+        Some((self.best_bid()?.into(), self.best_ask()?.into()))
+    }
+}
+
+impl From<GetOrderBookResponse> for OrderBookSnapshot {
+    fn from(response: GetOrderBookResponse) -> Self {
+        Self {
+            figi: response.figi,
+            instrument_uid: response.instrument_uid,
+            depth: response.depth,
+            bids: response.bids,
+            asks: response.asks,
+            time: response.orderbook_ts,
+        }
+    }
+}
+
+fn best_price(orders: &[Order]) -> Option<Quotation> {
+    orders.first().and_then(|order| order.price)
+}
+
+fn volume(orders: &[Order], levels: usize) -> Decimal {
+    orders
+        .iter()
+        .take(levels)
+        .map(|order| Decimal::from(order.quantity))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn order(price: Decimal, quantity: i64) -> Order {
+        Order {
+            price: Some(price.try_into().unwrap()),
+            quantity,
+        }
+    }
+
+    fn snapshot(bids: Vec<Order>, asks: Vec<Order>) -> OrderBookSnapshot {
+        GetOrderBookResponse {
+            bids,
+            asks,
+            ..Default::default()
+        }
+        .into()
+    }
+
+    #[test]
+    fn empty_book() {
+        let book = snapshot(vec![], vec![]);
+
+        assert_eq!(None, book.best_bid());
+        assert_eq!(None, book.best_ask());
+        assert_eq!(None, book.mid_price());
+        assert_eq!(None, book.spread());
+        assert_eq!(Decimal::ZERO, book.bid_volume(10));
+        assert_eq!(0.5, book.imbalance());
+    }
+
+    #[test]
+    fn one_sided_book() {
+        let book = snapshot(vec![order(dec!(99.5), 10), order(dec!(99), 5)], vec![]);
+
+        assert_eq!(Some(dec!(99.5).try_into().unwrap()), book.best_bid());
+        assert_eq!(None, book.best_ask());
+        assert_eq!(None, book.mid_price());
+        assert_eq!(None, book.spread());
+        assert_eq!(dec!(10), book.bid_volume(1));
+        assert_eq!(dec!(15), book.bid_volume(10));
+        assert_eq!(1.0, book.imbalance());
+    }
+
+    #[test]
+    fn balanced_book() {
+        let book = snapshot(
+            vec![order(dec!(99.5), 10), order(dec!(99), 30)],
+            vec![order(dec!(100.5), 20), order(dec!(101), 20)],
+        );
+
+        assert_eq!(Some(dec!(100)), book.mid_price());
+        assert_eq!(Some(dec!(1)), book.spread());
+        assert_eq!(dec!(40), book.ask_volume(2));
+        assert_eq!(0.5, book.imbalance());
+    }
+}
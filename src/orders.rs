@@ -0,0 +1,1388 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::future::join_all;
+use futures::{Stream, StreamExt};
+use rust_decimal::Decimal;
+use tonic::codegen::{Body, Bytes, StdError};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    CancelOrderRequest, CancelStopOrderRequest, GetOrderStateRequest, GetOrdersRequest,
+    GetStopOrdersRequest, OrderDirection, OrderExecutionReportStatus, OrderState,
+    OrderStateStreamRequest, OrderStateStreamResponse, OrderType, PostOrderRequest,
+    PostOrderResponse, PostStopOrderRequest, PriceType, Quotation, StopOrderDirection,
+    StopOrderExpirationType, StopOrderStatusOption, StopOrderType, TimeInForceType,
+    order_state_stream_response, orders_service_client::OrdersServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor, TInvestSdk};
+
+/// Время хранения ответов [`IdempotentOrderPlacer`] по умолчанию.
+const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+type Responses = HashMap<String, (DateTime<Utc>, PostOrderResponse)>;
+
+/// Идентификаторы заявок, выставленных [`TInvestSdk::place_bracket_order`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BracketOrderIds {
+    /// Идентификатор лимитной заявки на вход в позицию
+    pub entry_order_id: String,
+    /// Идентификатор стоп-заявки take-profit
+    pub take_profit_stop_id: String,
+    /// Идентификатор стоп-заявки stop-loss
+    pub stop_loss_stop_id: String,
+}
+
+impl TInvestSdk {
+    /// Выставляет bracket-заявку: лимитную заявку на вход и две стоп-заявки на выход.
+    ///
+    /// Стоп-заявки take-profit и stop-loss выставляются в обратном направлении
+    /// на то же количество лотов и действуют до отмены.
+    ///
+    /// API не связывает эти заявки между собой. Вызывающий код сам отвечает
+    /// за отмену оставшихся заявок после исполнения одной из стоп-заявок,
+    /// а также стоп-заявок, если заявка на вход не исполнилась.
+    ///
+    /// # Аргументы
+    /// * `account_id` - Номер счёта
+    /// * `figi` - FIGI-идентификатор инструмента
+    /// * `direction` - Направление заявки на вход
+    /// * `quantity` - Количество лотов
+    /// * `entry_price` - Цена заявки на вход
+    /// * `take_profit_price` - Цена активации take-profit
+    /// * `stop_loss_price` - Цена активации stop-loss
+    ///
+    /// # Ошибки
+    /// Возвращает ошибку, если направление не указано, цену не удалось представить
+    /// в виде `Quotation` или API отклонило одну из заявок. Если ошибка произошла
+    /// после выставления заявки на вход, уже выставленные заявки не отменяются.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_bracket_order(
+        &self,
+        account_id: &str,
+        figi: &str,
+        direction: OrderDirection,
+        quantity: i64,
+        entry_price: Decimal,
+        take_profit_price: Decimal,
+        stop_loss_price: Decimal,
+    ) -> Result<BracketOrderIds, TInvestError> {
+        let (entry, take_profit, stop_loss) = bracket_requests(
+            account_id,
+            figi,
+            direction,
+            quantity,
+            entry_price,
+            take_profit_price,
+            stop_loss_price,
+        )?;
+
+        let entry_order_id = self.orders().post_order(entry).await?.into_inner().order_id;
+
+        let mut stop_orders = self.stop_orders();
+        let take_profit_stop_id = stop_orders
+            .post_stop_order(take_profit)
+            .await?
+            .into_inner()
+            .stop_order_id;
+        let stop_loss_stop_id = stop_orders
+            .post_stop_order(stop_loss)
+            .await?
+            .into_inner()
+            .stop_order_id;
+
+        Ok(BracketOrderIds {
+            entry_order_id,
+            take_profit_stop_id,
+            stop_loss_stop_id,
+        })
+    }
+}
+
+/// Идентификаторы стоп-заявок, выставленных [`place_stop_pair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StopOrderPair {
+    /// Идентификатор стоп-заявки stop-loss
+    pub stop_loss_id: String,
+    /// Идентификатор стоп-заявки take-profit
+    pub take_profit_id: String,
+}
+
+/// Выставляет stop-loss и take-profit для открытой позиции.
+///
+/// Направление стоп-заявок определяется знаком `quantity`: для длинной позиции
+/// (положительное количество) выставляются заявки на продажу, для короткой —
+/// на покупку. Заявки действуют до отмены.
+///
+/// Сначала выставляется stop-loss, затем take-profit. Если take-profit выставить
+/// не удалось, stop-loss отменяется и возвращается ошибка выставления take-profit.
+/// Откат не атомарен: если stop-loss успеет исполниться до отмены, позиция будет
+/// закрыта, а ошибка отмены не возвращается.
+///
+/// # Аргументы
+/// * `sdk` - Экземпляр SDK
+/// * `account_id` - Номер счёта
+/// * `figi` - FIGI-идентификатор инструмента
+/// * `quantity` - Количество лотов в позиции, отрицательное для короткой позиции
+/// * `stop_loss` - Цена активации stop-loss
+/// * `take_profit` - Цена активации take-profit
+pub async fn place_stop_pair(
+    sdk: &TInvestSdk,
+    account_id: &str,
+    figi: &str,
+    quantity: i64,
+    stop_loss: Quotation,
+    take_profit: Quotation,
+) -> Result<StopOrderPair, TInvestError> {
+    let direction = match quantity.signum() {
+        1 => StopOrderDirection::Sell,
+        -1 => StopOrderDirection::Buy,
+        _ => {
+            return Err(TInvestError::InvalidArgument(
+                "Position quantity is zero".to_string(),
+            ));
+        }
+    };
+    let quantity = quantity.abs();
+
+    let mut client = sdk.stop_orders();
+    let stop_loss_id = client
+        .post_stop_order(stop_order_request(
+            account_id,
+            figi,
+            direction,
+            quantity,
+            StopOrderType::StopLoss,
+            stop_loss,
+        ))
+        .await?
+        .into_inner()
+        .stop_order_id;
+
+    let take_profit = client
+        .post_stop_order(stop_order_request(
+            account_id,
+            figi,
+            direction,
+            quantity,
+            StopOrderType::TakeProfit,
+            take_profit,
+        ))
+        .await;
+
+    match take_profit {
+        Ok(response) => Ok(StopOrderPair {
+            stop_loss_id,
+            take_profit_id: response.into_inner().stop_order_id,
+        }),
+        Err(status) => {
+            let _ = client
+                .cancel_stop_order(CancelStopOrderRequest {
+                    account_id: account_id.to_string(),
+                    stop_order_id: stop_loss_id,
+                })
+                .await;
+            Err(status.into())
+        }
+    }
+}
+
+/// Отменяет обе стоп-заявки, выставленные [`place_stop_pair`].
+///
+/// Отмена take-profit выполняется, даже если отменить stop-loss не удалось;
+/// возвращается первая возникшая ошибка.
+pub async fn cancel_stop_pair(
+    sdk: &TInvestSdk,
+    account_id: &str,
+    pair: &StopOrderPair,
+) -> Result<(), TInvestError> {
+    let mut client = sdk.stop_orders();
+    let mut cancel = async |stop_order_id: &str| {
+        client
+            .cancel_stop_order(CancelStopOrderRequest {
+                account_id: account_id.to_string(),
+                stop_order_id: stop_order_id.to_string(),
+            })
+            .await
+    };
+
+    let stop_loss = cancel(&pair.stop_loss_id).await;
+    let take_profit = cancel(&pair.take_profit_id).await;
+    stop_loss?;
+    take_profit?;
+
+    Ok(())
+}
+
+/// Результат массовой отмены заявок.
+#[derive(Debug, Default)]
+pub struct CancelAllResult {
+    /// Идентификаторы отменённых заявок
+    pub cancelled: Vec<String>,
+    /// Идентификаторы заявок, которые не удалось отменить, с ошибками
+    pub failed: Vec<(String, TInvestError)>,
+}
+
+/// Отменяет все активные заявки по счёту.
+///
+/// Заявки отменяются параллельно. Ошибка отмены одной заявки не прерывает
+/// отмену остальных и попадает в [`CancelAllResult::failed`].
+///
+/// # Ошибки
+/// Возвращает ошибку, если не удалось получить список заявок
+pub async fn cancel_all_orders(
+    sdk: &TInvestSdk,
+    account_id: &str,
+) -> Result<CancelAllResult, TInvestError> {
+    let client = sdk.orders();
+    let order_ids = client
+        .clone()
+        .get_orders(GetOrdersRequest {
+            account_id: account_id.to_string(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .orders
+        .into_iter()
+        .map(|order| order.order_id);
+
+    Ok(cancel_each(order_ids, |order_id| {
+        let mut client = client.clone();
+        async move {
+            client
+                .cancel_order(CancelOrderRequest {
+                    account_id: account_id.to_string(),
+                    order_id,
+                    ..Default::default()
+                })
+                .await
+                .map(|_| ())
+        }
+    })
+    .await)
+}
+
+/// Отменяет все активные стоп-заявки по счёту.
+///
+/// Работает так же, как [`cancel_all_orders`].
+pub async fn cancel_all_stop_orders(
+    sdk: &TInvestSdk,
+    account_id: &str,
+) -> Result<CancelAllResult, TInvestError> {
+    let client = sdk.stop_orders();
+    let stop_order_ids = client
+        .clone()
+        .get_stop_orders(GetStopOrdersRequest {
+            account_id: account_id.to_string(),
+            status: StopOrderStatusOption::StopOrderStatusActive.into(),
+            ..Default::default()
+        })
+        .await?
+        .into_inner()
+        .stop_orders
+        .into_iter()
+        .map(|stop_order| stop_order.stop_order_id);
+
+    Ok(cancel_each(stop_order_ids, |stop_order_id| {
+        let mut client = client.clone();
+        async move {
+            client
+                .cancel_stop_order(CancelStopOrderRequest {
+                    account_id: account_id.to_string(),
+                    stop_order_id,
+                })
+                .await
+                .map(|_| ())
+        }
+    })
+    .await)
+}
+
+/// Параллельно выполняет отмену для каждого идентификатора и разделяет результаты.
+async fn cancel_each<F, Fut>(ids: impl IntoIterator<Item = String>, cancel: F) -> CancelAllResult
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<(), tonic::Status>>,
+{
+    let results = join_all(ids.into_iter().map(|id| {
+        let cancelled = cancel(id.clone());
+        async move { (id, cancelled.await) }
+    }))
+    .await;
+
+    let mut result = CancelAllResult::default();
+    for (id, cancelled) in results {
+        match cancelled {
+            Ok(()) => result.cancelled.push(id),
+            Err(status) => result.failed.push((id, status.into())),
+        }
+    }
+
+    result
+}
+
+/// Ожидает завершения заявки, периодически запрашивая её состояние.
+///
+/// Завершёнными считаются исполненные, отклонённые и отменённые заявки.
+/// Первый запрос выполняется сразу, следующие — с интервалом `poll_interval`.
+/// Ошибка запроса состояния прерывает ожидание.
+///
+/// # Аргументы
+/// * `sdk` - Экземпляр SDK
+/// * `account_id` - Номер счёта
+/// * `order_id` - Биржевой идентификатор заявки
+/// * `poll_interval` - Интервал между запросами, больше нуля
+/// * `timeout` - Максимальное время ожидания
+///
+/// # Ошибки
+/// Возвращает `TInvestError::Timeout`, если заявка не завершилась за `timeout`
+pub async fn wait_for_order_completion(
+    sdk: &TInvestSdk,
+    account_id: &str,
+    order_id: &str,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<OrderState, TInvestError> {
+    if poll_interval.is_zero() {
+        return Err(TInvestError::InvalidArgument(
+            "Poll interval must be positive".to_string(),
+        ));
+    }
+
+    let client = sdk.orders();
+    poll_until_completed(
+        || get_order_state(client.clone(), account_id, order_id),
+        poll_interval,
+        timeout,
+    )
+    .await
+}
+
+/// Ожидает завершения заявки по стриму состояний заявок.
+///
+/// После подписки состояние заявки запрашивается один раз, чтобы не пропустить
+/// заявку, завершившуюся до подписки. Полное состояние завершённой заявки
+/// запрашивается через `GetOrderState`.
+///
+/// # Ошибки
+/// Возвращает `TInvestError::Timeout`, если заявка не завершилась за `timeout`,
+/// ошибку стрима, если он прервался раньше, и `TInvestError::StreamClosed`,
+/// если сервер закрыл стрим до завершения заявки
+pub async fn wait_for_order_completion_stream(
+    sdk: &TInvestSdk,
+    account_id: &str,
+    order_id: &str,
+    timeout: Duration,
+) -> Result<OrderState, TInvestError> {
+    let client = sdk.orders();
+    let waiting = async {
+        let stream = sdk
+            .orders_stream()
+            .order_state_stream(OrderStateStreamRequest {
+                accounts: vec![account_id.to_string()],
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        wait_in_stream(stream, order_id, || {
+            get_order_state(client.clone(), account_id, order_id)
+        })
+        .await
+    };
+
+    tokio::time::timeout(timeout, waiting)
+        .await
+        .map_err(|_| TInvestError::Timeout(timeout))?
+}
+
+async fn get_order_state(
+    mut client: OrdersServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    account_id: &str,
+    order_id: &str,
+) -> Result<OrderState, tonic::Status> {
+    client
+        .get_order_state(GetOrderStateRequest {
+            account_id: account_id.to_string(),
+            order_id: order_id.to_string(),
+            ..Default::default()
+        })
+        .await
+        .map(tonic::Response::into_inner)
+}
+
+/// Ожидает в стриме завершения заявки, предварительно запросив её состояние.
+async fn wait_in_stream<S, F, Fut>(
+    mut stream: S,
+    order_id: &str,
+    mut get_state: F,
+) -> Result<OrderState, TInvestError>
+where
+    S: Stream<Item = Result<OrderStateStreamResponse, tonic::Status>> + Unpin,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<OrderState, tonic::Status>>,
+{
+    let state = get_state().await?;
+    if is_completed(state.execution_report_status()) {
+        return Ok(state);
+    }
+
+    while let Some(message) = stream.next().await {
+        if let Some(order_state_stream_response::Payload::OrderState(update)) = message?.payload
+            && update.order_id == order_id
+            && is_completed(update.execution_report_status())
+        {
+            return Ok(get_state().await?);
+        }
+    }
+
+    Err(TInvestError::StreamClosed)
+}
+
+/// Запрашивает состояние заявки с интервалом, пока она не завершится.
+async fn poll_until_completed<F, Fut>(
+    mut poll: F,
+    poll_interval: Duration,
+    timeout: Duration,
+) -> Result<OrderState, TInvestError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<OrderState, tonic::Status>>,
+{
+    let polling = async {
+        let mut ticks = tokio::time::interval(poll_interval);
+        loop {
+            ticks.tick().await;
+            let state = poll().await?;
+            if is_completed(state.execution_report_status()) {
+                return Ok(state);
+            }
+        }
+    };
+
+    tokio::time::timeout(timeout, polling)
+        .await
+        .map_err(|_| TInvestError::Timeout(timeout))?
+}
+
+fn is_completed(status: OrderExecutionReportStatus) -> bool {
+    matches!(
+        status,
+        OrderExecutionReportStatus::ExecutionReportStatusFill
+            | OrderExecutionReportStatus::ExecutionReportStatusRejected
+            | OrderExecutionReportStatus::ExecutionReportStatusCancelled
+    )
+}
+
+/// Проверки типа стоп-заявки без сравнения числовых значений.
+pub trait StopOrderTypeExt {
+    /// Проверяет, является ли заявка stop-limit.
+    fn is_stop_limit(&self) -> bool;
+
+    /// Проверяет, является ли заявка stop-loss, исполняемой по рыночной цене.
+    fn is_stop_market(&self) -> bool;
+
+    /// Проверяет, является ли заявка take-profit.
+    fn is_take_profit(&self) -> bool;
+
+    /// Возвращает название типа для отображения.
+    fn display_name(&self) -> &'static str;
+}
+
+impl StopOrderTypeExt for StopOrderType {
+    fn is_stop_limit(&self) -> bool {
+        *self == StopOrderType::StopLimit
+    }
+
+    fn is_stop_market(&self) -> bool {
+        *self == StopOrderType::StopLoss
+    }
+
+    fn is_take_profit(&self) -> bool {
+        *self == StopOrderType::TakeProfit
+    }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            StopOrderType::Unspecified => "Unspecified",
+            StopOrderType::TakeProfit => "Take-profit",
+            StopOrderType::StopLoss => "Stop-loss",
+            StopOrderType::StopLimit => "Stop-limit",
+        }
+    }
+}
+
+/// Упрощённое выставление рыночных и лимитных заявок для [`OrdersServiceClient`].
+///
+/// Вызывающий задаёт счёт, FIGI инструмента, количество лотов, `order_id`
+/// и цену для лимитных заявок. Остальные поля фиксированы: инструмент
+/// передаётся через `instrument_id`, для `time_in_force` и `price_type`
+/// используются значения API по умолчанию, `confirm_margin_trade` выключено.
+///
+/// ```no_run
+/// # use t_invest_sdk::{OrdersExt, TInvestSdk};
+/// # async fn run(sdk: TInvestSdk) -> Result<(), t_invest_sdk::TInvestError> {
+/// let order_id = uuid::Uuid::new_v4().to_string();
+/// let response = sdk
+///     .orders()
+///     .market_buy("account", "BBG004730N88", 1, &order_id)
+///     .await?;
+/// println!("{:?}", response.execution_report_status());
+/// # Ok(())
+/// # }
+/// ```
+pub trait OrdersExt {
+    /// Выставляет рыночную заявку на покупку `quantity` лотов.
+    fn market_buy(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        order_id: &str,
+    ) -> impl Future<Output = Result<PostOrderResponse, TInvestError>> + Send;
+
+    /// Выставляет рыночную заявку на продажу `quantity` лотов.
+    fn market_sell(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        order_id: &str,
+    ) -> impl Future<Output = Result<PostOrderResponse, TInvestError>> + Send;
+
+    /// Выставляет лимитную заявку на покупку `quantity` лотов по цене `price`.
+    fn limit_buy(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        price: Quotation,
+        order_id: &str,
+    ) -> impl Future<Output = Result<PostOrderResponse, TInvestError>> + Send;
+
+    /// Выставляет лимитную заявку на продажу `quantity` лотов по цене `price`.
+    fn limit_sell(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        price: Quotation,
+        order_id: &str,
+    ) -> impl Future<Output = Result<PostOrderResponse, TInvestError>> + Send;
+}
+
+impl<T> OrdersExt for OrdersServiceClient<T>
+where
+    T: tonic::client::GrpcService<tonic::body::Body> + Send,
+    T::Future: Send,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    async fn market_buy(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        order_id: &str,
+    ) -> Result<PostOrderResponse, TInvestError> {
+        let request = simple_order_request(
+            account_id,
+            figi,
+            OrderDirection::Buy,
+            quantity,
+            None,
+            order_id,
+        );
+        Ok(self.post_order(request).await?.into_inner())
+    }
+
+    async fn market_sell(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        order_id: &str,
+    ) -> Result<PostOrderResponse, TInvestError> {
+        let request = simple_order_request(
+            account_id,
+            figi,
+            OrderDirection::Sell,
+            quantity,
+            None,
+            order_id,
+        );
+        Ok(self.post_order(request).await?.into_inner())
+    }
+
+    async fn limit_buy(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        price: Quotation,
+        order_id: &str,
+    ) -> Result<PostOrderResponse, TInvestError> {
+        let request = simple_order_request(
+            account_id,
+            figi,
+            OrderDirection::Buy,
+            quantity,
+            Some(price),
+            order_id,
+        );
+        Ok(self.post_order(request).await?.into_inner())
+    }
+
+    async fn limit_sell(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        price: Quotation,
+        order_id: &str,
+    ) -> Result<PostOrderResponse, TInvestError> {
+        let request = simple_order_request(
+            account_id,
+            figi,
+            OrderDirection::Sell,
+            quantity,
+            Some(price),
+            order_id,
+        );
+        Ok(self.post_order(request).await?.into_inner())
+    }
+}
+
+/// Формирует запрос на рыночную заявку или, если указана цена, на лимитную.
+fn simple_order_request(
+    account_id: &str,
+    figi: &str,
+    direction: OrderDirection,
+    quantity: i64,
+    price: Option<Quotation>,
+    order_id: &str,
+) -> PostOrderRequest {
+    let order_type = match price {
+        Some(_) => OrderType::Limit,
+        None => OrderType::Market,
+    };
+
+    PostOrderRequest {
+        quantity,
+        price,
+        direction: direction.into(),
+        account_id: account_id.to_string(),
+        order_type: order_type.into(),
+        order_id: order_id.to_string(),
+        instrument_id: figi.to_string(),
+        time_in_force: TimeInForceType::TimeInForceUnspecified.into(),
+        price_type: PriceType::Unspecified.into(),
+        ..Default::default()
+    }
+}
+
+/// Выставляет заявки, защищая от повторной отправки заявки с тем же `order_id`.
+///
+/// Ответы на успешно выставленные заявки сохраняются по `order_id`. Повторный вызов
+/// с тем же `order_id` возвращает сохранённый ответ без обращения к API, например
+/// при повторе запроса после сетевой ошибки. Ответы хранятся в течение
+/// [`Self::with_retention`], по умолчанию сутки.
+///
+/// Заявки с пустым `order_id` отправляются без проверки. Одновременные вызовы с
+/// одинаковым `order_id` из разных задач могут оба дойти до API.
+/// Клоны разделяют общее хранилище ответов.
+#[derive(Clone)]
+pub struct IdempotentOrderPlacer {
+    client: OrdersServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    retention: Duration,
+    responses: Arc<Mutex<Responses>>,
+}
+
+impl IdempotentOrderPlacer {
+    /// Создаёт обёртку над клиентом сервиса Orders.
+    pub fn new(
+        client: OrdersServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    ) -> Self {
+        Self {
+            client,
+            retention: DEFAULT_RETENTION,
+            responses: Arc::default(),
+        }
+    }
+
+    /// Устанавливает время хранения ответов.
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Выставляет заявку или возвращает сохранённый ответ для её `order_id`.
+    pub async fn place_order(
+        &self,
+        request: PostOrderRequest,
+    ) -> Result<PostOrderResponse, TInvestError> {
+        if request.order_id.is_empty() {
+            return Ok(self.client.clone().post_order(request).await?.into_inner());
+        }
+
+        let retention = TimeDelta::from_std(self.retention).unwrap_or(TimeDelta::MAX);
+        {
+            let mut responses = self.lock();
+            let now = Utc::now();
+            responses.retain(|_, (placed_at, _)| now - *placed_at < retention);
+            if let Some((_, response)) = responses.get(&request.order_id) {
+                return Ok(response.clone());
+            }
+        }
+
+        let order_id = request.order_id.clone();
+        let response = self.client.clone().post_order(request).await?.into_inner();
+        self.lock().insert(order_id, (Utc::now(), response.clone()));
+
+        Ok(response)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Responses> {
+        self.responses
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Результат сверки заявок [`OrderReconciler::reconcile`].
+///
+/// Все списки содержат идентификаторы заявок, отсортированные по возрастанию.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconciliationResult {
+    /// Заявки, по которым исполнены новые лоты
+    pub filled_orders: Vec<String>,
+    /// Заявки, отменённые или отклонённые
+    pub cancelled_orders: Vec<String>,
+    /// Локальные заявки, отсутствующие в ответе API
+    pub missing_orders: Vec<String>,
+    /// Заявки, которых нет в локальном состоянии
+    pub new_orders: Vec<String>,
+    /// Заявки без изменений
+    pub unchanged: Vec<String>,
+}
+
+/// Сверяет локальное состояние заявок с состоянием, полученным из API.
+///
+/// Позволяет обнаружить пропущенные исполнения и устаревшие заявки.
+/// Заявки сопоставляются по `order_id`.
+#[derive(Debug, Clone, Default)]
+pub struct OrderReconciler {
+    local_orders: HashMap<String, OrderState>,
+}
+
+impl OrderReconciler {
+    /// Создаёт сверку по локальному состоянию заявок.
+    ///
+    /// # Аргументы
+    /// * `local_orders` - Заявки по `order_id`, как их видит приложение
+    pub fn new(local_orders: HashMap<String, OrderState>) -> Self {
+        Self { local_orders }
+    }
+
+    /// Сравнивает локальные заявки с заявками из API.
+    ///
+    /// Заявка считается исполненной, если в API она исполнена или по ней исполнено
+    /// больше лотов, чем в локальном состоянии. `GetOrders` возвращает только активные
+    /// заявки, поэтому локальные заявки, отсутствующие в `api_orders`, могли быть
+    /// как исполнены, так и отменены. Они попадают в `missing_orders`, их состояние
+    /// нужно уточнить через `GetOrderState` и передать в повторную сверку.
+    pub fn reconcile(&self, api_orders: &[OrderState]) -> ReconciliationResult {
+        let mut result = ReconciliationResult::default();
+        let api_order_ids: HashSet<&str> = api_orders
+            .iter()
+            .map(|order| order.order_id.as_str())
+            .collect();
+
+        for api_order in api_orders {
+            let order_id = api_order.order_id.clone();
+            let Some(local_order) = self.local_orders.get(&order_id) else {
+                result.new_orders.push(order_id);
+                continue;
+            };
+
+            let status = api_order.execution_report_status();
+            if status != local_order.execution_report_status()
+                && matches!(
+                    status,
+                    OrderExecutionReportStatus::ExecutionReportStatusCancelled
+                        | OrderExecutionReportStatus::ExecutionReportStatusRejected
+                )
+            {
+                result.cancelled_orders.push(order_id);
+            } else if api_order.lots_executed > local_order.lots_executed
+                || (status == OrderExecutionReportStatus::ExecutionReportStatusFill
+                    && status != local_order.execution_report_status())
+            {
+                result.filled_orders.push(order_id);
+            } else {
+                result.unchanged.push(order_id);
+            }
+        }
+
+        result.missing_orders = self
+            .local_orders
+            .keys()
+            .filter(|order_id| !api_order_ids.contains(order_id.as_str()))
+            .cloned()
+            .collect();
+
+        result.filled_orders.sort();
+        result.cancelled_orders.sort();
+        result.missing_orders.sort();
+        result.new_orders.sort();
+        result.unchanged.sort();
+
+        result
+    }
+}
+
+/// Формирует запросы на заявку на вход, take-profit и stop-loss.
+fn bracket_requests(
+    account_id: &str,
+    figi: &str,
+    direction: OrderDirection,
+    quantity: i64,
+    entry_price: Decimal,
+    take_profit_price: Decimal,
+    stop_loss_price: Decimal,
+) -> Result<(PostOrderRequest, PostStopOrderRequest, PostStopOrderRequest), TInvestError> {
+    let exit_direction = match direction {
+        OrderDirection::Buy => StopOrderDirection::Sell,
+        OrderDirection::Sell => StopOrderDirection::Buy,
+        OrderDirection::Unspecified => {
+            return Err(TInvestError::InvalidArgument(
+                "Order direction is not specified".to_string(),
+            ));
+        }
+    };
+
+    let entry = PostOrderRequest {
+        quantity,
+        price: Some(quotation(entry_price)?),
+        direction: direction.into(),
+        account_id: account_id.to_string(),
+        order_type: OrderType::Limit.into(),
+        order_id: uuid::Uuid::new_v4().to_string(),
+        instrument_id: figi.to_string(),
+        ..Default::default()
+    };
+
+    let stop_order = |stop_order_type: StopOrderType, stop_price: Decimal| {
+        Ok::<_, TInvestError>(stop_order_request(
+            account_id,
+            figi,
+            exit_direction,
+            quantity,
+            stop_order_type,
+            quotation(stop_price)?,
+        ))
+    };
+
+    Ok((
+        entry,
+        stop_order(StopOrderType::TakeProfit, take_profit_price)?,
+        stop_order(StopOrderType::StopLoss, stop_loss_price)?,
+    ))
+}
+
+/// Формирует запрос на стоп-заявку, действующую до отмены.
+fn stop_order_request(
+    account_id: &str,
+    figi: &str,
+    direction: StopOrderDirection,
+    quantity: i64,
+    stop_order_type: StopOrderType,
+    stop_price: Quotation,
+) -> PostStopOrderRequest {
+    PostStopOrderRequest {
+        quantity,
+        stop_price: Some(stop_price),
+        direction: direction.into(),
+        account_id: account_id.to_string(),
+        expiration_type: StopOrderExpirationType::GoodTillCancel.into(),
+        stop_order_type: stop_order_type.into(),
+        instrument_id: figi.to_string(),
+        order_id: uuid::Uuid::new_v4().to_string(),
+        ..Default::default()
+    }
+}
+
+fn quotation(price: Decimal) -> Result<Quotation, TInvestError> {
+    Quotation::try_from(price).map_err(TInvestError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn bracket_requests_for_buy() {
+        let (entry, take_profit, stop_loss) = bracket_requests(
+            "account",
+            "FIGI",
+            OrderDirection::Buy,
+            3,
+            dec!(100),
+            dec!(110),
+            dec!(95),
+        )
+        .unwrap();
+
+        assert_eq!(OrderDirection::Buy as i32, entry.direction);
+        assert_eq!(OrderType::Limit as i32, entry.order_type);
+        assert_eq!(Some(Quotation::try_from(dec!(100)).unwrap()), entry.price);
+        assert_eq!("FIGI", entry.instrument_id);
+
+        assert_eq!(
+            StopOrderType::TakeProfit as i32,
+            take_profit.stop_order_type
+        );
+        assert_eq!(StopOrderDirection::Sell as i32, take_profit.direction);
+        assert_eq!(
+            Some(Quotation::try_from(dec!(110)).unwrap()),
+            take_profit.stop_price
+        );
+
+        assert_eq!(StopOrderType::StopLoss as i32, stop_loss.stop_order_type);
+        assert_eq!(3, stop_loss.quantity);
+        assert_ne!(take_profit.order_id, stop_loss.order_id);
+    }
+
+    fn placer() -> IdempotentOrderPlacer {
+        let channel = Channel::from_static("https://localhost").connect_lazy();
+        IdempotentOrderPlacer::new(OrdersServiceClient::with_interceptor(
+            channel,
+            TInvestInterceptor::new("t.test"),
+        ))
+    }
+
+    fn cached_response(placer: &IdempotentOrderPlacer, order_id: &str, placed_at: DateTime<Utc>) {
+        placer.lock().insert(
+            order_id.to_string(),
+            (
+                placed_at,
+                PostOrderResponse {
+                    order_id: "exchange-id".to_string(),
+                    ..Default::default()
+                },
+            ),
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_cached_response() {
+        let placer = placer();
+        cached_response(&placer, "client-id", Utc::now());
+
+        let response = placer
+            .place_order(PostOrderRequest {
+                order_id: "client-id".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!("exchange-id", response.order_id);
+    }
+
+    #[tokio::test]
+    async fn forgets_expired_responses() {
+        let placer = placer().with_retention(Duration::from_secs(60));
+        cached_response(&placer, "expired", Utc::now() - TimeDelta::minutes(2));
+        cached_response(&placer, "recent", Utc::now());
+
+        let response = placer
+            .place_order(PostOrderRequest {
+                order_id: "recent".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!("exchange-id", response.order_id);
+        assert!(!placer.lock().contains_key("expired"));
+    }
+
+    fn order_state(
+        order_id: &str,
+        status: OrderExecutionReportStatus,
+        lots_executed: i64,
+    ) -> OrderState {
+        OrderState {
+            order_id: order_id.to_string(),
+            execution_report_status: status.into(),
+            lots_requested: 10,
+            lots_executed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reconcile() {
+        use OrderExecutionReportStatus::*;
+
+        let local_orders = [
+            order_state("filled", ExecutionReportStatusNew, 0),
+            order_state("partial", ExecutionReportStatusPartiallyfill, 2),
+            order_state("cancelled", ExecutionReportStatusNew, 0),
+            order_state("missing", ExecutionReportStatusNew, 0),
+            order_state("same", ExecutionReportStatusPartiallyfill, 2),
+        ]
+        .into_iter()
+        .map(|order| (order.order_id.clone(), order))
+        .collect();
+        let reconciler = OrderReconciler::new(local_orders);
+
+        let result = reconciler.reconcile(&[
+            order_state("filled", ExecutionReportStatusFill, 10),
+            order_state("partial", ExecutionReportStatusPartiallyfill, 5),
+            order_state("cancelled", ExecutionReportStatusCancelled, 0),
+            order_state("same", ExecutionReportStatusPartiallyfill, 2),
+            order_state("external", ExecutionReportStatusNew, 0),
+        ]);
+
+        assert_eq!(
+            ReconciliationResult {
+                filled_orders: vec!["filled".to_string(), "partial".to_string()],
+                cancelled_orders: vec!["cancelled".to_string()],
+                missing_orders: vec!["missing".to_string()],
+                new_orders: vec!["external".to_string()],
+                unchanged: vec!["same".to_string()],
+            },
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn stop_pair_for_empty_position() {
+        let sdk = TInvestSdk {
+            channel: Channel::from_static("https://localhost").connect_lazy(),
+            interceptor: TInvestInterceptor::new("t.test"),
+        };
+        let price = Quotation::try_from(dec!(100)).unwrap();
+
+        let result = place_stop_pair(&sdk, "account", "FIGI", 0, price, price).await;
+
+        assert!(matches!(result, Err(TInvestError::InvalidArgument(_))));
+    }
+
+    #[test]
+    fn bracket_requests_without_direction() {
+        let result = bracket_requests(
+            "account",
+            "FIGI",
+            OrderDirection::Unspecified,
+            1,
+            dec!(100),
+            dec!(110),
+            dec!(95),
+        );
+
+        assert!(matches!(result, Err(TInvestError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    async fn cancel_each_separates_failures() {
+        let ids = ["1", "2", "3", "4"].map(String::from);
+
+        let result = cancel_each(ids, |id| async move {
+            match id.as_str() {
+                "2" | "4" => Err(tonic::Status::not_found("order not found")),
+                _ => Ok(()),
+            }
+        })
+        .await;
+
+        assert_eq!(vec!["1".to_string(), "3".to_string()], result.cancelled);
+        assert_eq!(
+            vec!["2", "4"],
+            result
+                .failed
+                .iter()
+                .map(|(id, _)| id.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(result.failed[0].1, TInvestError::Status(_)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polls_until_completed() {
+        let mut statuses = vec![
+            OrderExecutionReportStatus::ExecutionReportStatusNew,
+            OrderExecutionReportStatus::ExecutionReportStatusPartiallyfill,
+            OrderExecutionReportStatus::ExecutionReportStatusFill,
+        ]
+        .into_iter();
+        let started = tokio::time::Instant::now();
+
+        let state = poll_until_completed(
+            || {
+                let status = statuses.next().unwrap();
+                async move { Ok(order_state("order", status, 0)) }
+            },
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            OrderExecutionReportStatus::ExecutionReportStatusFill,
+            state.execution_report_status()
+        );
+        assert_eq!(Duration::from_secs(2), started.elapsed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn polling_timeout() {
+        let result = poll_until_completed(
+            || async {
+                Ok(order_state(
+                    "order",
+                    OrderExecutionReportStatus::ExecutionReportStatusNew,
+                    0,
+                ))
+            },
+            Duration::from_secs(1),
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(
+            matches!(result, Err(TInvestError::Timeout(timeout)) if timeout == Duration::from_secs(5))
+        );
+
+        let result = poll_until_completed(
+            || async { Err(tonic::Status::not_found("order not found")) },
+            Duration::from_secs(1),
+            Duration::from_secs(5),
+        )
+        .await;
+        assert!(matches!(result, Err(TInvestError::Status(_))));
+    }
+
+    fn stream_update(
+        order_id: &str,
+        status: OrderExecutionReportStatus,
+    ) -> Result<OrderStateStreamResponse, tonic::Status> {
+        Ok(OrderStateStreamResponse {
+            payload: Some(order_state_stream_response::Payload::OrderState(
+                order_state_stream_response::OrderState {
+                    order_id: order_id.to_string(),
+                    execution_report_status: status.into(),
+                    ..Default::default()
+                },
+            )),
+        })
+    }
+
+    #[tokio::test]
+    async fn waits_in_stream() {
+        let updates = futures::stream::iter(vec![
+            stream_update(
+                "other",
+                OrderExecutionReportStatus::ExecutionReportStatusFill,
+            ),
+            stream_update(
+                "order",
+                OrderExecutionReportStatus::ExecutionReportStatusFill,
+            ),
+        ]);
+        let mut statuses = vec![
+            OrderExecutionReportStatus::ExecutionReportStatusNew,
+            OrderExecutionReportStatus::ExecutionReportStatusFill,
+        ]
+        .into_iter();
+
+        let state = wait_in_stream(updates, "order", || {
+            let status = statuses.next().unwrap();
+            async move { Ok(order_state("order", status, 10)) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            OrderExecutionReportStatus::ExecutionReportStatusFill,
+            state.execution_report_status()
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_closed_before_completion() {
+        let updates = futures::stream::iter(vec![stream_update(
+            "order",
+            OrderExecutionReportStatus::ExecutionReportStatusPartiallyfill,
+        )]);
+
+        let result = wait_in_stream(updates, "order", || async {
+            Ok(order_state(
+                "order",
+                OrderExecutionReportStatus::ExecutionReportStatusNew,
+                0,
+            ))
+        })
+        .await;
+        assert!(matches!(result, Err(TInvestError::StreamClosed)));
+        assert!(!result.unwrap_err().is_transient());
+    }
+
+    #[test]
+    fn stop_order_types() {
+        let types = [
+            StopOrderType::Unspecified,
+            StopOrderType::TakeProfit,
+            StopOrderType::StopLoss,
+            StopOrderType::StopLimit,
+        ];
+        let flags: Vec<_> = types
+            .iter()
+            .map(|t| (t.is_take_profit(), t.is_stop_market(), t.is_stop_limit()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                (false, false, false),
+                (true, false, false),
+                (false, true, false),
+                (false, false, true),
+            ],
+            flags
+        );
+        assert_eq!("Stop-loss", StopOrderType::StopLoss.display_name());
+        assert_eq!(
+            Ok("Take-profit"),
+            StopOrderType::try_from(1).map(|t| t.display_name())
+        );
+    }
+
+    /// Сервис, отвечающий на `PostOrder` и сохраняющий полученные запросы.
+    #[derive(Clone, Default)]
+    struct MockOrdersService {
+        requests: Arc<Mutex<Vec<PostOrderRequest>>>,
+    }
+
+    impl tonic::server::UnaryService<PostOrderRequest> for MockOrdersService {
+        type Response = PostOrderResponse;
+        type Future =
+            futures::future::Ready<Result<tonic::Response<Self::Response>, tonic::Status>>;
+
+        fn call(&mut self, request: tonic::Request<PostOrderRequest>) -> Self::Future {
+            let request = request.into_inner();
+            let response = PostOrderResponse {
+                order_id: request.order_id.clone(),
+                lots_requested: request.quantity,
+                direction: request.direction,
+                order_type: request.order_type,
+                ..Default::default()
+            };
+            self.requests.lock().unwrap().push(request);
+            futures::future::ready(Ok(tonic::Response::new(response)))
+        }
+    }
+
+    impl tonic::codegen::Service<tonic::codegen::http::Request<tonic::body::Body>>
+        for MockOrdersService
+    {
+        type Response = tonic::codegen::http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = tonic::codegen::BoxFuture<Self::Response, Self::Error>;
+
+        fn poll_ready(
+            &mut self,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn call(
+            &mut self,
+            request: tonic::codegen::http::Request<tonic::body::Body>,
+        ) -> Self::Future {
+            let service = self.clone();
+            Box::pin(async move {
+                let mut grpc = tonic::server::Grpc::new(tonic_prost::ProstCodec::default());
+                Ok(grpc.unary(service, request).await)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn orders_ext_requests() {
+        let service = MockOrdersService::default();
+        let mut client = OrdersServiceClient::new(service.clone());
+        let price = Quotation {
+            units: 250,
+            nano: 500_000_000,
+        };
+
+        let response = client
+            .market_buy("account", "FIGI", 2, "id-1")
+            .await
+            .unwrap();
+        assert_eq!("id-1", response.order_id);
+        assert_eq!(2, response.lots_requested);
+        client
+            .market_sell("account", "FIGI", 3, "id-2")
+            .await
+            .unwrap();
+        client
+            .limit_buy("account", "FIGI", 4, price, "id-3")
+            .await
+            .unwrap();
+        client
+            .limit_sell("account", "FIGI", 5, price, "id-4")
+            .await
+            .unwrap();
+
+        let requests = service.requests.lock().unwrap().clone();
+        let summary: Vec<_> = requests
+            .iter()
+            .map(|request| {
+                (
+                    request.order_id.as_str(),
+                    request.direction(),
+                    request.order_type(),
+                    request.quantity,
+                    request.price,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("id-1", OrderDirection::Buy, OrderType::Market, 2, None),
+                ("id-2", OrderDirection::Sell, OrderType::Market, 3, None),
+                (
+                    "id-3",
+                    OrderDirection::Buy,
+                    OrderType::Limit,
+                    4,
+                    Some(price)
+                ),
+                (
+                    "id-4",
+                    OrderDirection::Sell,
+                    OrderType::Limit,
+                    5,
+                    Some(price)
+                ),
+            ],
+            summary
+        );
+        assert!(requests.iter().all(|request| {
+            request.account_id == "account"
+                && request.instrument_id == "FIGI"
+                && request.time_in_force() == TimeInForceType::TimeInForceUnspecified
+                && !request.confirm_margin_trade
+        }));
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use rust_decimal::Decimal;
+
+use crate::api::{OrderDirection, OrderTrades, PortfolioResponse};
+
+/// Тип инструмента валютных позиций портфеля.
+const CURRENCY_INSTRUMENT_TYPE: &str = "currency";
+
+/// Позиция по инструменту, учитываемая по средней цене.
+#[derive(Debug, Default)]
+struct Position {
+    quantity: Decimal,
+    average_price: Decimal,
+    price: Decimal,
+}
+
+impl Position {
+    /// Учитывает сделку со знаковым количеством и возвращает реализованный результат.
+    fn apply(&mut self, quantity: Decimal, price: Decimal) -> Decimal {
+        self.price = price;
+        if self.quantity.is_zero()
+            || self.quantity.is_sign_positive() == quantity.is_sign_positive()
+        {
+            let total = self.quantity + quantity;
+            self.average_price = (self.quantity * self.average_price + quantity * price) / total;
+            self.quantity = total;
+            return Decimal::ZERO;
+        }
+
+        let closed = quantity.abs().min(self.quantity.abs());
+        let realized = if self.quantity.is_sign_positive() {
+            (price - self.average_price) * closed
+        } else {
+            (self.average_price - price) * closed
+        };
+
+        self.quantity += quantity;
+        if self.quantity.is_zero() {
+            self.average_price = Decimal::ZERO;
+        } else if self.quantity.is_sign_positive() == quantity.is_sign_positive() {
+            // Сделка перевернула позицию, остаток открыт по цене сделки.
+            self.average_price = price;
+        }
+
+        realized
+    }
+
+    fn unrealized(&self) -> Decimal {
+        (self.price - self.average_price) * self.quantity
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    positions: HashMap<String, Position>,
+    realized: HashMap<String, Decimal>,
+}
+
+/// Прибыль и убыток портфеля в реальном времени.
+///
+/// Начальные позиции берутся из ответа `GetPortfolio` по средней цене
+/// приобретения, затем состояние обновляется сделками из `TradesStream`
+/// и ценами из стрима рыночных данных. Позиции учитываются по средней цене,
+/// валютные позиции не учитываются, комиссии не вычитаются.
+///
+/// Методы принимают `&self`, поэтому структуру можно разделять между задачами
+/// через `Arc`.
+#[derive(Debug, Default)]
+pub struct RealTimePnL {
+    state: Mutex<State>,
+}
+
+impl RealTimePnL {
+    /// Создаёт расчёт по текущему портфелю.
+    pub fn new(initial_portfolio: PortfolioResponse) -> Self {
+        let positions = initial_portfolio
+            .positions
+            .into_iter()
+            .filter(|position| position.instrument_type != CURRENCY_INSTRUMENT_TYPE)
+            .map(|position| {
+                let average_price: Decimal = position
+                    .average_position_price
+                    .map(Decimal::from)
+                    .unwrap_or_default();
+                let price = position
+                    .current_price
+                    .map(Decimal::from)
+                    .unwrap_or(average_price);
+                let quantity = position.quantity.map(Decimal::from).unwrap_or_default();
+
+                (
+                    position.figi,
+                    Position {
+                        quantity,
+                        average_price,
+                        price,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            state: Mutex::new(State {
+                positions,
+                realized: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Учитывает исполненные сделки по поручению.
+    ///
+    /// Цена последней сделки становится текущей ценой инструмента. Сделки без цены
+    /// пропускаются, чтобы не искажать среднюю цену и реализованный результат.
+    ///
+    /// # Возвращает
+    /// Количество пропущенных сделок: без цены или все сделки поручения
+    /// без направления
+    pub fn on_trade_fill(&self, fill: &OrderTrades) -> usize {
+        let sign = match fill.direction() {
+            OrderDirection::Buy => Decimal::ONE,
+            OrderDirection::Sell => Decimal::NEGATIVE_ONE,
+            OrderDirection::Unspecified => return fill.trades.len(),
+        };
+
+        let mut state = self.lock();
+        let State {
+            positions,
+            realized,
+        } = &mut *state;
+        let position = positions.entry(fill.figi.clone()).or_default();
+        let mut skipped = 0;
+        for trade in &fill.trades {
+            let Some(price) = trade.price.map(Decimal::from) else {
+                skipped += 1;
+                continue;
+            };
+            let result = position.apply(sign * Decimal::from(trade.quantity), price);
+            *realized.entry(fill.figi.clone()).or_default() += result;
+        }
+
+        skipped
+    }
+
+    /// Обновляет текущую цену инструмента. Цены инструментов без позиции игнорируются.
+    pub fn on_price_update(&self, figi: &str, price: Decimal) {
+        if let Some(position) = self.lock().positions.get_mut(figi) {
+            position.price = price;
+        }
+    }
+
+    /// Возвращает нереализованный результат по FIGI для открытых позиций.
+    pub fn get_unrealized_pnl(&self) -> HashMap<String, Decimal> {
+        self.lock()
+            .positions
+            .iter()
+            .filter(|(_, position)| !position.quantity.is_zero())
+            .map(|(figi, position)| (figi.clone(), position.unrealized()))
+            .collect()
+    }
+
+    /// Возвращает реализованный результат по FIGI с момента создания.
+    pub fn get_realized_pnl(&self) -> HashMap<String, Decimal> {
+        self.lock().realized.clone()
+    }
+
+    /// Возвращает сумму реализованного и нереализованного результата.
+    pub fn get_total_pnl(&self) -> Decimal {
+        let state = self.lock();
+        let unrealized: Decimal = state.positions.values().map(Position::unrealized).sum();
+        let realized: Decimal = state.realized.values().sum();
+
+        unrealized + realized
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, OrderTrade, PortfolioPosition, Quotation};
+
+    fn money(value: Decimal) -> Option<MoneyValue> {
+        let value = Quotation::try_from(value).unwrap();
+        Some(MoneyValue {
+            currency: "rub".to_string(),
+            units: value.units,
+            nano: value.nano,
+        })
+    }
+
+    fn fill(figi: &str, direction: OrderDirection, trades: &[(i64, Decimal)]) -> OrderTrades {
+        OrderTrades {
+            figi: figi.to_string(),
+            direction: direction.into(),
+            trades: trades
+                .iter()
+                .map(|(quantity, price)| OrderTrade {
+                    price: Some(Quotation::try_from(*price).unwrap()),
+                    quantity: *quantity,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn pnl() -> RealTimePnL {
+        RealTimePnL::new(PortfolioResponse {
+            positions: vec![
+                PortfolioPosition {
+                    figi: "SBER".to_string(),
+                    instrument_type: "share".to_string(),
+                    quantity: Some(Quotation::from(10)),
+                    average_position_price: money(dec!(250)),
+                    current_price: money(dec!(260)),
+                    ..Default::default()
+                },
+                PortfolioPosition {
+                    figi: "RUB000UTSTOM".to_string(),
+                    instrument_type: "currency".to_string(),
+                    quantity: Some(Quotation::from(1000)),
+                    average_position_price: money(dec!(1)),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn initial_portfolio_and_price_updates() {
+        let pnl = pnl();
+        assert_eq!(
+            HashMap::from([("SBER".to_string(), dec!(100))]),
+            pnl.get_unrealized_pnl()
+        );
+
+        pnl.on_price_update("SBER", dec!(240));
+        pnl.on_price_update("GAZP", dec!(150));
+        assert_eq!(dec!(-100), pnl.get_total_pnl());
+        assert!(pnl.get_realized_pnl().is_empty());
+    }
+
+    #[test]
+    fn trade_fills() {
+        let pnl = pnl();
+
+        // Докупка 10 по 270: средняя цена 260.
+        pnl.on_trade_fill(&fill("SBER", OrderDirection::Buy, &[(10, dec!(270))]));
+        assert_eq!(dec!(200), pnl.get_unrealized_pnl()["SBER"]);
+
+        // Продажа 15 двумя сделками: (280 - 260) * 15 = 300.
+        pnl.on_trade_fill(&fill(
+            "SBER",
+            OrderDirection::Sell,
+            &[(5, dec!(280)), (10, dec!(280))],
+        ));
+        assert_eq!(dec!(300), pnl.get_realized_pnl()["SBER"]);
+        assert_eq!(dec!(100), pnl.get_unrealized_pnl()["SBER"]);
+
+        // Продажа 10 закрывает позицию и открывает короткую на 5 по 290.
+        pnl.on_trade_fill(&fill("SBER", OrderDirection::Sell, &[(10, dec!(290))]));
+        assert_eq!(dec!(450), pnl.get_realized_pnl()["SBER"]);
+        pnl.on_price_update("SBER", dec!(300));
+        assert_eq!(dec!(-50), pnl.get_unrealized_pnl()["SBER"]);
+        assert_eq!(dec!(400), pnl.get_total_pnl());
+
+        // Закрытие короткой позиции.
+        pnl.on_trade_fill(&fill("SBER", OrderDirection::Buy, &[(5, dec!(280))]));
+        assert_eq!(dec!(500), pnl.get_realized_pnl()["SBER"]);
+        assert!(pnl.get_unrealized_pnl().is_empty());
+    }
+
+    #[test]
+    fn trades_without_price() {
+        let pnl = pnl();
+
+        let mut buy = fill(
+            "SBER",
+            OrderDirection::Buy,
+            &[(10, dec!(270)), (5, dec!(0))],
+        );
+        buy.trades[1].price = None;
+        assert_eq!(1, pnl.on_trade_fill(&buy));
+        // Учтена только сделка с ценой: средняя цена 260, количество 20.
+        assert_eq!(dec!(200), pnl.get_unrealized_pnl()["SBER"]);
+
+        let unspecified = fill("SBER", OrderDirection::Unspecified, &[(10, dec!(300))]);
+        assert_eq!(1, pnl.on_trade_fill(&unspecified));
+        assert_eq!(Decimal::ZERO, pnl.get_realized_pnl()["SBER"]);
+    }
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RealTimePnL>();
+    }
+}
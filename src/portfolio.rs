@@ -0,0 +1,673 @@
+//! Анализ портфеля: доходность, стресс-тесты и разложение результата.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+
+use rust_decimal::Decimal;
+use tonic::codegen::{Body, Bytes, StdError};
+
+use crate::TInvestError;
+use crate::api::{
+    MoneyValue, Operation, OperationState, OperationType, PortfolioPosition, PortfolioRequest,
+    PortfolioResponse, operations_service_client::OperationsServiceClient,
+};
+
+pub mod attribution;
+
+/// Открытая часть позиции, купленная (или проданная в шорт) по одной цене.
+#[derive(Debug, Clone, Copy)]
+struct Lot {
+    quantity: Decimal,
+    price: Decimal,
+}
+
+/// Анализ доходности портфеля по операциям и текущим позициям.
+///
+/// Реализованная доходность и средняя цена рассчитываются методом FIFO:
+/// продажа закрывает самые ранние покупки (для коротких позиций — наоборот).
+/// Учитываются только исполненные операции покупки и продажи, частично
+/// исполненные заявки учитываются по исполненному количеству (`quantity - quantity_rest`).
+/// Комиссии в расчёте не участвуют.
+#[derive(Debug, Clone)]
+pub struct PortfolioAnalyzer {
+    positions: Vec<PortfolioPosition>,
+    open_lots: HashMap<String, VecDeque<Lot>>,
+    realized: HashMap<String, Decimal>,
+}
+
+impl PortfolioAnalyzer {
+    /// Создаёт анализатор из операций по счёту и позиций портфеля.
+    ///
+    /// Порядок операций не важен: перед расчётом они сортируются по дате.
+    pub fn new(operations: Vec<Operation>, positions: Vec<PortfolioPosition>) -> Self {
+        let mut trades: Vec<(i64, i32, &str, Decimal, Decimal)> = operations
+            .iter()
+            .filter(|operation| operation.state == OperationState::Executed as i32)
+            .filter_map(|operation| {
+                let sign = trade_sign(operation.operation_type)?;
+                let quantity = Decimal::from(operation.quantity - operation.quantity_rest);
+                let price = operation.price.clone().map(Decimal::from)?;
+                let date = operation.date.unwrap_or_default();
+
+                Some((
+                    date.seconds,
+                    date.nanos,
+                    operation.figi.as_str(),
+                    quantity * sign,
+                    price,
+                ))
+            })
+            .collect();
+        trades.sort_by_key(|(seconds, nanos, ..)| (*seconds, *nanos));
+
+        let mut open_lots: HashMap<String, VecDeque<Lot>> = HashMap::new();
+        let mut realized: HashMap<String, Decimal> = HashMap::new();
+
+        for (_, _, figi, quantity, price) in trades {
+            let lots = open_lots.entry(figi.to_string()).or_default();
+            let pnl = apply_trade(lots, quantity, price);
+            *realized.entry(figi.to_string()).or_default() += pnl;
+        }
+
+        Self {
+            positions,
+            open_lots,
+            realized,
+        }
+    }
+
+    /// Возвращает нереализованную доходность по FIGI.
+    ///
+    /// Рассчитывается как `(текущая цена - средняя цена) * количество` для каждой
+    /// позиции портфеля. Средняя цена берётся из [`Self::average_cost_basis`],
+    /// а если по инструменту нет операций — из `average_position_price_fifo` позиции.
+    pub fn unrealized_pnl(&self) -> HashMap<String, Decimal> {
+        self.positions
+            .iter()
+            .filter_map(|position| {
+                let quantity = Decimal::from(position.quantity?);
+                let current_price = Decimal::from(position.current_price.clone()?);
+                let average_price = self.average_cost_basis(&position.figi).or_else(|| {
+                    position
+                        .average_position_price_fifo
+                        .clone()
+                        .map(Decimal::from)
+                })?;
+
+                Some((
+                    position.figi.clone(),
+                    (current_price - average_price) * quantity,
+                ))
+            })
+            .collect()
+    }
+
+    /// Возвращает реализованную доходность по FIGI.
+    pub fn realized_pnl(&self) -> HashMap<String, Decimal> {
+        self.realized.clone()
+    }
+
+    /// Возвращает среднюю цену открытой позиции по FIFO.
+    ///
+    /// # Возвращает
+    /// `None`, если открытой позиции по операциям нет
+    pub fn average_cost_basis(&self, figi: &str) -> Option<Decimal> {
+        let lots = self.open_lots.get(figi)?;
+        let quantity: Decimal = lots.iter().map(|lot| lot.quantity).sum();
+        if quantity.is_zero() {
+            return None;
+        }
+
+        let cost: Decimal = lots.iter().map(|lot| lot.quantity * lot.price).sum();
+        Some(cost / quantity)
+    }
+
+    /// Возвращает стоимость позиций портфеля по указанным ценам.
+    ///
+    /// Для инструментов, отсутствующих в `prices`, используется `current_price` позиции.
+    pub fn total_value(&self, prices: &HashMap<String, Decimal>) -> Decimal {
+        self.positions
+            .iter()
+            .filter_map(|position| {
+                let quantity = Decimal::from(position.quantity?);
+                let price = prices
+                    .get(&position.figi)
+                    .copied()
+                    .or_else(|| position.current_price.clone().map(Decimal::from))?;
+
+                Some(quantity * price)
+            })
+            .sum()
+    }
+}
+
+/// Позиция портфеля с числовыми полями в виде `Decimal`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortfolioPositionRecord {
+    /// FIGI-идентификатор инструмента
+    pub figi: String,
+    /// Тип инструмента
+    pub instrument_type: String,
+    /// Количество инструмента в штуках
+    pub quantity: Decimal,
+    /// Средняя цена позиции, ноль если API её не вернул
+    pub average_price: Decimal,
+    /// Текущая цена за 1 инструмент
+    pub current_price: Option<Decimal>,
+    /// Текущая рассчитанная доходность позиции
+    pub expected_yield: Option<Decimal>,
+    /// Текущий НКД
+    pub current_nkd: Option<Decimal>,
+    /// Валюта средней цены, а если её нет — текущей цены
+    pub currency: String,
+}
+
+impl From<PortfolioPosition> for PortfolioPositionRecord {
+    fn from(position: PortfolioPosition) -> Self {
+        let currency = position
+            .average_position_price
+            .as_ref()
+            .or(position.current_price.as_ref())
+            .map(|price| price.currency.clone())
+            .unwrap_or_default();
+
+        Self {
+            figi: position.figi,
+            instrument_type: position.instrument_type,
+            quantity: position.quantity.map(Decimal::from).unwrap_or_default(),
+            average_price: position
+                .average_position_price
+                .map(Decimal::from)
+                .unwrap_or_default(),
+            current_price: position.current_price.map(Decimal::from),
+            expected_yield: position.expected_yield.map(Decimal::from),
+            current_nkd: position.current_nkd.map(Decimal::from),
+            currency,
+        }
+    }
+}
+
+/// Сценарий стресс-теста портфеля.
+///
+/// Изменения задаются в процентах, например `-20` для падения на 20%.
+/// Изменения для всех позиций, отдельной позиции и валюты перемножаются.
+/// Общее изменение не применяется к валютным позициям (`instrument_type = "currency"`).
+/// Изменение валюты применяется к позициям, цена которых указана в этой валюте.
+///
+/// Стоимость пересчитывается в базовую валюту по курсам, заданным через
+/// [`Self::exchange_rate`]. Позиции в валюте без курса считаются
+/// номинированными в базовой валюте.
+///
+/// ```
+/// # use rust_decimal_macros::dec;
+/// # use t_invest_sdk::{StressTestScenario, api::PortfolioResponse};
+/// # let portfolio = PortfolioResponse::default();
+/// let value = StressTestScenario::new()
+///     .base_currency("rub")
+///     .exchange_rate("usd", dec!(90))
+///     .shock_all_positions(dec!(-20))
+///     .shock_currency("usd", dec!(10))
+///     .apply(&portfolio);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StressTestScenario {
+    base_currency: String,
+    all_positions: Decimal,
+    positions: HashMap<String, Decimal>,
+    currencies: HashMap<String, Decimal>,
+    exchange_rates: HashMap<String, Decimal>,
+}
+
+impl Default for StressTestScenario {
+    fn default() -> Self {
+        Self {
+            base_currency: "rub".to_string(),
+            all_positions: Decimal::ZERO,
+            positions: HashMap::new(),
+            currencies: HashMap::new(),
+            exchange_rates: HashMap::new(),
+        }
+    }
+}
+
+impl StressTestScenario {
+    /// Создаёт сценарий без изменений с базовой валютой `rub`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Устанавливает базовую валюту результата.
+    pub fn base_currency(&mut self, currency: &str) -> &mut Self {
+        self.base_currency = currency.to_lowercase();
+        self
+    }
+
+    /// Устанавливает курс валюты к базовой валюте.
+    pub fn exchange_rate(&mut self, currency: &str, rate: Decimal) -> &mut Self {
+        self.exchange_rates.insert(currency.to_lowercase(), rate);
+        self
+    }
+
+    /// Изменяет цену всех позиций, кроме валютных.
+    pub fn shock_all_positions(&mut self, pct_change: Decimal) -> &mut Self {
+        self.all_positions = pct_change;
+        self
+    }
+
+    /// Изменяет цену позиции с указанным FIGI.
+    pub fn shock_position(&mut self, figi: &str, pct_change: Decimal) -> &mut Self {
+        self.positions.insert(figi.to_string(), pct_change);
+        self
+    }
+
+    /// Изменяет курс валюты к базовой валюте.
+    pub fn shock_currency(&mut self, currency: &str, pct_change: Decimal) -> &mut Self {
+        self.currencies.insert(currency.to_lowercase(), pct_change);
+        self
+    }
+
+    /// Возвращает стоимость портфеля в базовой валюте после применения сценария.
+    pub fn apply(&self, portfolio: &PortfolioResponse) -> Decimal {
+        portfolio
+            .positions
+            .iter()
+            .filter_map(|position| {
+                let quantity = Decimal::from(position.quantity?);
+                let price = position.current_price.as_ref()?;
+                let currency = price.currency.to_lowercase();
+
+                let mut value = quantity * Decimal::from(price.clone());
+                if position.instrument_type != "currency" {
+                    value *= factor(self.all_positions);
+                }
+                if let Some(pct_change) = self.positions.get(&position.figi) {
+                    value *= factor(*pct_change);
+                }
+                if currency != self.base_currency {
+                    if let Some(pct_change) = self.currencies.get(&currency) {
+                        value *= factor(*pct_change);
+                    }
+                    if let Some(rate) = self.exchange_rates.get(&currency) {
+                        value *= rate;
+                    }
+                }
+
+                Some(value)
+            })
+            .sum()
+    }
+}
+
+/// Портфель с позициями, проиндексированными по FIGI.
+///
+/// Суммы приведены к `Decimal` в валюте портфеля, отсутствующие суммы считаются нулевыми.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexedPortfolio {
+    /// Позиции по FIGI
+    pub positions: HashMap<String, PortfolioPosition>,
+    /// Стоимость акций
+    pub total_amount_shares: Decimal,
+    /// Стоимость облигаций
+    pub total_amount_bonds: Decimal,
+    /// Стоимость фондов
+    pub total_amount_etf: Decimal,
+    /// Стоимость валют
+    pub total_amount_currencies: Decimal,
+    /// Стоимость фьючерсов
+    pub total_amount_futures: Decimal,
+    /// Текущая относительная доходность портфеля в процентах
+    pub expected_yield: Decimal,
+}
+
+impl IndexedPortfolio {
+    /// Возвращает позицию по FIGI.
+    pub fn position(&self, figi: &str) -> Option<&PortfolioPosition> {
+        self.positions.get(figi)
+    }
+
+    /// Проверяет, есть ли в портфеле позиция по FIGI.
+    pub fn has_position(&self, figi: &str) -> bool {
+        self.positions.contains_key(figi)
+    }
+
+    /// Возвращает количество инструментов в позиции, для отсутствующей позиции — ноль.
+    pub fn net_quantity(&self, figi: &str) -> Decimal {
+        self.position(figi)
+            .and_then(|position| position.quantity)
+            .map(Decimal::from)
+            .unwrap_or_default()
+    }
+
+    /// Возвращает сумму стоимости акций, облигаций, фондов, валют и фьючерсов.
+    pub fn total_value(&self) -> Decimal {
+        self.total_amount_shares
+            + self.total_amount_bonds
+            + self.total_amount_etf
+            + self.total_amount_currencies
+            + self.total_amount_futures
+    }
+}
+
+impl From<PortfolioResponse> for IndexedPortfolio {
+    /// Если в ответе несколько позиций с одним FIGI, сохраняется последняя.
+    fn from(portfolio: PortfolioResponse) -> Self {
+        let amount = |amount: Option<MoneyValue>| amount.map(Decimal::from).unwrap_or_default();
+
+        Self {
+            total_amount_shares: amount(portfolio.total_amount_shares),
+            total_amount_bonds: amount(portfolio.total_amount_bonds),
+            total_amount_etf: amount(portfolio.total_amount_etf),
+            total_amount_currencies: amount(portfolio.total_amount_currencies),
+            total_amount_futures: amount(portfolio.total_amount_futures),
+            expected_yield: portfolio
+                .expected_yield
+                .map(Decimal::from)
+                .unwrap_or_default(),
+            positions: portfolio
+                .positions
+                .into_iter()
+                .map(|position| (position.figi.clone(), position))
+                .collect(),
+        }
+    }
+}
+
+/// Запрос портфеля с позициями по FIGI для [`OperationsServiceClient`].
+pub trait GetPortfolioExt {
+    /// Запрашивает портфель счёта и индексирует позиции по FIGI.
+    fn get_indexed_portfolio(
+        &mut self,
+        account_id: &str,
+    ) -> impl Future<Output = Result<IndexedPortfolio, TInvestError>> + Send;
+}
+
+impl<T> GetPortfolioExt for OperationsServiceClient<T>
+where
+    T: tonic::client::GrpcService<tonic::body::Body> + Send,
+    T::Future: Send,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+{
+    async fn get_indexed_portfolio(
+        &mut self,
+        account_id: &str,
+    ) -> Result<IndexedPortfolio, TInvestError> {
+        let portfolio = self
+            .get_portfolio(PortfolioRequest {
+                account_id: account_id.to_string(),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        Ok(portfolio.into())
+    }
+}
+
+fn factor(pct_change: Decimal) -> Decimal {
+    Decimal::ONE + pct_change / Decimal::ONE_HUNDRED
+}
+
+/// Возвращает знак сделки: `1` для покупки, `-1` для продажи.
+pub(crate) fn trade_sign(operation_type: i32) -> Option<Decimal> {
+    match OperationType::try_from(operation_type).ok()? {
+        OperationType::Buy
+        | OperationType::BuyCard
+        | OperationType::BuyMargin
+        | OperationType::DeliveryBuy => Some(Decimal::ONE),
+        OperationType::Sell
+        | OperationType::SellCard
+        | OperationType::SellMargin
+        | OperationType::DeliverySell => Some(Decimal::NEGATIVE_ONE),
+        _ => None,
+    }
+}
+
+/// Применяет сделку к открытым лотам по FIFO.
+///
+/// `quantity` положительно для покупки и отрицательно для продажи.
+///
+/// # Возвращает
+/// Реализованную доходность сделки
+fn apply_trade(lots: &mut VecDeque<Lot>, mut quantity: Decimal, price: Decimal) -> Decimal {
+    let mut realized = Decimal::ZERO;
+
+    while !quantity.is_zero() {
+        let Some(lot) = lots.front_mut() else {
+            break;
+        };
+        if lot.quantity.is_sign_positive() == quantity.is_sign_positive() {
+            break;
+        }
+
+        let matched = lot.quantity.abs().min(quantity.abs());
+        let matched = if lot.quantity.is_sign_positive() {
+            matched
+        } else {
+            -matched
+        };
+        realized += (price - lot.price) * matched;
+        lot.quantity -= matched;
+        quantity += matched;
+
+        if lot.quantity.is_zero() {
+            lots.pop_front();
+        }
+    }
+
+    if !quantity.is_zero() {
+        lots.push_back(Lot { quantity, price });
+    }
+
+    realized
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::Quotation;
+
+    fn money(value: Decimal) -> MoneyValue {
+        let quotation = Quotation::try_from(value).unwrap();
+        MoneyValue {
+            currency: "rub".to_string(),
+            units: quotation.units,
+            nano: quotation.nano,
+        }
+    }
+
+    fn operation(
+        seconds: i64,
+        figi: &str,
+        operation_type: OperationType,
+        quantity: i64,
+        quantity_rest: i64,
+        price: Decimal,
+    ) -> Operation {
+        Operation {
+            figi: figi.to_string(),
+            state: OperationState::Executed as i32,
+            operation_type: operation_type as i32,
+            quantity,
+            quantity_rest,
+            price: Some(money(price)),
+            date: Some(prost_types::Timestamp { seconds, nanos: 0 }),
+            ..Default::default()
+        }
+    }
+
+    fn position(figi: &str, quantity: i64, current_price: Decimal) -> PortfolioPosition {
+        PortfolioPosition {
+            figi: figi.to_string(),
+            quantity: Some(Quotation {
+                units: quantity,
+                nano: 0,
+            }),
+            current_price: Some(money(current_price)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fifo_buy_sell_sequence() {
+        let analyzer = PortfolioAnalyzer::new(
+            vec![
+                operation(3, "A", OperationType::Sell, 15, 0, dec!(130)),
+                operation(1, "A", OperationType::Buy, 10, 0, dec!(100)),
+                operation(2, "A", OperationType::Buy, 10, 0, dec!(120)),
+            ],
+            vec![position("A", 5, dec!(140))],
+        );
+
+        assert_eq!(Some(&dec!(350)), analyzer.realized_pnl().get("A"));
+        assert_eq!(Some(dec!(120)), analyzer.average_cost_basis("A"));
+        assert_eq!(Some(&dec!(100)), analyzer.unrealized_pnl().get("A"));
+        assert_eq!(dec!(700), analyzer.total_value(&HashMap::new()));
+        assert_eq!(
+            dec!(750),
+            analyzer.total_value(&HashMap::from([("A".to_string(), dec!(150))]))
+        );
+    }
+
+    #[test]
+    fn partial_fills() {
+        let mut canceled = operation(4, "A", OperationType::Buy, 100, 0, dec!(1));
+        canceled.state = OperationState::Canceled as i32;
+
+        let analyzer = PortfolioAnalyzer::new(
+            vec![
+                operation(1, "A", OperationType::Buy, 10, 4, dec!(100)),
+                operation(2, "A", OperationType::Sell, 5, 1, dec!(110)),
+                canceled,
+            ],
+            vec![],
+        );
+
+        assert_eq!(Some(&dec!(40)), analyzer.realized_pnl().get("A"));
+        assert_eq!(Some(dec!(100)), analyzer.average_cost_basis("A"));
+    }
+
+    #[test]
+    fn zero_position() {
+        let analyzer = PortfolioAnalyzer::new(
+            vec![
+                operation(1, "A", OperationType::Buy, 10, 0, dec!(100)),
+                operation(2, "A", OperationType::Sell, 10, 0, dec!(90)),
+                operation(3, "B", OperationType::Dividend, 0, 0, dec!(0)),
+            ],
+            vec![],
+        );
+
+        assert_eq!(Some(&dec!(-100)), analyzer.realized_pnl().get("A"));
+        assert_eq!(None, analyzer.average_cost_basis("A"));
+        assert_eq!(None, analyzer.average_cost_basis("B"));
+        assert!(analyzer.unrealized_pnl().is_empty());
+        assert_eq!(dec!(0), analyzer.total_value(&HashMap::new()));
+    }
+
+    #[test]
+    fn position_record() {
+        let mut api_position = position("BBG004730N88", 10, dec!(250.5));
+        api_position.instrument_type = "share".to_string();
+        api_position.average_position_price = Some(money(dec!(200.25)));
+        api_position.expected_yield = Some(Quotation::try_from(dec!(502.5)).unwrap());
+
+        assert_eq!(
+            PortfolioPositionRecord {
+                figi: "BBG004730N88".to_string(),
+                instrument_type: "share".to_string(),
+                quantity: dec!(10),
+                average_price: dec!(200.25),
+                current_price: Some(dec!(250.5)),
+                expected_yield: Some(dec!(502.5)),
+                current_nkd: None,
+                currency: "rub".to_string(),
+            },
+            PortfolioPositionRecord::from(api_position)
+        );
+
+        let record = PortfolioPositionRecord::from(PortfolioPosition::default());
+        assert_eq!(Decimal::ZERO, record.average_price);
+        assert_eq!(None, record.current_price);
+        assert_eq!("", record.currency);
+    }
+
+    #[test]
+    fn stress_test() {
+        let mut usd_share = position("B", 2, dec!(50));
+        usd_share.current_price.as_mut().unwrap().currency = "usd".to_string();
+        let mut cash = position("RUB000UTSTOM", 1000, dec!(1));
+        cash.instrument_type = "currency".to_string();
+
+        let portfolio = PortfolioResponse {
+            positions: vec![position("A", 10, dec!(100)), usd_share, cash],
+            ..Default::default()
+        };
+
+        let mut scenario = StressTestScenario::new();
+        scenario.exchange_rate("usd", dec!(90));
+        assert_eq!(dec!(11000), scenario.apply(&portfolio));
+
+        scenario.shock_all_positions(dec!(-20));
+        assert_eq!(
+            dec!(800) + dec!(7200) + dec!(1000),
+            scenario.apply(&portfolio)
+        );
+
+        scenario.shock_position("A", dec!(-50));
+        scenario.shock_currency("usd", dec!(10));
+        assert_eq!(
+            dec!(400) + dec!(7920) + dec!(1000),
+            scenario.apply(&portfolio)
+        );
+    }
+
+    #[test]
+    fn short_position() {
+        let analyzer = PortfolioAnalyzer::new(
+            vec![
+                operation(1, "A", OperationType::Sell, 10, 0, dec!(100)),
+                operation(2, "A", OperationType::Buy, 4, 0, dec!(90)),
+            ],
+            vec![],
+        );
+
+        assert_eq!(Some(&dec!(40)), analyzer.realized_pnl().get("A"));
+        assert_eq!(Some(dec!(100)), analyzer.average_cost_basis("A"));
+    }
+
+    #[test]
+    fn indexed_portfolio() {
+        let portfolio = IndexedPortfolio::from(PortfolioResponse {
+            total_amount_shares: Some(money(dec!(1500))),
+            total_amount_bonds: Some(money(dec!(1000.5))),
+            total_amount_currencies: Some(money(dec!(200))),
+            expected_yield: Some(Quotation::try_from(dec!(-1.25)).unwrap()),
+            positions: vec![
+                position("A", 10, dec!(150)),
+                position("B", 1, dec!(1000.5)),
+                position("RUB", 200, dec!(1)),
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(3, portfolio.positions.len());
+        assert_eq!(
+            Some(dec!(1000.5)),
+            portfolio
+                .position("B")
+                .and_then(|position| position.current_price.clone())
+                .map(Decimal::from)
+        );
+        assert!(portfolio.has_position("A"));
+        assert!(!portfolio.has_position("C"));
+        assert_eq!(dec!(10), portfolio.net_quantity("A"));
+        assert_eq!(Decimal::ZERO, portfolio.net_quantity("C"));
+        assert_eq!(dec!(2700.5), portfolio.total_value());
+        assert_eq!(dec!(-1.25), portfolio.expected_yield);
+        assert_eq!(Decimal::ZERO, portfolio.total_amount_futures);
+    }
+}
@@ -0,0 +1,269 @@
+//! Разложение результата портфеля по секторам, валютам и странам.
+//!
+//! Функции принимают позиции портфеля, результат по FIGI и справочник
+//! инструментов [`InstrumentUniverse`]. Позиции, для которых нет результата,
+//! пропускаются, а позиции, группу которых определить не удалось, попадают
+//! в группу [`UNKNOWN_GROUP`]. Повторяющиеся позиции учитываются один раз.
+
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::Decimal;
+
+use crate::PortfolioPositionRecord;
+use crate::api::{Bond, Etf, Share};
+
+/// Группа для инструментов без сектора, валюты или страны.
+pub const UNKNOWN_GROUP: &str = "unknown";
+
+/// Сведения об инструменте, используемые для группировки.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstrumentInfo {
+    /// Сектор экономики
+    pub sector: String,
+    /// Валюта расчётов в нижнем регистре
+    pub currency: String,
+    /// Код страны риска, например `RU`
+    pub country_of_risk: String,
+}
+
+impl From<&Share> for InstrumentInfo {
+    fn from(share: &Share) -> Self {
+        Self {
+            sector: share.sector.clone(),
+            currency: share.currency.to_lowercase(),
+            country_of_risk: share.country_of_risk.clone(),
+        }
+    }
+}
+
+impl From<&Bond> for InstrumentInfo {
+    fn from(bond: &Bond) -> Self {
+        Self {
+            sector: bond.sector.clone(),
+            currency: bond.currency.to_lowercase(),
+            country_of_risk: bond.country_of_risk.clone(),
+        }
+    }
+}
+
+impl From<&Etf> for InstrumentInfo {
+    fn from(etf: &Etf) -> Self {
+        Self {
+            sector: etf.sector.clone(),
+            currency: etf.currency.to_lowercase(),
+            country_of_risk: etf.country_of_risk.clone(),
+        }
+    }
+}
+
+/// Справочник инструментов по FIGI.
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentUniverse {
+    instruments: HashMap<String, InstrumentInfo>,
+}
+
+impl InstrumentUniverse {
+    /// Создаёт пустой справочник.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет или заменяет сведения об инструменте.
+    pub fn insert(&mut self, figi: &str, info: InstrumentInfo) -> &mut Self {
+        self.instruments.insert(figi.to_string(), info);
+        self
+    }
+
+    /// Добавляет акции.
+    pub fn add_shares(&mut self, shares: &[Share]) -> &mut Self {
+        for share in shares {
+            self.insert(&share.figi, share.into());
+        }
+        self
+    }
+
+    /// Добавляет облигации.
+    pub fn add_bonds(&mut self, bonds: &[Bond]) -> &mut Self {
+        for bond in bonds {
+            self.insert(&bond.figi, bond.into());
+        }
+        self
+    }
+
+    /// Добавляет фонды.
+    pub fn add_etfs(&mut self, etfs: &[Etf]) -> &mut Self {
+        for etf in etfs {
+            self.insert(&etf.figi, etf.into());
+        }
+        self
+    }
+
+    /// Возвращает сведения об инструменте.
+    pub fn get(&self, figi: &str) -> Option<&InstrumentInfo> {
+        self.instruments.get(figi)
+    }
+}
+
+/// Суммирует результат позиций по секторам.
+pub fn aggregate_pnl_by_sector(
+    positions: &[PortfolioPositionRecord],
+    pnl: &HashMap<String, Decimal>,
+    universe: &InstrumentUniverse,
+) -> HashMap<String, Decimal> {
+    aggregate(positions, pnl, |position| {
+        universe.get(&position.figi).map(|info| info.sector.clone())
+    })
+}
+
+/// Суммирует результат позиций по валютам.
+///
+/// Используется валюта позиции, а если она не заполнена — валюта инструмента из справочника.
+pub fn aggregate_pnl_by_currency(
+    positions: &[PortfolioPositionRecord],
+    pnl: &HashMap<String, Decimal>,
+    universe: &InstrumentUniverse,
+) -> HashMap<String, Decimal> {
+    aggregate(positions, pnl, |position| {
+        Some(position.currency.to_lowercase())
+            .filter(|currency| !currency.is_empty())
+            .or_else(|| {
+                universe
+                    .get(&position.figi)
+                    .map(|info| info.currency.clone())
+            })
+    })
+}
+
+/// Суммирует результат позиций по странам риска.
+pub fn aggregate_pnl_by_country(
+    positions: &[PortfolioPositionRecord],
+    pnl: &HashMap<String, Decimal>,
+    universe: &InstrumentUniverse,
+) -> HashMap<String, Decimal> {
+    aggregate(positions, pnl, |position| {
+        universe
+            .get(&position.figi)
+            .map(|info| info.country_of_risk.clone())
+    })
+}
+
+/// Суммирует результат позиций по группам.
+fn aggregate(
+    positions: &[PortfolioPositionRecord],
+    pnl: &HashMap<String, Decimal>,
+    group: impl Fn(&PortfolioPositionRecord) -> Option<String>,
+) -> HashMap<String, Decimal> {
+    let mut seen = HashSet::new();
+    let mut groups: HashMap<String, Decimal> = HashMap::new();
+    for position in positions {
+        if !seen.insert(position.figi.as_str()) {
+            continue;
+        }
+        let Some(value) = pnl.get(&position.figi) else {
+            continue;
+        };
+
+        let name = group(position)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| UNKNOWN_GROUP.to_string());
+        *groups.entry(name).or_default() += value;
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    fn position(figi: &str) -> PortfolioPositionRecord {
+        PortfolioPositionRecord {
+            figi: figi.to_string(),
+            instrument_type: "share".to_string(),
+            quantity: dec!(10),
+            average_price: dec!(100),
+            current_price: None,
+            expected_yield: None,
+            current_nkd: None,
+            currency: String::new(),
+        }
+    }
+
+    fn share(figi: &str, sector: &str, currency: &str, country: &str) -> Share {
+        Share {
+            figi: figi.to_string(),
+            sector: sector.to_string(),
+            currency: currency.to_string(),
+            country_of_risk: country.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn attribution_by_groups() {
+        let mut universe = InstrumentUniverse::new();
+        universe
+            .add_shares(&[
+                share("SBER", "financial", "RUB", "RU"),
+                share("VTBR", "financial", "rub", "RU"),
+                share("AAPL", "it", "usd", "US"),
+            ])
+            .add_bonds(&[Bond {
+                figi: "OFZ".to_string(),
+                currency: "rub".to_string(),
+                country_of_risk: "RU".to_string(),
+                ..Default::default()
+            }]);
+
+        let positions = vec![
+            position("SBER"),
+            position("VTBR"),
+            position("AAPL"),
+            position("OFZ"),
+            position("UNLISTED"),
+            position("NO_PNL"),
+            position("SBER"),
+            PortfolioPositionRecord {
+                currency: "EUR".to_string(),
+                ..position("EUROBOND")
+            },
+        ];
+        let pnl = HashMap::from([
+            ("SBER".to_string(), dec!(1500)),
+            ("VTBR".to_string(), dec!(-300)),
+            ("AAPL".to_string(), dec!(42.5)),
+            ("OFZ".to_string(), dec!(120)),
+            ("UNLISTED".to_string(), dec!(-10)),
+            ("EUROBOND".to_string(), dec!(5)),
+        ]);
+        let group = |name: &str, value| (name.to_string(), value);
+
+        assert_eq!(
+            HashMap::from([
+                group("financial", dec!(1200)),
+                group("it", dec!(42.5)),
+                group(UNKNOWN_GROUP, dec!(115)),
+            ]),
+            aggregate_pnl_by_sector(&positions, &pnl, &universe)
+        );
+        assert_eq!(
+            HashMap::from([
+                group("rub", dec!(1320)),
+                group("usd", dec!(42.5)),
+                group("eur", dec!(5)),
+                group(UNKNOWN_GROUP, dec!(-10)),
+            ]),
+            aggregate_pnl_by_currency(&positions, &pnl, &universe)
+        );
+        assert_eq!(
+            HashMap::from([
+                group("RU", dec!(1320)),
+                group("US", dec!(42.5)),
+                group(UNKNOWN_GROUP, dec!(-5)),
+            ]),
+            aggregate_pnl_by_country(&positions, &pnl, &universe)
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::channel::mpsc::{self, UnboundedSender};
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt};
+use rust_decimal::Decimal;
+use tokio::time::{Instant, interval_at};
+use tonic::{service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    LastPrice, MarketDataRequest, MarketDataResponse, PortfolioPosition, PortfolioRequest,
+    PortfolioResponse, market_data_request, market_data_response,
+    market_data_stream_service_client::MarketDataStreamServiceClient,
+    operations_service_client::OperationsServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor, subscriptions};
+
+/// Периодический запрос портфеля для обнаружения новых позиций.
+struct PortfolioPoll {
+    client: OperationsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    interval: Duration,
+}
+
+/// Стрим стоимости портфеля в реальном времени.
+///
+/// Стоимость рассчитывается как сумма `quantity * price` по позициям портфеля.
+/// Сначала используются текущие цены из ответа `GetPortfolio`, затем они заменяются
+/// последними ценами из стрима рыночных данных. Валюты позиций не пересчитываются,
+/// а цены облигаций и фьючерсов в стриме приходят в пунктах, поэтому стрим подходит
+/// для портфелей из акций и фондов в одной валюте.
+///
+/// ```no_run
+/// # use futures::StreamExt;
+/// # use std::time::Duration;
+/// # use t_invest_sdk::{PortfolioValueStream, TInvestSdk};
+/// # async fn run(sdk: TInvestSdk) -> Result<(), t_invest_sdk::TInvestError> {
+/// let portfolio = sdk.get_portfolio("account-id").await?;
+/// let mut values = PortfolioValueStream::new(portfolio, sdk.market_data_stream())
+///     .with_portfolio_poll(sdk.operations(), Duration::from_secs(60))
+///     .start()
+///     .await?;
+///
+/// while let Some(value) = values.next().await {
+///     println!("{}", value?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PortfolioValueStream {
+    portfolio: PortfolioResponse,
+    client: MarketDataStreamServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    poll: Option<PortfolioPoll>,
+}
+
+impl PortfolioValueStream {
+    /// Создаёт стрим по начальному портфелю.
+    pub fn new(
+        portfolio: PortfolioResponse,
+        client: MarketDataStreamServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    ) -> Self {
+        Self {
+            portfolio,
+            client,
+            poll: None,
+        }
+    }
+
+    /// Включает периодический запрос портфеля по счёту `account_id` начального портфеля.
+    ///
+    /// На цены новых позиций стрим подписывается автоматически, закрытые позиции
+    /// перестают учитываться. Ошибки запроса портфеля возвращаются из стрима,
+    /// после чего опрос продолжается.
+    pub fn with_portfolio_poll(
+        mut self,
+        client: OperationsServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+        interval: Duration,
+    ) -> Self {
+        self.poll = Some(PortfolioPoll { client, interval });
+        self
+    }
+
+    /// Подписывается на последние цены и возвращает стрим стоимости портфеля.
+    ///
+    /// Первое значение рассчитывается по текущим ценам портфеля, следующие —
+    /// при каждом изменении цены или состава портфеля. Стрим завершается
+    /// после ошибки стрима рыночных данных.
+    pub async fn start(
+        mut self,
+    ) -> Result<BoxStream<'static, Result<Decimal, TInvestError>>, TInvestError> {
+        let valuation = PortfolioValuation::new(&self.portfolio);
+        let (subscriptions, requests) = mpsc::unbounded();
+        subscribe(&subscriptions, &valuation.instrument_ids());
+
+        let prices = self
+            .client
+            .market_data_stream(requests)
+            .await?
+            .into_inner()
+            .map(Event::Price)
+            .chain(stream::once(async { Event::End }));
+
+        let events = match self.poll {
+            Some(poll) => stream::select(
+                prices,
+                portfolio_polls(poll, self.portfolio.account_id.clone()),
+            )
+            .boxed(),
+            None => prices.boxed(),
+        };
+
+        Ok(valuations(valuation, events, subscriptions).boxed())
+    }
+}
+
+/// События, влияющие на стоимость портфеля.
+#[allow(clippy::large_enum_variant)]
+enum Event {
+    Price(Result<MarketDataResponse, tonic::Status>),
+    Portfolio(Result<PortfolioResponse, tonic::Status>),
+    End,
+}
+
+fn portfolio_polls(poll: PortfolioPoll, account_id: String) -> impl Stream<Item = Event> + Send {
+    let ticks = interval_at(Instant::now() + poll.interval, poll.interval);
+
+    stream::unfold((poll.client, ticks), move |(mut client, mut ticks)| {
+        let account_id = account_id.clone();
+        async move {
+            ticks.tick().await;
+            let portfolio = client
+                .get_portfolio(PortfolioRequest {
+                    account_id,
+                    ..Default::default()
+                })
+                .await
+                .map(tonic::Response::into_inner);
+
+            Some((Event::Portfolio(portfolio), (client, ticks)))
+        }
+    })
+}
+
+/// Отправляет запрос подписки на последние цены инструментов.
+fn subscribe(subscriptions: &UnboundedSender<MarketDataRequest>, instrument_ids: &[String]) {
+    if instrument_ids.is_empty() {
+        return;
+    }
+
+    let mut builder = subscriptions::last_price();
+    for instrument_id in instrument_ids {
+        builder.add_instrument(instrument_id);
+    }
+
+    // Ошибка означает, что стрим рыночных данных уже закрыт.
+    let _ = subscriptions.unbounded_send(MarketDataRequest {
+        payload: Some(market_data_request::Payload::SubscribeLastPriceRequest(
+            builder.build(),
+        )),
+    });
+}
+
+/// Преобразует события в стрим стоимости портфеля.
+fn valuations<S>(
+    valuation: PortfolioValuation,
+    events: S,
+    subscriptions: UnboundedSender<MarketDataRequest>,
+) -> impl Stream<Item = Result<Decimal, TInvestError>>
+where
+    S: Stream<Item = Event> + Unpin,
+{
+    let initial = valuation.total();
+
+    stream::once(async move { Ok(initial) }).chain(stream::unfold(
+        Some((valuation, events, subscriptions)),
+        |state| async move {
+            let (mut valuation, mut events, subscriptions) = state?;
+
+            loop {
+                let changed = match events.next().await? {
+                    Event::Price(Ok(response)) => match response.payload {
+                        Some(market_data_response::Payload::LastPrice(last_price)) => {
+                            valuation.update_price(&last_price)
+                        }
+                        _ => false,
+                    },
+                    Event::Price(Err(status)) => return Some((Err(status.into()), None)),
+                    Event::Portfolio(Ok(portfolio)) => {
+                        let before = valuation.total();
+                        subscribe(&subscriptions, &valuation.update_portfolio(&portfolio));
+                        valuation.total() != before
+                    }
+                    Event::Portfolio(Err(status)) => {
+                        return Some((
+                            Err(status.into()),
+                            Some((valuation, events, subscriptions)),
+                        ));
+                    }
+                    Event::End => return None,
+                };
+
+                if changed {
+                    let total = valuation.total();
+                    return Some((Ok(total), Some((valuation, events, subscriptions))));
+                }
+            }
+        },
+    ))
+}
+
+/// Количество и последняя цена позиций по идентификатору инструмента.
+#[derive(Debug, Default)]
+struct PortfolioValuation {
+    positions: HashMap<String, (Decimal, Decimal)>,
+    figis: HashMap<String, String>,
+}
+
+impl PortfolioValuation {
+    fn new(portfolio: &PortfolioResponse) -> Self {
+        let mut valuation = Self::default();
+        valuation.update_portfolio(portfolio);
+        valuation
+    }
+
+    fn instrument_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.positions.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    fn total(&self) -> Decimal {
+        self.positions
+            .values()
+            .map(|(quantity, price)| quantity * price)
+            .sum()
+    }
+
+    /// Обновляет цену позиции, возвращает `true`, если цена изменилась.
+    fn update_price(&mut self, last_price: &LastPrice) -> bool {
+        let Some(price) = last_price.price.map(Decimal::from) else {
+            return false;
+        };
+
+        let id = if self.positions.contains_key(&last_price.instrument_uid) {
+            &last_price.instrument_uid
+        } else {
+            match self.figis.get(&last_price.figi) {
+                Some(id) => id,
+                None => return false,
+            }
+        };
+
+        match self.positions.get_mut(id) {
+            Some((_, current)) if *current != price => {
+                *current = price;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Заменяет состав портфеля, сохраняя цены из стрима для известных позиций.
+    ///
+    /// Возвращает идентификаторы новых позиций.
+    fn update_portfolio(&mut self, portfolio: &PortfolioResponse) -> Vec<String> {
+        let mut positions = HashMap::new();
+        let mut figis = HashMap::new();
+        let mut new_ids = Vec::new();
+
+        for position in &portfolio.positions {
+            let id = instrument_id(position);
+            let quantity = position.quantity.map(Decimal::from).unwrap_or_default();
+            let price = match self.positions.get(&id) {
+                Some((_, price)) => *price,
+                None => {
+                    new_ids.push(id.clone());
+                    position
+                        .current_price
+                        .clone()
+                        .map(Decimal::from)
+                        .unwrap_or_default()
+                }
+            };
+
+            figis.insert(position.figi.clone(), id.clone());
+            positions.insert(id, (quantity, price));
+        }
+
+        self.positions = positions;
+        self.figis = figis;
+        new_ids.sort();
+        new_ids
+    }
+}
+
+fn instrument_id(position: &PortfolioPosition) -> String {
+    if position.instrument_uid.is_empty() {
+        position.figi.clone()
+    } else {
+        position.instrument_uid.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, Quotation};
+
+    fn position(uid: &str, quantity: i64, price: Decimal) -> PortfolioPosition {
+        let price = Quotation::try_from(price).unwrap();
+        PortfolioPosition {
+            figi: format!("FIGI-{}", uid),
+            instrument_uid: uid.to_string(),
+            quantity: Some(Quotation {
+                units: quantity,
+                nano: 0,
+            }),
+            current_price: Some(MoneyValue {
+                currency: "rub".to_string(),
+                units: price.units,
+                nano: price.nano,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn price(figi: &str, uid: &str, price: Decimal) -> Event {
+        Event::Price(Ok(MarketDataResponse {
+            payload: Some(market_data_response::Payload::LastPrice(LastPrice {
+                figi: figi.to_string(),
+                instrument_uid: uid.to_string(),
+                price: Some(Quotation::try_from(price).unwrap()),
+                ..Default::default()
+            })),
+        }))
+    }
+
+    fn portfolio(positions: Vec<PortfolioPosition>) -> PortfolioResponse {
+        PortfolioResponse {
+            positions,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn recomputes_on_price_updates() {
+        let valuation = PortfolioValuation::new(&portfolio(vec![
+            position("a", 10, dec!(100)),
+            position("b", 2, dec!(50)),
+        ]));
+        let (subscriptions, _requests) = mpsc::unbounded();
+        let events = stream::iter(vec![
+            price("", "a", dec!(110)),
+            price("", "a", dec!(110)),
+            price("FIGI-b", "", dec!(40)),
+            price("", "unknown", dec!(1)),
+            Event::End,
+            price("", "a", dec!(120)),
+        ]);
+
+        let values: Vec<Decimal> = valuations(valuation, events, subscriptions)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(vec![dec!(1100), dec!(1200), dec!(1180)], values);
+    }
+
+    #[tokio::test]
+    async fn subscribes_to_new_positions() {
+        let valuation = PortfolioValuation::new(&portfolio(vec![position("a", 10, dec!(100))]));
+        let (subscriptions, mut requests) = mpsc::unbounded();
+        let events = stream::iter(vec![
+            price("", "a", dec!(110)),
+            Event::Portfolio(Ok(portfolio(vec![
+                position("a", 10, dec!(100)),
+                position("c", 1, dec!(500)),
+            ]))),
+            Event::Portfolio(Err(tonic::Status::unavailable("poll failed"))),
+            price("", "c", dec!(400)),
+            Event::Price(Err(tonic::Status::internal("stream failed"))),
+            price("", "c", dec!(300)),
+        ]);
+
+        let values: Vec<Result<Decimal, TInvestError>> =
+            valuations(valuation, events, subscriptions).collect().await;
+
+        let values: Vec<Option<Decimal>> = values.into_iter().map(Result::ok).collect();
+        assert_eq!(
+            vec![
+                Some(dec!(1000)),
+                Some(dec!(1100)),
+                Some(dec!(1600)),
+                None,
+                Some(dec!(1500)),
+                None,
+            ],
+            values
+        );
+
+        let request = requests.next().await.unwrap();
+        let Some(market_data_request::Payload::SubscribeLastPriceRequest(subscription)) =
+            request.payload
+        else {
+            panic!("unexpected request");
+        };
+        assert_eq!(
+            vec!["c".to_string()],
+            subscription
+                .instruments
+                .into_iter()
+                .map(|instrument| instrument.instrument_id)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Расчёты стоимости позиции и изменения результата по ней.
+//!
+//! Количество задаётся в лотах, цена — за один инструмент. Для короткой
+//! позиции количество отрицательно.
+
+use rust_decimal::Decimal;
+
+/// Рассчитывает изменение результата позиции при движении цены
+/// `(new_price - old_price) * quantity * lot_size`.
+///
+/// # Аргументы
+/// * `old_price` - Предыдущая цена одного инструмента
+/// * `new_price` - Новая цена одного инструмента
+/// * `quantity` - Количество лотов, отрицательное для короткой позиции
+/// * `lot_size` - Количество инструментов в лоте
+pub fn position_delta_pnl(
+    old_price: Decimal,
+    new_price: Decimal,
+    quantity: i64,
+    lot_size: i32,
+) -> Decimal {
+    (new_price - old_price) * Decimal::from(quantity) * Decimal::from(lot_size)
+}
+
+/// Рассчитывает стоимость позиции `price * quantity * lot_size`.
+///
+/// Для короткой позиции стоимость отрицательна.
+pub fn position_total_value(price: Decimal, quantity: i64, lot_size: i32) -> Decimal {
+    price * Decimal::from(quantity) * Decimal::from(lot_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn delta_and_value() {
+        assert_eq!(
+            dec!(250),
+            position_delta_pnl(dec!(250), dec!(252.5), 10, 10)
+        );
+        assert_eq!(
+            dec!(-250),
+            position_delta_pnl(dec!(250), dec!(252.5), -10, 10)
+        );
+        assert_eq!(
+            Decimal::ZERO,
+            position_delta_pnl(dec!(250), dec!(250), 10, 10)
+        );
+
+        assert_eq!(dec!(25250), position_total_value(dec!(252.5), 10, 10));
+        assert_eq!(dec!(-25250), position_total_value(dec!(252.5), -10, 10));
+        assert_eq!(Decimal::ZERO, position_total_value(dec!(252.5), 0, 10));
+    }
+}
@@ -0,0 +1,618 @@
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::api::Quotation;
+
+/// Максимальное количество знаков после запятой, представимое в Quotation.
+const MAX_SCALE: u32 = 9;
+
+/// Количество нано-единиц в одной целой единице.
+const NANOS_PER_UNIT: i128 = 1_000_000_000;
+
+/// Разделители разрядов в русской локали: пробел, неразрывный и узкие пробелы.
+const THOUSANDS_SEPARATORS: [char; 4] = [' ', '\u{00A0}', '\u{2009}', '\u{202F}'];
+
+/// Ошибки разбора Quotation из строки.
+///
+/// - `DecimalParseError`: Строка не является десятичным числом
+/// - `ConversionError`: Число не может быть представлено как Quotation
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum QuotationParseError {
+    #[error(transparent)]
+    DecimalParseError(#[from] rust_decimal::Error),
+    #[error("{0}")]
+    ConversionError(String),
+}
+
+impl Quotation {
+    /// Проверяет, равно ли значение нулю.
+    pub fn is_zero(&self) -> bool {
+        self.units == 0 && self.nano == 0
+    }
+
+    /// Проверяет, является ли значение строго положительным.
+    ///
+    /// При `units == 0` знак определяется полем `nano`.
+    pub fn is_positive(&self) -> bool {
+        self.units > 0 || (self.units == 0 && self.nano > 0)
+    }
+
+    /// Проверяет, является ли значение строго отрицательным.
+    ///
+    /// При `units == 0` знак определяется полем `nano`.
+    pub fn is_negative(&self) -> bool {
+        self.units < 0 || (self.units == 0 && self.nano < 0)
+    }
+
+    /// Возвращает абсолютное значение.
+    ///
+    /// Модуль значений меньше `-i64::MAX` не представим и ограничивается `i64::MAX`.
+    pub fn abs(&self) -> Quotation {
+        Self::from_nanos(self.nanos().abs())
+    }
+
+    /// Округляет значение до `decimal_places` знаков после запятой.
+    ///
+    /// Половина округляется от нуля, как принято на бирже: `0.015` даёт `0.02`,
+    /// а `-0.015` — `-0.02`. При `decimal_places >= 9` значение не меняется.
+    pub fn round(self, decimal_places: u32) -> Quotation {
+        if decimal_places >= MAX_SCALE {
+            return self;
+        }
+
+        let step = 10i128.pow(MAX_SCALE - decimal_places);
+        Self::from_nanos(round_nanos(self.nanos(), step))
+    }
+
+    /// Округляет значение до ближайшего кратного шагу цены `tick`.
+    ///
+    /// Половина округляется от нуля, знак `tick` не учитывается.
+    /// При нулевом `tick` значение не меняется.
+    pub fn round_to_tick(self, tick: Quotation) -> Quotation {
+        let step = tick.nanos().abs();
+        if step == 0 {
+            return self;
+        }
+
+        Self::from_nanos(round_nanos(self.nanos(), step))
+    }
+
+    /// Разбирает цену в русской локали, например `"114,25"` или `"1 500,00"`.
+    ///
+    /// Запятая считается десятичным разделителем, пробелы (в том числе
+    /// неразрывные и узкие) — разделителями разрядов.
+    pub fn from_price_string_ru(s: &str) -> Result<Quotation, String> {
+        let normalized: String = s
+            .trim()
+            .chars()
+            .filter(|c| !THOUSANDS_SEPARATORS.contains(c))
+            .map(|c| if c == ',' { '.' } else { c })
+            .collect();
+
+        normalized
+            .parse()
+            .map_err(|err: QuotationParseError| format!("Can't parse price {:?}: {}", s, err))
+    }
+
+    fn nanos(self) -> i128 {
+        i128::from(self.units) * NANOS_PER_UNIT + i128::from(self.nano)
+    }
+
+    /// Собирает Quotation из количества нано-единиц, насыщая целую часть до границ `i64`.
+    fn from_nanos(nanos: i128) -> Quotation {
+        let units = nanos / NANOS_PER_UNIT;
+        match i64::try_from(units) {
+            Ok(units) => Quotation {
+                units,
+                nano: (nanos % NANOS_PER_UNIT) as i32,
+            },
+            Err(_) if units > 0 => Quotation {
+                units: i64::MAX,
+                nano: 0,
+            },
+            Err(_) => Quotation {
+                units: i64::MIN,
+                nano: 0,
+            },
+        }
+    }
+}
+
+/// Округляет количество нано-единиц до кратного `step`, половина округляется от нуля.
+fn round_nanos(nanos: i128, step: i128) -> i128 {
+    let remainder = nanos % step;
+    let truncated = nanos - remainder;
+    if remainder.abs() * 2 >= step {
+        truncated + remainder.signum() * step
+    } else {
+        truncated
+    }
+}
+
+/// Разбирает Quotation из десятичной строки, например `"114.25"` или `"-0.01"`.
+///
+/// Возвращает ошибку, если строка не является числом или содержит
+/// больше 9 знаков после запятой.
+impl FromStr for Quotation {
+    type Err = QuotationParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.parse::<Decimal>()?;
+
+        if value.normalize().scale() > MAX_SCALE {
+            return Err(QuotationParseError::ConversionError(format!(
+                "Can't convert decimal {} to quotation",
+                value
+            )));
+        }
+
+        Quotation::try_from(value).map_err(QuotationParseError::ConversionError)
+    }
+}
+
+/// Разбирает Quotation из десятичной строки. См. [`FromStr`] для Quotation.
+impl TryFrom<&str> for Quotation {
+    type Error = QuotationParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Разбирает Quotation из десятичной строки. См. [`FromStr`] для Quotation.
+impl TryFrom<String> for Quotation {
+    type Error = QuotationParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.as_str().parse()
+    }
+}
+
+/// Преобразует целочисленную Quotation в целое число.
+///
+/// Удобно для количественных полей, например лотов в заявках. Возвращает ошибку,
+/// если у значения есть дробная часть или оно не помещается в целевой тип.
+macro_rules! impl_try_from_quotation {
+    ($($target:ty),*) => {
+        $(
+            impl TryFrom<Quotation> for $target {
+                type Error = String;
+
+                fn try_from(quotation: Quotation) -> Result<Self, Self::Error> {
+                    if quotation.nano != 0 {
+                        return Err(format!(
+                            "Can't convert fractional quotation {} to {}",
+                            Decimal::from(quotation),
+                            stringify!($target)
+                        ));
+                    }
+
+                    <$target>::try_from(quotation.units).map_err(|_| {
+                        format!(
+                            "Can't convert quotation {} to {}",
+                            quotation.units,
+                            stringify!($target)
+                        )
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_quotation!(i32, u32, i64, u64);
+
+/// Преобразует целое число в Quotation с нулевой дробной частью.
+impl From<i64> for Quotation {
+    fn from(units: i64) -> Self {
+        Quotation { units, nano: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::api::MoneyValue;
+
+    #[test]
+    fn quotation_sign() {
+        let zero = Quotation { units: 0, nano: 0 };
+        assert!(zero.is_zero());
+        assert!(!zero.is_positive());
+        assert!(!zero.is_negative());
+
+        let small_positive = Quotation { units: 0, nano: 1 };
+        assert!(!small_positive.is_zero());
+        assert!(small_positive.is_positive());
+        assert!(!small_positive.is_negative());
+
+        let small_negative = Quotation { units: 0, nano: -1 };
+        assert!(!small_negative.is_zero());
+        assert!(!small_negative.is_positive());
+        assert!(small_negative.is_negative());
+
+        let negative = Quotation { units: -1, nano: 0 };
+        assert!(!negative.is_zero());
+        assert!(!negative.is_positive());
+        assert!(negative.is_negative());
+
+        let positive = Quotation {
+            units: 114,
+            nano: 250000000,
+        };
+        assert!(positive.is_positive());
+        assert!(!positive.is_negative());
+    }
+
+    #[test]
+    fn str_to_quotation() {
+        assert_eq!(Ok(Quotation { units: 0, nano: 0 }), "0".parse());
+
+        assert_eq!(
+            Ok(Quotation {
+                units: 114,
+                nano: 250000000,
+            }),
+            "114.25".try_into()
+        );
+
+        assert_eq!(
+            Ok(Quotation {
+                units: -200,
+                nano: -200000000,
+            }),
+            String::from("-200.20").try_into()
+        );
+
+        assert_eq!(
+            Ok(Quotation {
+                units: -0,
+                nano: -10000000,
+            }),
+            "-0.01".parse()
+        );
+
+        assert_eq!(
+            Ok(Quotation {
+                units: 999,
+                nano: 999999999,
+            }),
+            "999.999999999".parse()
+        );
+
+        assert_eq!(
+            Ok(Quotation {
+                units: -999,
+                nano: -999999999,
+            }),
+            "-999.999999999".parse()
+        );
+
+        assert_eq!(
+            Ok(Quotation {
+                units: 1,
+                nano: 500000000,
+            }),
+            "1.500000000000".parse()
+        );
+    }
+
+    #[test]
+    fn str_to_quotation_errors() {
+        assert!(matches!(
+            "NaN".parse::<Quotation>(),
+            Err(QuotationParseError::DecimalParseError(_))
+        ));
+
+        assert!(matches!(
+            "inf".parse::<Quotation>(),
+            Err(QuotationParseError::DecimalParseError(_))
+        ));
+
+        assert!(matches!(
+            "".parse::<Quotation>(),
+            Err(QuotationParseError::DecimalParseError(_))
+        ));
+
+        assert!(matches!(
+            "0.0000000001".parse::<Quotation>(),
+            Err(QuotationParseError::ConversionError(_))
+        ));
+    }
+
+    #[test]
+    fn quotation_abs() {
+        assert_eq!(
+            Quotation { units: 0, nano: 0 },
+            Quotation { units: 0, nano: 0 }.abs()
+        );
+
+        assert_eq!(
+            Quotation { units: 0, nano: 1 },
+            Quotation { units: 0, nano: -1 }.abs()
+        );
+
+        assert_eq!(
+            Quotation { units: 1, nano: 0 },
+            Quotation { units: -1, nano: 0 }.abs()
+        );
+
+        assert_eq!(
+            Quotation {
+                units: 200,
+                nano: 200000000
+            },
+            Quotation {
+                units: -200,
+                nano: -200000000
+            }
+            .abs()
+        );
+
+        assert_eq!(
+            Quotation {
+                units: 114,
+                nano: 250000000
+            },
+            Quotation {
+                units: 114,
+                nano: 250000000
+            }
+            .abs()
+        );
+
+        assert_eq!(
+            Quotation {
+                units: i64::MAX,
+                nano: 0
+            },
+            Quotation {
+                units: i64::MIN,
+                nano: 0
+            }
+            .abs()
+        );
+        assert_eq!(
+            Quotation {
+                units: i64::MAX,
+                nano: 0
+            },
+            Quotation {
+                units: i64::MIN,
+                nano: -999_999_999
+            }
+            .abs()
+        );
+        assert_eq!(
+            Quotation {
+                units: i64::MAX,
+                nano: 999_999_999
+            },
+            Quotation {
+                units: -i64::MAX,
+                nano: -999_999_999
+            }
+            .abs()
+        );
+    }
+
+    #[test]
+    fn quotation_to_integer() {
+        let quotation = |units: i64, nano: i32| Quotation { units, nano };
+
+        assert_eq!(Ok(100), i64::try_from(quotation(100, 0)));
+        assert_eq!(Ok(-100), i32::try_from(quotation(-100, 0)));
+        assert_eq!(Ok(100), u32::try_from(quotation(100, 0)));
+        assert_eq!(Ok(100), u64::try_from(quotation(100, 0)));
+
+        assert!(i64::try_from(quotation(1, 500_000_000)).is_err());
+        assert!(u64::try_from(quotation(0, -1)).is_err());
+        assert!(u32::try_from(quotation(-1, 0)).is_err());
+        assert!(u64::try_from(quotation(-1, 0)).is_err());
+
+        assert_eq!(Ok(i64::MAX), i64::try_from(quotation(i64::MAX, 0)));
+        assert_eq!(Ok(i32::MIN), i32::try_from(quotation(i32::MIN as i64, 0)));
+        assert_eq!(Ok(u32::MAX), u32::try_from(quotation(u32::MAX as i64, 0)));
+        assert!(i32::try_from(quotation(i32::MAX as i64 + 1, 0)).is_err());
+        assert!(u32::try_from(quotation(u32::MAX as i64 + 1, 0)).is_err());
+    }
+
+    #[test]
+    fn integer_to_quotation() {
+        assert_eq!(Quotation { units: 42, nano: 0 }, Quotation::from(42));
+        assert_eq!(Quotation { units: -7, nano: 0 }, (-7i64).into());
+    }
+
+    #[test]
+    fn quotation_round() {
+        let quotation = |units: i64, nano: i32| Quotation { units, nano };
+
+        assert_eq!(quotation(0, 20_000_000), quotation(0, 15_000_000).round(2));
+        assert_eq!(
+            quotation(0, -20_000_000),
+            quotation(0, -15_000_000).round(2)
+        );
+        assert_eq!(quotation(0, 10_000_000), quotation(0, 14_999_999).round(2));
+        assert_eq!(
+            quotation(0, -10_000_000),
+            quotation(0, -14_999_999).round(2)
+        );
+
+        assert_eq!(
+            quotation(114, 300_000_000),
+            quotation(114, 250_000_000).round(1)
+        );
+        assert_eq!(quotation(114, 0), quotation(114, 250_000_000).round(0));
+        assert_eq!(quotation(-200, 0), quotation(-200, -200_000_000).round(0));
+        assert_eq!(quotation(1000, 0), quotation(999, 999_999_999).round(2));
+        assert_eq!(quotation(-1000, 0), quotation(-999, -999_999_999).round(8));
+        assert_eq!(
+            quotation(999, 999_999_999),
+            quotation(999, 999_999_999).round(9)
+        );
+        assert_eq!(quotation(0, 0), quotation(0, -1).round(2));
+        assert_eq!(
+            quotation(i64::MAX, 0),
+            quotation(i64::MAX, 500_000_000).round(0)
+        );
+    }
+
+    #[test]
+    fn quotation_round_to_tick() {
+        let quotation = |units: i64, nano: i32| Quotation { units, nano };
+        let tick = quotation(0, 50_000_000);
+
+        assert_eq!(
+            quotation(114, 250_000_000),
+            quotation(114, 250_000_000).round_to_tick(tick)
+        );
+        assert_eq!(
+            quotation(114, 250_000_000),
+            quotation(114, 260_000_000).round_to_tick(tick)
+        );
+        assert_eq!(
+            quotation(114, 300_000_000),
+            quotation(114, 275_000_000).round_to_tick(tick)
+        );
+        assert_eq!(
+            quotation(-114, -300_000_000),
+            quotation(-114, -275_000_000).round_to_tick(tick)
+        );
+        assert_eq!(
+            quotation(-114, -300_000_000),
+            quotation(-114, -275_000_000).round_to_tick(quotation(0, -50_000_000))
+        );
+
+        assert_eq!(
+            quotation(-200, 0),
+            quotation(-202, -400_000_000).round_to_tick(quotation(5, 0))
+        );
+        assert_eq!(
+            quotation(202, 500_000_000),
+            quotation(202, 400_000_000).round_to_tick(quotation(2, 500_000_000))
+        );
+        assert_eq!(
+            quotation(0, -10_000_000),
+            quotation(0, -10_000_000).round_to_tick(quotation(0, 0))
+        );
+    }
+
+    #[test]
+    fn russian_price_string() {
+        let quotation = |units: i64, nano: i32| Quotation { units, nano };
+
+        assert_eq!(
+            Ok(quotation(114, 250_000_000)),
+            Quotation::from_price_string_ru("114,25")
+        );
+        assert_eq!(
+            Ok(quotation(1500, 0)),
+            Quotation::from_price_string_ru("1 500,00")
+        );
+        assert_eq!(
+            Ok(quotation(1_234_567, 890_000_000)),
+            Quotation::from_price_string_ru("1\u{2009}234\u{202F}567,89")
+        );
+        assert_eq!(
+            Ok(quotation(-200, -200_000_000)),
+            Quotation::from_price_string_ru(" -200,20\u{00A0}")
+        );
+        assert_eq!(Ok(quotation(42, 0)), Quotation::from_price_string_ru("42"));
+
+        assert!(Quotation::from_price_string_ru("").is_err());
+        assert!(Quotation::from_price_string_ru("1,500,00").is_err());
+        assert!(Quotation::from_price_string_ru("114,25 ₽").is_err());
+    }
+
+    #[test]
+    fn quotation_as_map_key() {
+        // prost выводит `Eq` и `Hash` по полям `units` и `nano`.
+        let mut volumes: HashMap<Quotation, Decimal> = HashMap::new();
+        *volumes
+            .entry(Quotation {
+                units: 100,
+                nano: 500_000_000,
+            })
+            .or_default() += Decimal::from(3);
+        *volumes
+            .entry(Quotation {
+                units: 100,
+                nano: 500_000_000,
+            })
+            .or_default() += Decimal::from(2);
+        *volumes.entry(Quotation::from(100)).or_default() += Decimal::ONE;
+
+        assert_eq!(2, volumes.len());
+        assert_eq!(Decimal::from(5), volumes[&"100.5".parse().unwrap()]);
+
+        let money = |currency: &str| MoneyValue {
+            currency: currency.to_string(),
+            units: 10,
+            nano: 0,
+        };
+        let currencies = HashSet::from([money("rub"), money("rub"), money("usd")]);
+        assert_eq!(2, currencies.len());
+    }
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod proptests {
+    use proptest::prelude::*;
+    use rust_decimal::Decimal;
+
+    use crate::api::Quotation;
+
+    /// Quotation, у которой `units` и `nano` имеют один знак.
+    fn canonical_quotation() -> impl Strategy<Value = Quotation> {
+        (any::<i64>(), 0..=999_999_999i32).prop_map(|(units, nano)| Quotation {
+            units,
+            nano: if units < 0 { -nano } else { nano },
+        })
+    }
+
+    /// Decimal не более чем с 9 знаками после запятой и целой частью в пределах `i64`.
+    fn quotation_decimal() -> impl Strategy<Value = Decimal> {
+        (0..=9u32).prop_flat_map(|scale| {
+            let bound = i128::from(i64::MAX) * 10i128.pow(scale);
+            (-bound..=bound)
+                .prop_map(move |mantissa| Decimal::from_i128_with_scale(mantissa, scale))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn quotation_decimal_round_trip(quotation in canonical_quotation()) {
+            let decimal = Decimal::from(quotation);
+            prop_assert_eq!(Ok(quotation), Quotation::try_from(decimal));
+        }
+
+        #[test]
+        fn decimal_quotation_round_trip(decimal in quotation_decimal()) {
+            let quotation = Quotation::try_from(decimal);
+            prop_assert!(quotation.is_ok(), "{} is not convertible: {:?}", decimal, quotation);
+            prop_assert_eq!(decimal, Decimal::from(quotation.unwrap()));
+        }
+
+        #[test]
+        fn mixed_signs_keep_value(units in any::<i32>(), nano in -999_999_999..=999_999_999i32) {
+            let decimal = Decimal::from(Quotation { units: units.into(), nano });
+            prop_assert_eq!(
+                Decimal::from(units) + Decimal::new(nano.into(), 9),
+                decimal
+            );
+
+            let canonical = Quotation::try_from(decimal).unwrap();
+            prop_assert!(
+                canonical.units == 0 || canonical.nano == 0
+                    || (canonical.units > 0) == (canonical.nano > 0),
+                "inconsistent signs in {:?}",
+                canonical
+            );
+            prop_assert_eq!(decimal, Decimal::from(canonical));
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use rust_decimal::Decimal;
+
+use crate::api::{
+    InstrumentIdType, InstrumentRequest, MoneyValue, OrderDirection, OrderType, PortfolioRequest,
+    PortfolioResponse, PostOrderRequest, Quotation, SandboxPayInRequest,
+};
+use crate::{TInvestError, TInvestSdk};
+
+/// Валюта пополнения счёта в песочнице.
+const PAY_IN_CURRENCY: &str = "rub";
+
+/// Шаг сценария.
+#[derive(Debug, Clone)]
+enum Step {
+    Deposit {
+        account_id: String,
+        amount: Decimal,
+        currency: String,
+    },
+    SeedPosition {
+        account_id: String,
+        figi: String,
+        quantity: i64,
+        average_price: Decimal,
+    },
+    PlaceOrder(PostOrderRequest),
+    AssertPortfolioContains {
+        figi: String,
+        quantity: i64,
+    },
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Step::Deposit {
+                account_id,
+                amount,
+                currency,
+            } => format!("deposit {} {} to {}", amount, currency, account_id),
+            Step::SeedPosition {
+                account_id,
+                figi,
+                quantity,
+                average_price,
+            } => format!(
+                "seed {} x {} at {} on {}",
+                quantity, figi, average_price, account_id
+            ),
+            Step::PlaceOrder(request) => format!(
+                "place {:?} order for {} lots of {} on {}",
+                request.direction(),
+                request.quantity,
+                request.instrument_id,
+                request.account_id
+            ),
+            Step::AssertPortfolioContains { figi, quantity } => {
+                format!("assert portfolio contains {} x {}", quantity, figi)
+            }
+        }
+    }
+
+    fn account_id(&self) -> Option<&str> {
+        match self {
+            Step::Deposit { account_id, .. } | Step::SeedPosition { account_id, .. } => {
+                Some(account_id)
+            }
+            Step::PlaceOrder(request) => Some(&request.account_id),
+            Step::AssertPortfolioContains { .. } => None,
+        }
+    }
+}
+
+/// Результат выполнения сценария.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScenarioReport {
+    /// Описания выполненных шагов в порядке выполнения
+    pub completed_steps: Vec<String>,
+    /// Идентификаторы выставленных поручений
+    pub order_ids: Vec<String>,
+}
+
+/// Сценарий для проверки торговых стратегий в песочнице.
+///
+/// Шаги добавляются цепочкой вызовов и выполняются последовательно методом
+/// [`SandboxScenario::run`]. Выполнение останавливается на первой ошибке,
+/// которая возвращается как [`TInvestError::Step`] с номером и описанием шага.
+///
+/// Проверки портфеля выполняются по счёту последнего шага, в котором он указан.
+///
+/// # Пример
+///
+/// ```no_run
+/// # async fn example(sdk: &t_invest_sdk::TInvestSdk) -> Result<(), t_invest_sdk::TInvestError> {
+/// use rust_decimal::Decimal;
+/// use t_invest_sdk::SandboxScenario;
+///
+/// let report = SandboxScenario::new()
+///     .deposit("account", Decimal::from(100_000), "rub")
+///     .seed_position("account", "BBG004730N88", 10, Decimal::from(250))
+///     .assert_portfolio_contains("BBG004730N88", 10)
+///     .run(sdk)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SandboxScenario {
+    steps: Vec<Step>,
+}
+
+impl SandboxScenario {
+    /// Создаёт пустой сценарий.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Пополняет счёт. Песочница поддерживает пополнение только в рублях.
+    pub fn deposit(&mut self, account_id: &str, amount: Decimal, currency: &str) -> &mut Self {
+        self.steps.push(Step::Deposit {
+            account_id: account_id.to_string(),
+            amount,
+            currency: currency.to_lowercase(),
+        });
+        self
+    }
+
+    /// Создаёт позицию по инструменту.
+    ///
+    /// Песочница не позволяет задать позицию напрямую, поэтому счёт пополняется
+    /// на `quantity * average_price` рублей, после чего выставляется рыночная
+    /// заявка на покупку. Фактическая цена покупки определяется рынком.
+    ///
+    /// # Аргументы
+    /// * `account_id` - Идентификатор счёта
+    /// * `figi` - FIGI инструмента
+    /// * `quantity` - Количество инструментов (не лотов), кратное лоту
+    /// * `average_price` - Ожидаемая цена одного инструмента
+    pub fn seed_position(
+        &mut self,
+        account_id: &str,
+        figi: &str,
+        quantity: i64,
+        average_price: Decimal,
+    ) -> &mut Self {
+        self.steps.push(Step::SeedPosition {
+            account_id: account_id.to_string(),
+            figi: figi.to_string(),
+            quantity,
+            average_price,
+        });
+        self
+    }
+
+    /// Выставляет заявку. Пустой `order_id` заменяется случайным.
+    pub fn place_order(&mut self, req: PostOrderRequest) -> &mut Self {
+        self.steps.push(Step::PlaceOrder(req));
+        self
+    }
+
+    /// Проверяет, что в портфеле ровно `quantity` инструментов (не лотов).
+    ///
+    /// Несовпадение возвращается как [`TInvestError::AssertionFailed`].
+    pub fn assert_portfolio_contains(&mut self, figi: &str, quantity: i64) -> &mut Self {
+        self.steps.push(Step::AssertPortfolioContains {
+            figi: figi.to_string(),
+            quantity,
+        });
+        self
+    }
+
+    /// Выполняет шаги сценария последовательно.
+    pub async fn run(&self, sdk: &TInvestSdk) -> Result<ScenarioReport, TInvestError> {
+        let mut report = ScenarioReport::default();
+        let mut account_id = None;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let description = step.describe();
+            if let Some(step_account_id) = step.account_id() {
+                account_id = Some(step_account_id.to_string());
+            }
+
+            run_step(sdk, step, account_id.as_deref(), &mut report)
+                .await
+                .map_err(|err| step_error(index, &description, err))?;
+            report.completed_steps.push(description);
+        }
+
+        Ok(report)
+    }
+}
+
+async fn run_step(
+    sdk: &TInvestSdk,
+    step: &Step,
+    account_id: Option<&str>,
+    report: &mut ScenarioReport,
+) -> Result<(), TInvestError> {
+    let mut sandbox = sdk.sandbox();
+
+    match step {
+        Step::Deposit {
+            account_id,
+            amount,
+            currency,
+        } => {
+            if currency != PAY_IN_CURRENCY {
+                return Err(TInvestError::InvalidArgument(format!(
+                    "Sandbox accepts deposits only in {}",
+                    PAY_IN_CURRENCY
+                )));
+            }
+            pay_in(sdk, account_id, *amount).await
+        }
+        Step::SeedPosition {
+            account_id,
+            figi,
+            quantity,
+            average_price,
+        } => {
+            let lot = sdk
+                .instruments()
+                .get_instrument_by(InstrumentRequest {
+                    id_type: InstrumentIdType::Figi.into(),
+                    id: figi.clone(),
+                    ..Default::default()
+                })
+                .await?
+                .into_inner()
+                .instrument
+                .ok_or_else(|| TInvestError::NotFound(format!("instrument with FIGI {}", figi)))?
+                .lot;
+            let lots = lots(*quantity, lot.into()).map_err(TInvestError::InvalidArgument)?;
+
+            pay_in(sdk, account_id, Decimal::from(*quantity) * average_price).await?;
+            let order = PostOrderRequest {
+                quantity: lots,
+                direction: OrderDirection::Buy.into(),
+                account_id: account_id.clone(),
+                order_type: OrderType::Market.into(),
+                order_id: uuid::Uuid::new_v4().to_string(),
+                instrument_id: figi.clone(),
+                ..Default::default()
+            };
+            let response = sandbox.post_sandbox_order(order).await?.into_inner();
+            report.order_ids.push(response.order_id);
+            Ok(())
+        }
+        Step::PlaceOrder(request) => {
+            let mut request = request.clone();
+            if request.order_id.is_empty() {
+                request.order_id = uuid::Uuid::new_v4().to_string();
+            }
+            let response = sandbox.post_sandbox_order(request).await?.into_inner();
+            report.order_ids.push(response.order_id);
+            Ok(())
+        }
+        Step::AssertPortfolioContains { figi, quantity } => {
+            let account_id = account_id.ok_or_else(|| {
+                TInvestError::InvalidArgument(
+                    "No account specified before portfolio assertion".to_string(),
+                )
+            })?;
+            let portfolio = sandbox
+                .get_sandbox_portfolio(PortfolioRequest {
+                    account_id: account_id.to_string(),
+                    ..Default::default()
+                })
+                .await?
+                .into_inner();
+
+            check_position(&portfolio, figi, *quantity).map_err(TInvestError::AssertionFailed)
+        }
+    }
+}
+
+async fn pay_in(sdk: &TInvestSdk, account_id: &str, amount: Decimal) -> Result<(), TInvestError> {
+    let amount = Quotation::try_from(amount).map_err(TInvestError::InvalidArgument)?;
+    sdk.sandbox()
+        .sandbox_pay_in(SandboxPayInRequest {
+            account_id: account_id.to_string(),
+            amount: Some(MoneyValue {
+                currency: PAY_IN_CURRENCY.to_string(),
+                units: amount.units,
+                nano: amount.nano,
+            }),
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Переводит количество инструментов в лоты.
+fn lots(quantity: i64, lot: i64) -> Result<i64, String> {
+    if lot <= 0 || quantity % lot != 0 {
+        return Err(format!(
+            "Quantity {} is not a multiple of lot size {}",
+            quantity, lot
+        ));
+    }
+
+    Ok(quantity / lot)
+}
+
+/// Проверяет количество инструментов в портфеле. Отсутствующая позиция считается нулевой.
+fn check_position(portfolio: &PortfolioResponse, figi: &str, quantity: i64) -> Result<(), String> {
+    let actual: Decimal = portfolio
+        .positions
+        .iter()
+        .filter(|position| position.figi == figi)
+        .filter_map(|position| position.quantity)
+        .map(Decimal::from)
+        .sum();
+
+    if actual != Decimal::from(quantity) {
+        return Err(format!(
+            "expected {} x {} in portfolio, found {}",
+            quantity, figi, actual
+        ));
+    }
+
+    Ok(())
+}
+
+/// Добавляет к ошибке номер и описание шага, сохраняя исходную ошибку.
+fn step_error(index: usize, description: &str, err: TInvestError) -> TInvestError {
+    TInvestError::Step {
+        index,
+        description: description.to_string(),
+        source: Box::new(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::PortfolioPosition;
+
+    #[test]
+    fn step_descriptions_and_accounts() {
+        let mut scenario = SandboxScenario::new();
+        scenario
+            .deposit("account", dec!(1000), "RUB")
+            .place_order(PostOrderRequest {
+                quantity: 2,
+                direction: OrderDirection::Sell.into(),
+                account_id: "other".to_string(),
+                instrument_id: "FIGI".to_string(),
+                ..Default::default()
+            })
+            .assert_portfolio_contains("FIGI", 0);
+
+        let descriptions: Vec<String> = scenario.steps.iter().map(Step::describe).collect();
+        assert_eq!(
+            vec![
+                "deposit 1000 rub to account",
+                "place Sell order for 2 lots of FIGI on other",
+                "assert portfolio contains 0 x FIGI",
+            ],
+            descriptions
+        );
+        assert_eq!(Some("other"), scenario.steps[1].account_id());
+        assert_eq!(None, scenario.steps[2].account_id());
+    }
+
+    #[test]
+    fn position_checks() {
+        let portfolio = PortfolioResponse {
+            positions: vec![PortfolioPosition {
+                figi: "FIGI".to_string(),
+                quantity: Some(Quotation::from(10)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(Ok(()), check_position(&portfolio, "FIGI", 10));
+        assert_eq!(Ok(()), check_position(&portfolio, "OTHER", 0));
+        assert_eq!(
+            Err("expected 5 x FIGI in portfolio, found 10".to_string()),
+            check_position(&portfolio, "FIGI", 5)
+        );
+
+        assert_eq!(Ok(5), lots(50, 10));
+        assert!(lots(15, 10).is_err());
+
+        let mut status = tonic::Status::resource_exhausted("80002");
+        status
+            .metadata_mut()
+            .insert("x-tracking-id", "tracking".parse().unwrap());
+        let err = step_error(2, "place order", status.into());
+        assert_eq!(
+            "Step 3 (place order): Rate limit exceeded: 80002",
+            err.to_string()
+        );
+        assert_eq!(Some(429), err.status_code());
+        assert!(err.is_transient());
+        match err {
+            TInvestError::Step { index, source, .. } => {
+                assert_eq!(2, index);
+                assert!(matches!(
+                    *source,
+                    TInvestError::Status(status)
+                        if status.metadata().get("x-tracking-id").unwrap() == "tracking"
+                ));
+            }
+            _ => unreachable!(),
+        }
+
+        let err = step_error(
+            0,
+            "assert portfolio contains 5 x FIGI",
+            TInvestError::NotFound("instrument with FIGI FIGI".to_string()),
+        );
+        assert_eq!(
+            "Step 1 (assert portfolio contains 5 x FIGI): Not found: instrument with FIGI FIGI",
+            err.to_string()
+        );
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
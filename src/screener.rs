@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::api::HistoricCandle;
+use crate::indicators;
+
+/// Период RSI, используемый [`TechnicalScreener::rsi_below`].
+const RSI_PERIOD: usize = 14;
+/// Параметры MACD, используемые [`TechnicalScreener::positive_macd_histogram`].
+const MACD_FAST: usize = 12;
+const MACD_SLOW: usize = 26;
+const MACD_SIGNAL: usize = 9;
+
+/// Фильтр инструментов по техническим индикаторам.
+///
+/// Индикаторы рассчитываются по ценам закрытия свечей, условия проверяются
+/// для последней свечи. Инструменты, для которых недостаточно свечей
+/// для расчёта индикатора, не проходят фильтр.
+#[derive(Debug, Clone, Default)]
+pub struct TechnicalScreener {
+    above_sma: Option<usize>,
+    rsi_below: Option<Decimal>,
+    positive_macd_histogram: bool,
+}
+
+impl TechnicalScreener {
+    /// Создаёт фильтр без условий.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Требует, чтобы цена закрытия была выше SMA с указанным периодом.
+    pub fn above_sma(&mut self, period: usize) -> &mut Self {
+        self.above_sma = Some(period);
+        self
+    }
+
+    /// Требует, чтобы RSI(14) был ниже указанного уровня.
+    pub fn rsi_below(&mut self, level: Decimal) -> &mut Self {
+        self.rsi_below = Some(level);
+        self
+    }
+
+    /// Требует положительную гистограмму MACD(12, 26, 9).
+    pub fn positive_macd_histogram(&mut self) -> &mut Self {
+        self.positive_macd_histogram = true;
+        self
+    }
+
+    /// Возвращает FIGI инструментов, удовлетворяющих всем условиям.
+    ///
+    /// # Аргументы
+    /// * `candle_data` - Свечи по FIGI, отсортированные по времени
+    pub fn apply(&self, candle_data: &HashMap<String, Vec<HistoricCandle>>) -> Vec<String> {
+        let mut figis: Vec<String> = candle_data
+            .iter()
+            .filter(|(_, candles)| self.matches(&close_prices(candles)))
+            .map(|(figi, _)| figi.clone())
+            .collect();
+        figis.sort();
+
+        figis
+    }
+
+    fn matches(&self, closes: &[Decimal]) -> bool {
+        let Some(last_close) = closes.last() else {
+            return false;
+        };
+
+        self.above_sma.is_none_or(|period| {
+            last_value(indicators::simple_moving_average(closes, period))
+                .is_some_and(|sma| *last_close > sma)
+        }) && self.rsi_below.is_none_or(|level| {
+            last_value(indicators::rsi(closes, RSI_PERIOD)).is_some_and(|rsi| rsi < level)
+        }) && (!self.positive_macd_histogram
+            || last_value(indicators::macd(closes, MACD_FAST, MACD_SLOW, MACD_SIGNAL))
+                .is_some_and(|macd| macd.histogram.is_sign_positive() && !macd.histogram.is_zero()))
+    }
+}
+
+fn close_prices(candles: &[HistoricCandle]) -> Vec<Decimal> {
+    candles
+        .iter()
+        .filter_map(|candle| candle.close.map(Decimal::from))
+        .collect()
+}
+
+fn last_value<T>(values: Vec<Option<T>>) -> Option<T> {
+    values.into_iter().last().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::Quotation;
+
+    fn candles(closes: impl IntoIterator<Item = i64>) -> Vec<HistoricCandle> {
+        closes
+            .into_iter()
+            .map(|close| HistoricCandle {
+                close: Some(Quotation {
+                    units: close,
+                    nano: 0,
+                }),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn technical_screener() {
+        let candle_data = HashMap::from([
+            ("rising".to_string(), candles((1..=40).map(|i| i * i))),
+            ("falling".to_string(), candles((1..=40).rev())),
+            ("short".to_string(), candles(1..=3)),
+        ]);
+
+        assert_eq!(
+            vec!["rising"],
+            TechnicalScreener::new()
+                .above_sma(20)
+                .positive_macd_histogram()
+                .apply(&candle_data)
+        );
+
+        assert_eq!(
+            vec!["falling"],
+            TechnicalScreener::new()
+                .rsi_below(dec!(30))
+                .apply(&candle_data)
+        );
+
+        assert_eq!(3, TechnicalScreener::new().apply(&candle_data).len());
+    }
+}
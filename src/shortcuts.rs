@@ -0,0 +1,248 @@
+use rust_decimal::Decimal;
+
+use crate::api::{
+    Account, GetAccountsRequest, GetLastPricesRequest, GetLastPricesResponse, GetOrderBookRequest,
+    Instrument, InstrumentIdType, InstrumentRequest, InstrumentResponse, MoneyValue,
+    PortfolioRequest, PortfolioResponse,
+};
+use crate::{OrderBookSnapshot, TInvestError, TInvestSdk};
+
+/// Сводная стоимость портфеля по типам инструментов.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PortfolioStatistics {
+    /// Стоимость акций
+    pub total_amount_shares: Decimal,
+    /// Стоимость облигаций
+    pub total_amount_bonds: Decimal,
+    /// Стоимость фондов
+    pub total_amount_etfs: Decimal,
+    /// Стоимость валют
+    pub total_amount_currencies: Decimal,
+    /// Стоимость фьючерсов
+    pub total_amount_futures: Decimal,
+    /// Относительная доходность портфеля в процентах
+    pub expected_yield: Decimal,
+    /// Валюта, в которой рассчитаны суммы, в нижнем регистре
+    pub currency: String,
+}
+
+impl From<&PortfolioResponse> for PortfolioStatistics {
+    /// Отсутствующие суммы считаются нулём, валюта берётся из общей стоимости
+    /// портфеля или первой заполненной суммы.
+    fn from(portfolio: &PortfolioResponse) -> Self {
+        let amounts = [
+            &portfolio.total_amount_portfolio,
+            &portfolio.total_amount_shares,
+            &portfolio.total_amount_bonds,
+            &portfolio.total_amount_etf,
+            &portfolio.total_amount_currencies,
+            &portfolio.total_amount_futures,
+        ];
+        let currency = amounts
+            .iter()
+            .filter_map(|amount| amount.as_ref())
+            .map(|amount| amount.currency.to_lowercase())
+            .find(|currency| !currency.is_empty())
+            .unwrap_or_default();
+        let amount =
+            |amount: &Option<MoneyValue>| amount.clone().map(Decimal::from).unwrap_or_default();
+
+        Self {
+            total_amount_shares: amount(&portfolio.total_amount_shares),
+            total_amount_bonds: amount(&portfolio.total_amount_bonds),
+            total_amount_etfs: amount(&portfolio.total_amount_etf),
+            total_amount_currencies: amount(&portfolio.total_amount_currencies),
+            total_amount_futures: amount(&portfolio.total_amount_futures),
+            expected_yield: portfolio
+                .expected_yield
+                .map(Decimal::from)
+                .unwrap_or_default(),
+            currency,
+        }
+    }
+}
+
+/// Упрощённые методы для частых запросов.
+///
+/// Методы создают клиент нужного сервиса, заполняют минимально необходимые
+/// поля запроса и извлекают результат из ответа.
+impl TInvestSdk {
+    /// Возвращает последнюю цену инструмента (FIGI или UID).
+    ///
+    /// Возвращает [`TInvestError::NotFound`], если API не вернул цену для инструмента.
+    pub async fn get_last_price(&self, figi: &str) -> Result<Decimal, TInvestError> {
+        let response = self
+            .market_data()
+            .get_last_prices(GetLastPricesRequest {
+                instrument_id: vec![figi.to_string()],
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        last_price(response, figi)
+    }
+
+    /// Возвращает стакан инструмента (FIGI или UID) указанной глубины.
+    pub async fn get_order_book(
+        &self,
+        figi: &str,
+        depth: i32,
+    ) -> Result<OrderBookSnapshot, TInvestError> {
+        let response = self
+            .market_data()
+            .get_order_book(GetOrderBookRequest {
+                depth,
+                instrument_id: Some(figi.to_string()),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        Ok(response.into())
+    }
+
+    /// Возвращает основную информацию об инструменте по FIGI.
+    ///
+    /// Возвращает [`TInvestError::NotFound`], если API не вернул инструмент.
+    pub async fn get_instrument_by_figi(&self, figi: &str) -> Result<Instrument, TInvestError> {
+        let response = self
+            .instruments()
+            .get_instrument_by(InstrumentRequest {
+                id_type: InstrumentIdType::Figi.into(),
+                id: figi.to_string(),
+                ..Default::default()
+            })
+            .await?
+            .into_inner();
+
+        instrument(response, figi)
+    }
+
+    /// Возвращает счета пользователя.
+    pub async fn get_accounts(&self) -> Result<Vec<Account>, TInvestError> {
+        Ok(self
+            .users()
+            .get_accounts(GetAccountsRequest::default())
+            .await?
+            .into_inner()
+            .accounts)
+    }
+
+    /// Возвращает портфель по счёту.
+    pub async fn get_portfolio(&self, account_id: &str) -> Result<PortfolioResponse, TInvestError> {
+        Ok(self
+            .operations()
+            .get_portfolio(PortfolioRequest {
+                account_id: account_id.to_string(),
+                ..Default::default()
+            })
+            .await?
+            .into_inner())
+    }
+
+    /// Возвращает сводную стоимость портфеля по счёту.
+    pub async fn get_portfolio_statistics(
+        &self,
+        account_id: &str,
+    ) -> Result<PortfolioStatistics, TInvestError> {
+        let portfolio = self.get_portfolio(account_id).await?;
+
+        Ok(PortfolioStatistics::from(&portfolio))
+    }
+}
+
+fn last_price(response: GetLastPricesResponse, figi: &str) -> Result<Decimal, TInvestError> {
+    response
+        .last_prices
+        .into_iter()
+        .find_map(|last_price| last_price.price)
+        .map(Decimal::from)
+        .ok_or_else(|| TInvestError::NotFound(format!("last price for {}", figi)))
+}
+
+fn instrument(response: InstrumentResponse, figi: &str) -> Result<Instrument, TInvestError> {
+    response
+        .instrument
+        .ok_or_else(|| TInvestError::NotFound(format!("instrument with FIGI {}", figi)))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{LastPrice, Quotation};
+
+    fn is_not_found<T>(result: Result<T, TInvestError>) -> bool {
+        matches!(result, Err(TInvestError::NotFound(_)))
+    }
+
+    #[test]
+    fn last_price_from_response() {
+        let response = GetLastPricesResponse {
+            last_prices: vec![LastPrice {
+                price: Some(dec!(123.45).try_into().unwrap()),
+                ..Default::default()
+            }],
+        };
+        assert_eq!(dec!(123.45), last_price(response, "FIGI").unwrap());
+
+        let response = GetLastPricesResponse {
+            last_prices: vec![LastPrice::default()],
+        };
+        assert!(is_not_found(last_price(response, "FIGI")));
+    }
+
+    #[test]
+    fn instrument_from_response() {
+        let response = InstrumentResponse {
+            instrument: Some(Instrument {
+                ticker: "SBER".to_string(),
+                ..Default::default()
+            }),
+        };
+        assert_eq!("SBER", instrument(response, "FIGI").unwrap().ticker);
+
+        assert!(is_not_found(instrument(
+            InstrumentResponse::default(),
+            "FIGI"
+        )));
+    }
+
+    #[test]
+    fn portfolio_statistics_from_response() {
+        let money = |value: Decimal| {
+            let quotation = Quotation::try_from(value).unwrap();
+            Some(MoneyValue {
+                currency: "RUB".to_string(),
+                units: quotation.units,
+                nano: quotation.nano,
+            })
+        };
+        let portfolio = PortfolioResponse {
+            total_amount_shares: money(dec!(15000.5)),
+            total_amount_bonds: money(dec!(7000)),
+            total_amount_currencies: money(dec!(120.25)),
+            expected_yield: Some(dec!(-3.14).try_into().unwrap()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            PortfolioStatistics {
+                total_amount_shares: dec!(15000.5),
+                total_amount_bonds: dec!(7000),
+                total_amount_etfs: Decimal::ZERO,
+                total_amount_currencies: dec!(120.25),
+                total_amount_futures: Decimal::ZERO,
+                expected_yield: dec!(-3.14),
+                currency: "rub".to_string(),
+            },
+            PortfolioStatistics::from(&portfolio)
+        );
+        assert_eq!(
+            PortfolioStatistics::default(),
+            PortfolioStatistics::from(&PortfolioResponse::default())
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, join_all};
+
+use crate::TInvestError;
+use crate::api::{GetSignalsRequest, InstrumentType, Signal, SignalDirection};
+use crate::datetime::{datetime_utc_to_timestamp, timestamp_to_datetime_utc};
+
+/// Фильтр сигналов, полученных через `GetSignals`.
+///
+/// Условия направления и времени создания поддерживаются сервером и
+/// переносятся в запрос [`Self::to_request_params`]. Тип инструмента и
+/// вероятность проверяются только на клиенте в [`Self::apply`].
+///
+/// Сигнал не содержит тип инструмента, поэтому для фильтра по типу
+/// типы инструментов нужно передать через [`Self::instrument`]. Сигналы по
+/// инструментам с неизвестным типом не проходят такой фильтр.
+#[derive(Debug, Clone, Default)]
+pub struct SignalFilter {
+    instrument_type: Option<InstrumentType>,
+    min_confidence: Option<f64>,
+    direction: Option<SignalDirection>,
+    since: Option<DateTime<Utc>>,
+    instrument_types: HashMap<String, InstrumentType>,
+}
+
+impl SignalFilter {
+    /// Создаёт фильтр без условий.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Требует, чтобы сигнал был по инструменту указанного типа.
+    pub fn instrument_type(&mut self, instrument_type: InstrumentType) -> &mut Self {
+        self.instrument_type = Some(instrument_type);
+        self
+    }
+
+    /// Задаёт тип инструмента с указанным `instrument_uid`.
+    pub fn instrument(
+        &mut self,
+        instrument_uid: &str,
+        instrument_type: InstrumentType,
+    ) -> &mut Self {
+        self.instrument_types
+            .insert(instrument_uid.to_string(), instrument_type);
+        self
+    }
+
+    /// Требует, чтобы вероятность сигнала была не ниже указанной.
+    ///
+    /// Вероятность задаётся долей от 0 до 1. Сигналы без вероятности не проходят фильтр.
+    pub fn min_confidence(&mut self, confidence: f64) -> &mut Self {
+        self.min_confidence = Some(confidence);
+        self
+    }
+
+    /// Требует указанное направление сигнала.
+    pub fn direction(&mut self, direction: SignalDirection) -> &mut Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Требует, чтобы сигнал был создан не раньше указанного времени.
+    pub fn since(&mut self, datetime: DateTime<Utc>) -> &mut Self {
+        self.since = Some(datetime);
+        self
+    }
+
+    /// Возвращает запрос с условиями, которые поддерживает сервер.
+    pub fn to_request_params(&self) -> GetSignalsRequest {
+        GetSignalsRequest {
+            from: self.since.map(datetime_utc_to_timestamp),
+            direction: self.direction.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    /// Возвращает сигналы, удовлетворяющие всем условиям.
+    pub fn apply(&self, signals: Vec<Signal>) -> Vec<Signal> {
+        signals
+            .into_iter()
+            .filter(|signal| self.matches(signal))
+            .collect()
+    }
+
+    fn matches(&self, signal: &Signal) -> bool {
+        self.instrument_type.is_none_or(|instrument_type| {
+            self.instrument_types.get(&signal.instrument_uid) == Some(&instrument_type)
+        }) && self.min_confidence.is_none_or(|confidence| {
+            signal
+                .probability
+                .is_some_and(|probability| f64::from(probability) / 100.0 >= confidence)
+        }) && self
+            .direction
+            .is_none_or(|direction| signal.direction == direction as i32)
+            && self.since.is_none_or(|since| {
+                signal
+                    .create_dt
+                    .and_then(|create_dt| timestamp_to_datetime_utc(create_dt).ok())
+                    .is_some_and(|create_dt| create_dt >= since)
+            })
+    }
+}
+
+/// Обработчик сигналов стратегии.
+///
+/// Метод возвращает `BoxFuture`, чтобы обработчики можно было хранить как
+/// `Arc<dyn SignalHandler>`:
+///
+/// ```
+/// use futures::FutureExt;
+/// use futures::future::BoxFuture;
+/// use t_invest_sdk::{SignalHandler, TInvestError, api::Signal};
+///
+/// struct Logger;
+///
+/// impl SignalHandler for Logger {
+///     fn on_signal<'a>(&'a self, signal: &'a Signal) -> BoxFuture<'a, Result<(), TInvestError>> {
+///         async move {
+///             println!("{}: {}", signal.instrument_uid, signal.name);
+///             Ok(())
+///         }
+///         .boxed()
+///     }
+/// }
+/// ```
+pub trait SignalHandler: Send + Sync {
+    /// Обрабатывает сигнал.
+    fn on_signal<'a>(&'a self, signal: &'a Signal) -> BoxFuture<'a, Result<(), TInvestError>>;
+}
+
+/// Маршрутизатор сигналов по обработчикам инструментов.
+///
+/// Сигнал передаётся всем обработчикам, зарегистрированным для его `instrument_uid`.
+#[derive(Clone, Default)]
+pub struct SignalBroker {
+    handlers: HashMap<String, Vec<Arc<dyn SignalHandler>>>,
+}
+
+impl SignalBroker {
+    /// Создаёт маршрутизатор без обработчиков.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Регистрирует обработчик сигналов по инструменту.
+    ///
+    /// # Аргументы
+    /// * `instrument_uid` - UID инструмента
+    /// * `handler` - Обработчик
+    pub fn register_handler(&mut self, instrument_uid: &str, handler: Arc<dyn SignalHandler>) {
+        self.handlers
+            .entry(instrument_uid.to_string())
+            .or_default()
+            .push(handler);
+    }
+
+    /// Передаёт сигнал обработчикам его инструмента.
+    ///
+    /// Обработчики вызываются одновременно, и ошибка одного из них не прерывает
+    /// остальные.
+    ///
+    /// # Возвращает
+    /// Первую по порядку регистрации ошибку обработчиков
+    pub async fn dispatch(&self, signal: &Signal) -> Result<(), TInvestError> {
+        let Some(handlers) = self.handlers.get(&signal.instrument_uid) else {
+            return Ok(());
+        };
+
+        join_all(handlers.iter().map(|handler| handler.on_signal(signal)))
+            .await
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use chrono::TimeZone;
+    use futures::FutureExt;
+
+    use super::*;
+
+    struct Counter {
+        calls: AtomicUsize,
+        fail: bool,
+    }
+
+    impl SignalHandler for Counter {
+        fn on_signal<'a>(&'a self, _: &'a Signal) -> BoxFuture<'a, Result<(), TInvestError>> {
+            async move {
+                self.calls.fetch_add(1, Ordering::Relaxed);
+                if self.fail {
+                    return Err(TInvestError::NotFound("strategy".to_string()));
+                }
+                Ok(())
+            }
+            .boxed()
+        }
+    }
+
+    fn counter(fail: bool) -> Arc<Counter> {
+        Arc::new(Counter {
+            calls: AtomicUsize::new(0),
+            fail,
+        })
+    }
+
+    #[tokio::test]
+    async fn dispatch_to_instrument_handlers() {
+        let first = counter(false);
+        let failing = counter(true);
+        let other = counter(false);
+
+        let mut broker = SignalBroker::new();
+        broker.register_handler("sber", first.clone());
+        broker.register_handler("sber", failing.clone());
+        broker.register_handler("gazp", other.clone());
+
+        let sber = signal("1", "sber", SignalDirection::Buy, 80, 1);
+        assert!(matches!(
+            broker.dispatch(&sber).await,
+            Err(TInvestError::NotFound(_))
+        ));
+        assert_eq!(1, first.calls.load(Ordering::Relaxed));
+        assert_eq!(1, failing.calls.load(Ordering::Relaxed));
+        assert_eq!(0, other.calls.load(Ordering::Relaxed));
+
+        let gazp = signal("2", "gazp", SignalDirection::Sell, 60, 2);
+        broker.dispatch(&gazp).await.unwrap();
+        assert_eq!(1, other.calls.load(Ordering::Relaxed));
+
+        let unknown = signal("3", "lkoh", SignalDirection::Buy, 60, 3);
+        broker.dispatch(&unknown).await.unwrap();
+    }
+
+    fn signal(
+        id: &str,
+        uid: &str,
+        direction: SignalDirection,
+        probability: i32,
+        day: u32,
+    ) -> Signal {
+        Signal {
+            signal_id: id.to_string(),
+            instrument_uid: uid.to_string(),
+            direction: direction.into(),
+            probability: Some(probability),
+            create_dt: Some(datetime_utc_to_timestamp(
+                Utc.with_ymd_and_hms(2024, 3, day, 0, 0, 0).unwrap(),
+            )),
+            ..Default::default()
+        }
+    }
+
+    fn ids(signals: &[Signal]) -> Vec<&str> {
+        signals.iter().map(|s| s.signal_id.as_str()).collect()
+    }
+
+    #[test]
+    fn combined_filters() {
+        let signals = vec![
+            signal("1", "share", SignalDirection::Buy, 80, 10),
+            signal("2", "share", SignalDirection::Sell, 80, 10),
+            signal("3", "share", SignalDirection::Buy, 40, 10),
+            signal("4", "share", SignalDirection::Buy, 80, 1),
+            signal("5", "bond", SignalDirection::Buy, 80, 10),
+            signal("6", "unknown", SignalDirection::Buy, 80, 10),
+        ];
+
+        let mut filter = SignalFilter::new();
+        assert_eq!(6, filter.apply(signals.clone()).len());
+
+        filter.direction(SignalDirection::Buy);
+        assert_eq!(
+            vec!["1", "3", "4", "5", "6"],
+            ids(&filter.apply(signals.clone()))
+        );
+
+        filter
+            .min_confidence(0.5)
+            .since(Utc.with_ymd_and_hms(2024, 3, 5, 0, 0, 0).unwrap());
+        assert_eq!(vec!["1", "5", "6"], ids(&filter.apply(signals.clone())));
+
+        filter
+            .instrument("share", InstrumentType::Share)
+            .instrument("bond", InstrumentType::Bond)
+            .instrument_type(InstrumentType::Share);
+        assert_eq!(vec!["1"], ids(&filter.apply(signals)));
+    }
+
+    #[test]
+    fn request_params() {
+        let since = Utc.with_ymd_and_hms(2024, 3, 5, 0, 0, 0).unwrap();
+        let request = SignalFilter::new()
+            .direction(SignalDirection::Sell)
+            .min_confidence(0.5)
+            .since(since)
+            .to_request_params();
+
+        assert_eq!(Some(SignalDirection::Sell as i32), request.direction);
+        assert_eq!(Some(datetime_utc_to_timestamp(since)), request.from);
+        assert_eq!(None, request.instrument_uid);
+    }
+}
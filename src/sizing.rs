@@ -0,0 +1,201 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+/// Расчёт размера позиции с учётом ограничений риска.
+///
+/// Все методы возвращают количество целых лотов, округлённое вниз.
+/// При некорректных входных данных (неположительная цена, лот или риск) возвращается `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionSizer {
+    /// Стоимость портфеля
+    pub portfolio_value: Decimal,
+    /// Допустимый риск на сделку в виде доли от стоимости портфеля, например `0.01`
+    pub risk_per_trade: Decimal,
+}
+
+impl PositionSizer {
+    /// Создаёт расчёт для портфеля.
+    pub fn new(portfolio_value: Decimal, risk_per_trade: Decimal) -> Self {
+        Self {
+            portfolio_value,
+            risk_per_trade,
+        }
+    }
+
+    /// Возвращает количество лотов, убыток по которым при срабатывании стопа
+    /// не превышает `risk_per_trade * portfolio_value`.
+    ///
+    /// # Аргументы
+    /// * `entry` - Цена входа
+    /// * `stop_loss` - Цена стоп-лосса
+    /// * `lot_size` - Количество инструментов в лоте
+    pub fn units_by_risk(&self, entry: Decimal, stop_loss: Decimal, lot_size: i64) -> i64 {
+        let loss_per_lot = (entry - stop_loss).abs() * Decimal::from(lot_size);
+        whole_lots(self.risk_per_trade * self.portfolio_value, loss_per_lot)
+    }
+
+    /// Возвращает количество лотов, стоимость которых не превышает доли
+    /// `target_weight` от стоимости портфеля.
+    ///
+    /// # Аргументы
+    /// * `target_weight` - Целевая доля позиции, например `0.1`
+    /// * `price` - Цена одного инструмента
+    /// * `lot_size` - Количество инструментов в лоте
+    pub fn units_by_weight(&self, target_weight: Decimal, price: Decimal, lot_size: i64) -> i64 {
+        whole_lots(
+            target_weight * self.portfolio_value,
+            price * Decimal::from(lot_size),
+        )
+    }
+}
+
+/// Возвращает максимальное количество лотов, которое можно купить на доступные
+/// средства с учётом комиссии.
+///
+/// # Аргументы
+/// * `available_cash` - Доступные средства
+/// * `price` - Цена одного инструмента
+/// * `lot_size` - Количество инструментов в лоте
+/// * `commission_rate` - Комиссия в виде доли от объёма сделки, например `0.003`
+pub fn max_affordable_lots(
+    available_cash: Decimal,
+    price: Decimal,
+    lot_size: i64,
+    commission_rate: Decimal,
+) -> i64 {
+    whole_lots(
+        available_cash,
+        price * Decimal::from(lot_size) * (Decimal::ONE + commission_rate),
+    )
+}
+
+/// Рассчитывает оптимальную долю капитала на сделку по критерию Келли
+/// `win_rate - (1 - win_rate) / (avg_win / avg_loss)`.
+///
+/// Отрицательное значение означает, что у стратегии нет преимущества и сделку
+/// открывать не стоит. При неположительных `avg_win` или `avg_loss` возвращается `0`.
+///
+/// # Аргументы
+/// * `win_rate` - Доля прибыльных сделок от `0` до `1`
+/// * `avg_win` - Средняя прибыль по прибыльной сделке
+/// * `avg_loss` - Средний убыток по убыточной сделке, положительное число
+pub fn kelly_optimal_fraction(win_rate: Decimal, avg_win: Decimal, avg_loss: Decimal) -> Decimal {
+    if avg_win <= Decimal::ZERO || avg_loss <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+
+    win_rate - (Decimal::ONE - win_rate) * avg_loss / avg_win
+}
+
+/// Возвращает количество лотов для доли капитала по критерию Келли.
+///
+/// Полный критерий Келли даёт большие просадки, поэтому доля обычно
+/// уменьшается множителем `kelly_multiplier` от `0.25` до `0.5`.
+/// При отрицательной доле возвращается `0`.
+///
+/// # Аргументы
+/// * `total_capital` - Капитал
+/// * `price_per_lot` - Цена одного инструмента в лоте
+/// * `kelly_fraction` - Доля, рассчитанная [`kelly_optimal_fraction`]
+/// * `lot_size` - Количество инструментов в лоте
+/// * `kelly_multiplier` - Множитель доли Келли
+pub fn kelly_position_size(
+    total_capital: Decimal,
+    price_per_lot: Decimal,
+    kelly_fraction: Decimal,
+    lot_size: i64,
+    kelly_multiplier: Decimal,
+) -> i64 {
+    whole_lots(
+        total_capital * kelly_fraction * kelly_multiplier,
+        price_per_lot * Decimal::from(lot_size),
+    )
+}
+
+/// Возвращает количество целых лотов стоимостью `per_lot` в сумме `amount`.
+fn whole_lots(amount: Decimal, per_lot: Decimal) -> i64 {
+    if amount <= Decimal::ZERO || per_lot <= Decimal::ZERO {
+        return 0;
+    }
+
+    (amount / per_lot).floor().to_i64().unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::*;
+
+    #[test]
+    fn sizing_by_risk() {
+        let sizer = PositionSizer::new(dec!(1000000), dec!(0.01));
+
+        // Риск 10 000, убыток на лот (250 - 240) * 10 = 100.
+        assert_eq!(100, sizer.units_by_risk(dec!(250), dec!(240), 10));
+        // Для короткой позиции стоп выше входа.
+        assert_eq!(100, sizer.units_by_risk(dec!(240), dec!(250), 10));
+        // 10 000 / 3 000 = 3.33 округляется вниз.
+        assert_eq!(3, sizer.units_by_risk(dec!(250), dec!(220), 100));
+
+        // Риска не хватает даже на один лот.
+        let small = PositionSizer::new(dec!(10000), dec!(0.001));
+        assert_eq!(0, small.units_by_risk(dec!(250), dec!(240), 10));
+        assert_eq!(0, sizer.units_by_risk(dec!(250), dec!(250), 10));
+        assert_eq!(0, sizer.units_by_risk(dec!(250), dec!(240), 0));
+    }
+
+    #[test]
+    fn sizing_by_weight_and_cash() {
+        let sizer = PositionSizer::new(dec!(1000000), dec!(0.01));
+
+        // 100 000 / (270.5 * 10) = 36.97
+        assert_eq!(36, sizer.units_by_weight(dec!(0.1), dec!(270.5), 10));
+        assert_eq!(0, sizer.units_by_weight(dec!(0.001), dec!(270.5), 10));
+        assert_eq!(0, sizer.units_by_weight(dec!(0.1), Decimal::ZERO, 10));
+
+        // 10 000 / (1000 * 1.003) = 9.97
+        assert_eq!(
+            9,
+            max_affordable_lots(dec!(10000), dec!(100), 10, dec!(0.003))
+        );
+        assert_eq!(
+            10,
+            max_affordable_lots(dec!(10000), dec!(100), 10, Decimal::ZERO)
+        );
+        assert_eq!(
+            0,
+            max_affordable_lots(dec!(999), dec!(100), 10, Decimal::ZERO)
+        );
+        assert_eq!(
+            0,
+            max_affordable_lots(dec!(-1000), dec!(100), 10, Decimal::ZERO)
+        );
+    }
+
+    #[test]
+    fn kelly_sizing() {
+        // 0.6 - 0.4 / (150 / 100) = 0.3333...
+        let fraction = kelly_optimal_fraction(dec!(0.6), dec!(150), dec!(100));
+        assert_eq!(dec!(0.3333), fraction.round_dp(4));
+        assert_eq!(
+            dec!(0.625),
+            kelly_optimal_fraction(dec!(0.75), dec!(100), dec!(50))
+        );
+        assert!(kelly_optimal_fraction(dec!(0.3), dec!(100), dec!(100)) < Decimal::ZERO);
+        assert_eq!(
+            Decimal::ZERO,
+            kelly_optimal_fraction(dec!(0.6), dec!(150), Decimal::ZERO)
+        );
+
+        // 1 000 000 * 0.5 * 0.25 = 125 000, лот стоит 250 * 10 = 2 500.
+        assert_eq!(
+            50,
+            kelly_position_size(dec!(1000000), dec!(250), dec!(0.5), 10, dec!(0.25))
+        );
+        assert_eq!(
+            0,
+            kelly_position_size(dec!(1000000), dec!(250), dec!(-0.1), 10, dec!(0.5))
+        );
+    }
+}
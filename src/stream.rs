@@ -0,0 +1,320 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{Stream, StreamExt, future, stream};
+use tokio::time::{Instant, Sleep};
+
+use crate::TInvestError;
+use crate::api::market_data_response::Payload;
+use crate::api::{
+    Candle, LastPrice, MarketDataResponse, OpenInterest, OrderBook, Trade, TradingStatus,
+};
+
+/// Обёртка над стримом, обнаруживающая зависание соединения.
+///
+/// gRPC стримы могут перестать присылать данные без какой-либо ошибки,
+/// например при проблемах с сетью. Если за `stall_timeout` из стрима не пришло
+/// ни одного сообщения, обёртка возвращает `TInvestError::StreamStall`,
+/// после чего стрим можно переподключить. Таймер сбрасывается после каждого
+/// сообщения и после каждой ошибки зависания.
+///
+/// Для стримов, присылающих `ping`, таймаут стоит выбирать больше интервала пингов.
+pub struct StreamHealthMonitor<S> {
+    inner: S,
+    stall_timeout: Duration,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl<S> StreamHealthMonitor<S> {
+    /// Оборачивает стрим с указанным таймаутом зависания.
+    pub fn new(inner: S, stall_timeout: Duration) -> Self {
+        Self {
+            inner,
+            stall_timeout,
+            sleep: Box::pin(tokio::time::sleep(stall_timeout)),
+        }
+    }
+
+    /// Возвращает исходный стрим.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn reset_timer(&mut self) {
+        let deadline = Instant::now() + self.stall_timeout;
+        self.sleep.as_mut().reset(deadline);
+    }
+}
+
+impl<S, T, E> Stream for StreamHealthMonitor<S>
+where
+    S: Stream<Item = Result<T, E>> + Unpin,
+    E: Into<TInvestError>,
+{
+    type Item = Result<T, TInvestError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(item)) => {
+                self.reset_timer();
+                Poll::Ready(Some(item.map_err(Into::into)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => match self.sleep.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    self.reset_timer();
+                    Poll::Ready(Some(Err(TInvestError::StreamStall(self.stall_timeout))))
+                }
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+/// Расширение для оборачивания стримов в [`StreamHealthMonitor`].
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use futures::StreamExt;
+/// # use t_invest_sdk::{StreamHealthExt, TInvestError, TInvestSdk};
+/// # async fn run(sdk: TInvestSdk, requests: futures::stream::Pending<t_invest_sdk::api::MarketDataRequest>) -> Result<(), TInvestError> {
+/// let mut stream = sdk
+///     .market_data_stream()
+///     .market_data_stream(requests)
+///     .await?
+///     .into_inner()
+///     .with_stall_timeout(Duration::from_secs(30));
+///
+/// while let Some(message) = stream.next().await {
+///     println!("{:?}", message?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait StreamHealthExt: Stream + Sized {
+    /// Оборачивает стрим в [`StreamHealthMonitor`] с указанным таймаутом зависания.
+    fn with_stall_timeout(self, stall_timeout: Duration) -> StreamHealthMonitor<Self> {
+        StreamHealthMonitor::new(self, stall_timeout)
+    }
+}
+
+impl<S: Stream> StreamHealthExt for S {}
+
+/// Превращает вектор в стрим, выдающий элементы по порядку.
+///
+/// Позволяет обрабатывать исторические данные тем же кодом, что и данные
+/// из стримов, например запускать стратегию на `impl Stream<Item = Candle>`
+/// и в бэктесте, и на реальном рынке.
+pub fn vec_into_stream<T: Send + 'static>(items: Vec<T>) -> impl Stream<Item = T> + Send + 'static {
+    stream::iter(items)
+}
+
+/// Превращает свечи в стрим. См. [`vec_into_stream`].
+pub fn candles_into_stream(candles: Vec<Candle>) -> impl Stream<Item = Candle> + Send + 'static {
+    vec_into_stream(candles)
+}
+
+/// Рыночное событие из стрима или исторических данных.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketDataEvent {
+    Candle(Candle),
+    OrderBook(OrderBook),
+    Trade(Trade),
+    LastPrice(LastPrice),
+    TradingStatus(TradingStatus),
+    OpenInterest(OpenInterest),
+}
+
+impl MarketDataEvent {
+    /// Извлекает событие из ответа стрима рыночных данных.
+    ///
+    /// # Возвращает
+    /// `None` для пингов и ответов на подписку
+    pub fn from_response(response: MarketDataResponse) -> Option<Self> {
+        match response.payload? {
+            Payload::Candle(candle) => Some(MarketDataEvent::Candle(candle)),
+            Payload::Orderbook(order_book) => Some(MarketDataEvent::OrderBook(order_book)),
+            Payload::Trade(trade) => Some(MarketDataEvent::Trade(trade)),
+            Payload::LastPrice(last_price) => Some(MarketDataEvent::LastPrice(last_price)),
+            Payload::TradingStatus(status) => Some(MarketDataEvent::TradingStatus(status)),
+            Payload::OpenInterest(open_interest) => {
+                Some(MarketDataEvent::OpenInterest(open_interest))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Источник рыночных событий для стратегии.
+///
+/// Позволяет запускать один и тот же цикл обработки событий и в бэктесте,
+/// и на реальном рынке, см. [`next_event`].
+pub enum EventSource {
+    /// События из исторических данных
+    Historical(Box<dyn Iterator<Item = MarketDataEvent>>),
+    /// События из стрима
+    Live(Box<dyn Stream<Item = Result<MarketDataEvent, TInvestError>> + Unpin>),
+}
+
+impl EventSource {
+    /// Создаёт исторический источник из свечей.
+    pub fn from_candles(candles: Vec<Candle>) -> Self {
+        EventSource::Historical(Box::new(candles.into_iter().map(MarketDataEvent::Candle)))
+    }
+
+    /// Создаёт источник из стрима рыночных данных.
+    ///
+    /// Пинги и ответы на подписку пропускаются.
+    pub fn from_market_data_stream<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<MarketDataResponse, E>> + Unpin + 'static,
+        E: Into<TInvestError>,
+    {
+        EventSource::Live(Box::new(stream.filter_map(|response| {
+            future::ready(match response {
+                Ok(response) => MarketDataEvent::from_response(response).map(Ok),
+                Err(err) => Some(Err(err.into())),
+            })
+        })))
+    }
+}
+
+/// Возвращает следующее событие источника.
+///
+/// ```no_run
+/// # use t_invest_sdk::{EventSource, MarketDataEvent, TInvestError, next_event};
+/// # async fn run(mut source: EventSource) -> Result<(), TInvestError> {
+/// while let Some(event) = next_event(&mut source).await {
+///     if let MarketDataEvent::Candle(candle) = event? {
+///         println!("{:?}", candle.close);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Возвращает
+/// `None`, когда данные или стрим закончились
+pub async fn next_event(source: &mut EventSource) -> Option<Result<MarketDataEvent, TInvestError>> {
+    match source {
+        EventSource::Historical(events) => events.next().map(Ok),
+        EventSource::Live(stream) => stream.next().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::codec::Streaming;
+
+    use super::*;
+    use crate::api::{
+        OperationsStreamResponse, OrderStateStreamResponse, PortfolioStreamResponse,
+        PositionsStreamResponse, TradesStreamResponse,
+    };
+
+    #[test]
+    fn grpc_streams_support_stream_ext() {
+        // Ответы серверных стримов tonic уже реализуют `Stream` и `Unpin`,
+        // поэтому к ним применимы комбинаторы `StreamExt` без обёрток.
+        fn assert_stream<T>()
+        where
+            Streaming<T>: Stream<Item = Result<T, tonic::Status>> + Unpin,
+        {
+        }
+
+        assert_stream::<MarketDataResponse>();
+        assert_stream::<TradesStreamResponse>();
+        assert_stream::<OrderStateStreamResponse>();
+        assert_stream::<OperationsStreamResponse>();
+        assert_stream::<PortfolioStreamResponse>();
+        assert_stream::<PositionsStreamResponse>();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn passes_messages_through() {
+        let mut monitored = stream::iter(vec![Ok(1), Err(tonic::Status::internal("error"))])
+            .with_stall_timeout(Duration::from_secs(5));
+
+        assert_eq!(1, monitored.next().await.unwrap().unwrap());
+        assert!(matches!(
+            monitored.next().await,
+            Some(Err(TInvestError::Status(_)))
+        ));
+        assert!(monitored.next().await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn emits_stall_error() {
+        let mut monitored = stream::pending::<Result<i32, tonic::Status>>()
+            .with_stall_timeout(Duration::from_secs(5));
+
+        let started = Instant::now();
+        assert!(matches!(
+            monitored.next().await,
+            Some(Err(TInvestError::StreamStall(timeout))) if timeout == Duration::from_secs(5)
+        ));
+        assert_eq!(Duration::from_secs(5), started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn vec_as_stream() {
+        let candles = vec![
+            Candle {
+                volume: 1,
+                ..Default::default()
+            },
+            Candle {
+                volume: 2,
+                ..Default::default()
+            },
+        ];
+
+        let volumes: Vec<i64> = candles_into_stream(candles)
+            .map(|candle| candle.volume)
+            .collect()
+            .await;
+        assert_eq!(vec![1, 2], volumes);
+        assert_eq!(0, vec_into_stream(Vec::<i32>::new()).count().await);
+    }
+
+    #[tokio::test]
+    async fn historical_and_live_events() {
+        let candle = |volume| Candle {
+            volume,
+            ..Default::default()
+        };
+        async fn volumes(mut source: EventSource) -> Vec<Result<i64, TInvestError>> {
+            let mut volumes = Vec::new();
+            while let Some(event) = next_event(&mut source).await {
+                volumes.push(event.map(|event| match event {
+                    MarketDataEvent::Candle(candle) => candle.volume,
+                    _ => -1,
+                }));
+            }
+            volumes
+        }
+
+        let historical = volumes(EventSource::from_candles(vec![candle(1), candle(2)])).await;
+        assert_eq!(
+            vec![1, 2],
+            historical.into_iter().flatten().collect::<Vec<_>>()
+        );
+
+        let response = |payload| MarketDataResponse {
+            payload: Some(payload),
+        };
+        let live = volumes(EventSource::from_market_data_stream(stream::iter(vec![
+            Ok(response(Payload::Ping(Default::default()))),
+            Ok(response(Payload::Candle(candle(3)))),
+            Ok(MarketDataResponse { payload: None }),
+            Ok(response(Payload::LastPrice(Default::default()))),
+            Err(tonic::Status::unavailable("closed")),
+        ])))
+        .await;
+        assert_eq!(3, live.len());
+        assert!(matches!(live[0], Ok(3)));
+        assert!(matches!(live[1], Ok(-1)));
+        assert!(matches!(live[2], Err(TInvestError::Status(_))));
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::HashSet;
+
+use futures::channel::mpsc::{self, UnboundedSender};
+use tonic::{Streaming, service::interceptor::InterceptedService, transport::Channel};
+
+use crate::api::{
+    CandleInterval, MarketDataRequest, MarketDataResponse, SubscriptionAction,
+    SubscriptionInterval, market_data_request::Payload,
+    market_data_stream_service_client::MarketDataStreamServiceClient,
+};
+use crate::{TInvestError, TInvestInterceptor, subscriptions};
+
+/// Подписки стрима рыночных данных с сохранением состояния.
+///
+/// Менеджер хранит активные подписки на свечи, стаканы и последние цены и
+/// отправляет в открытый стрим только изменения: повторная подписка на тот же
+/// инструмент не отправляет запрос. Подписки можно менять до открытия стрима —
+/// при каждом вызове [`SubscriptionManager::connect`] открывается новый стрим и
+/// все сохранённые подписки отправляются заново, поэтому для переподключения
+/// после обрыва достаточно вызвать `connect` ещё раз.
+///
+/// ```no_run
+/// # use futures::StreamExt;
+/// # use t_invest_sdk::{SubscriptionManager, TInvestSdk};
+/// # use t_invest_sdk::api::CandleInterval;
+/// # async fn run(sdk: TInvestSdk) -> Result<(), t_invest_sdk::TInvestError> {
+/// let mut manager = SubscriptionManager::new(sdk.market_data_stream());
+/// manager.subscribe_candles("BBG004730N88", CandleInterval::CandleInterval1Min)?;
+/// manager.subscribe_last_price("BBG004730N88");
+///
+/// loop {
+///     let mut stream = manager.connect().await?;
+///     while let Some(Ok(response)) = stream.next().await {
+///         println!("{:?}", response);
+///     }
+/// }
+/// # }
+/// ```
+pub struct SubscriptionManager {
+    client: MarketDataStreamServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    requests: Option<UnboundedSender<MarketDataRequest>>,
+    subscribed_candles: HashSet<(String, CandleInterval)>,
+    subscribed_order_books: HashSet<(String, i32)>,
+    subscribed_last_prices: HashSet<String>,
+}
+
+impl SubscriptionManager {
+    /// Создаёт менеджер без подписок.
+    pub fn new(
+        client: MarketDataStreamServiceClient<InterceptedService<Channel, TInvestInterceptor>>,
+    ) -> Self {
+        Self {
+            client,
+            requests: None,
+            subscribed_candles: HashSet::new(),
+            subscribed_order_books: HashSet::new(),
+            subscribed_last_prices: HashSet::new(),
+        }
+    }
+
+    /// Открывает стрим рыночных данных и отправляет в него все сохранённые подписки.
+    ///
+    /// Ранее открытый стрим перестаёт получать изменения подписок.
+    pub async fn connect(&mut self) -> Result<Streaming<MarketDataResponse>, TInvestError> {
+        let (requests, stream) = mpsc::unbounded();
+        for request in self.replay_requests() {
+            let _ = requests.unbounded_send(request);
+        }
+        self.requests = Some(requests);
+
+        Ok(self.client.market_data_stream(stream).await?.into_inner())
+    }
+
+    /// Подписывается на свечи инструмента.
+    ///
+    /// # Возвращает
+    /// `false`, если подписка уже была активна
+    ///
+    /// # Ошибки
+    /// Возвращает [`TInvestError::InvalidArgument`] для интервалов, недоступных в стриме
+    pub fn subscribe_candles(
+        &mut self,
+        figi: &str,
+        interval: CandleInterval,
+    ) -> Result<bool, TInvestError> {
+        let stream_interval = subscription_interval(interval).ok_or_else(|| {
+            TInvestError::InvalidArgument(format!(
+                "Candle interval {:?} is not available in market data stream",
+                interval
+            ))
+        })?;
+        if !self.subscribed_candles.insert((figi.to_string(), interval)) {
+            return Ok(false);
+        }
+
+        let request = subscriptions::candles()
+            .add_instrument(figi, stream_interval)
+            .build();
+        self.send(Payload::SubscribeCandlesRequest(request));
+        Ok(true)
+    }
+
+    /// Отменяет подписку на свечи инструмента.
+    ///
+    /// # Возвращает
+    /// `false`, если подписки не было
+    pub fn unsubscribe_candles(&mut self, figi: &str, interval: CandleInterval) -> bool {
+        if !self
+            .subscribed_candles
+            .remove(&(figi.to_string(), interval))
+        {
+            return false;
+        }
+
+        if let Some(stream_interval) = subscription_interval(interval) {
+            let request = subscriptions::candles()
+                .action(SubscriptionAction::Unsubscribe)
+                .add_instrument(figi, stream_interval)
+                .build();
+            self.send(Payload::SubscribeCandlesRequest(request));
+        }
+        true
+    }
+
+    /// Подписывается на стакан инструмента указанной глубины.
+    ///
+    /// # Возвращает
+    /// `false`, если подписка уже была активна
+    pub fn subscribe_order_book(&mut self, figi: &str, depth: i32) -> bool {
+        if !self
+            .subscribed_order_books
+            .insert((figi.to_string(), depth))
+        {
+            return false;
+        }
+
+        let request = subscriptions::order_book()
+            .add_instrument(figi, depth)
+            .build();
+        self.send(Payload::SubscribeOrderBookRequest(request));
+        true
+    }
+
+    /// Отменяет подписку на стакан инструмента.
+    ///
+    /// # Возвращает
+    /// `false`, если подписки не было
+    pub fn unsubscribe_order_book(&mut self, figi: &str, depth: i32) -> bool {
+        if !self
+            .subscribed_order_books
+            .remove(&(figi.to_string(), depth))
+        {
+            return false;
+        }
+
+        let request = subscriptions::order_book()
+            .action(SubscriptionAction::Unsubscribe)
+            .add_instrument(figi, depth)
+            .build();
+        self.send(Payload::SubscribeOrderBookRequest(request));
+        true
+    }
+
+    /// Подписывается на последние цены инструмента.
+    ///
+    /// # Возвращает
+    /// `false`, если подписка уже была активна
+    pub fn subscribe_last_price(&mut self, figi: &str) -> bool {
+        if !self.subscribed_last_prices.insert(figi.to_string()) {
+            return false;
+        }
+
+        let request = subscriptions::last_price().add_instrument(figi).build();
+        self.send(Payload::SubscribeLastPriceRequest(request));
+        true
+    }
+
+    /// Отменяет подписку на последние цены инструмента.
+    ///
+    /// # Возвращает
+    /// `false`, если подписки не было
+    pub fn unsubscribe_last_price(&mut self, figi: &str) -> bool {
+        if !self.subscribed_last_prices.remove(figi) {
+            return false;
+        }
+
+        let request = subscriptions::last_price()
+            .action(SubscriptionAction::Unsubscribe)
+            .add_instrument(figi)
+            .build();
+        self.send(Payload::SubscribeLastPriceRequest(request));
+        true
+    }
+
+    /// Возвращает FIGI инструментов с хотя бы одной активной подпиской по возрастанию.
+    pub fn subscribed_instruments(&self) -> Vec<String> {
+        let mut figis: Vec<String> = self
+            .subscribed_candles
+            .iter()
+            .map(|(figi, _)| figi)
+            .chain(self.subscribed_order_books.iter().map(|(figi, _)| figi))
+            .chain(&self.subscribed_last_prices)
+            .cloned()
+            .collect();
+        figis.sort();
+        figis.dedup();
+        figis
+    }
+
+    /// Отправляет запрос в открытый стрим. Без стрима изменение только сохраняется.
+    fn send(&self, payload: Payload) {
+        if let Some(requests) = &self.requests {
+            // Ошибка означает, что стрим закрыт; подписка будет отправлена при переподключении.
+            let _ = requests.unbounded_send(MarketDataRequest {
+                payload: Some(payload),
+            });
+        }
+    }
+
+    /// Формирует запросы подписки на все сохранённые подписки.
+    fn replay_requests(&self) -> Vec<MarketDataRequest> {
+        let mut payloads = Vec::new();
+
+        let mut candles: Vec<_> = self.subscribed_candles.iter().collect();
+        candles.sort();
+        let mut builder = subscriptions::candles();
+        for (figi, interval) in &candles {
+            if let Some(interval) = subscription_interval(*interval) {
+                builder.add_instrument(figi, interval);
+            }
+        }
+        if !candles.is_empty() {
+            payloads.push(Payload::SubscribeCandlesRequest(builder.build()));
+        }
+
+        let mut order_books: Vec<_> = self.subscribed_order_books.iter().collect();
+        order_books.sort();
+        let mut builder = subscriptions::order_book();
+        for (figi, depth) in &order_books {
+            builder.add_instrument(figi, *depth);
+        }
+        if !order_books.is_empty() {
+            payloads.push(Payload::SubscribeOrderBookRequest(builder.build()));
+        }
+
+        let mut last_prices: Vec<_> = self.subscribed_last_prices.iter().collect();
+        last_prices.sort();
+        let mut builder = subscriptions::last_price();
+        for figi in &last_prices {
+            builder.add_instrument(figi);
+        }
+        if !last_prices.is_empty() {
+            payloads.push(Payload::SubscribeLastPriceRequest(builder.build()));
+        }
+
+        payloads
+            .into_iter()
+            .map(|payload| MarketDataRequest {
+                payload: Some(payload),
+            })
+            .collect()
+    }
+}
+
+/// Возвращает интервал подписки на свечи. Секундные интервалы в стриме недоступны.
+fn subscription_interval(interval: CandleInterval) -> Option<SubscriptionInterval> {
+    match interval {
+        CandleInterval::CandleInterval1Min => Some(SubscriptionInterval::OneMinute),
+        CandleInterval::CandleInterval2Min => Some(SubscriptionInterval::SubscriptionInterval2Min),
+        CandleInterval::CandleInterval3Min => Some(SubscriptionInterval::SubscriptionInterval3Min),
+        CandleInterval::CandleInterval5Min => Some(SubscriptionInterval::FiveMinutes),
+        CandleInterval::CandleInterval10Min => {
+            Some(SubscriptionInterval::SubscriptionInterval10Min)
+        }
+        CandleInterval::CandleInterval15Min => Some(SubscriptionInterval::FifteenMinutes),
+        CandleInterval::CandleInterval30Min => {
+            Some(SubscriptionInterval::SubscriptionInterval30Min)
+        }
+        CandleInterval::Hour => Some(SubscriptionInterval::OneHour),
+        CandleInterval::CandleInterval2Hour => {
+            Some(SubscriptionInterval::SubscriptionInterval2Hour)
+        }
+        CandleInterval::CandleInterval4Hour => {
+            Some(SubscriptionInterval::SubscriptionInterval4Hour)
+        }
+        CandleInterval::Day => Some(SubscriptionInterval::OneDay),
+        CandleInterval::Week => Some(SubscriptionInterval::Week),
+        CandleInterval::Month => Some(SubscriptionInterval::Month),
+        CandleInterval::Unspecified
+        | CandleInterval::CandleInterval5Sec
+        | CandleInterval::CandleInterval10Sec
+        | CandleInterval::CandleInterval30Sec => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use futures::channel::mpsc::UnboundedReceiver;
+
+    use super::*;
+
+    fn manager() -> SubscriptionManager {
+        let channel = Channel::from_static("http://[::1]:1").connect_lazy();
+        SubscriptionManager::new(MarketDataStreamServiceClient::with_interceptor(
+            channel,
+            TInvestInterceptor::new("t.test"),
+        ))
+    }
+
+    /// Подключает менеджер к каналу вместо стрима.
+    fn attach(manager: &mut SubscriptionManager) -> UnboundedReceiver<MarketDataRequest> {
+        let (requests, stream) = mpsc::unbounded();
+        manager.requests = Some(requests);
+        stream
+    }
+
+    fn describe(request: MarketDataRequest) -> String {
+        match request.payload.unwrap() {
+            Payload::SubscribeCandlesRequest(request) => format!(
+                "{:?} candles {:?}",
+                request.subscription_action(),
+                request
+                    .instruments
+                    .iter()
+                    .map(|instrument| (instrument.instrument_id.as_str(), instrument.interval))
+                    .collect::<Vec<_>>()
+            ),
+            Payload::SubscribeOrderBookRequest(request) => format!(
+                "{:?} order books {:?}",
+                request.subscription_action(),
+                request
+                    .instruments
+                    .iter()
+                    .map(|instrument| (instrument.instrument_id.as_str(), instrument.depth))
+                    .collect::<Vec<_>>()
+            ),
+            Payload::SubscribeLastPriceRequest(request) => format!(
+                "{:?} last prices {:?}",
+                request.subscription_action(),
+                request
+                    .instruments
+                    .iter()
+                    .map(|instrument| instrument.instrument_id.as_str())
+                    .collect::<Vec<_>>()
+            ),
+            payload => format!("{:?}", payload),
+        }
+    }
+
+    #[tokio::test]
+    async fn state_transitions() {
+        let mut manager = manager();
+        let mut requests = attach(&mut manager);
+
+        assert!(
+            manager
+                .subscribe_candles("A", CandleInterval::CandleInterval1Min)
+                .unwrap()
+        );
+        assert!(
+            !manager
+                .subscribe_candles("A", CandleInterval::CandleInterval1Min)
+                .unwrap()
+        );
+        assert!(
+            manager
+                .subscribe_candles("A", CandleInterval::Hour)
+                .unwrap()
+        );
+        assert!(
+            manager
+                .subscribe_candles("A", CandleInterval::CandleInterval5Sec)
+                .is_err()
+        );
+        assert!(manager.subscribe_order_book("B", 10));
+        assert!(!manager.subscribe_order_book("B", 10));
+        assert!(manager.subscribe_last_price("C"));
+        assert!(!manager.subscribe_last_price("C"));
+        assert_eq!(vec!["A", "B", "C"], manager.subscribed_instruments());
+
+        assert!(manager.unsubscribe_candles("A", CandleInterval::CandleInterval1Min));
+        assert!(!manager.unsubscribe_candles("A", CandleInterval::CandleInterval1Min));
+        assert!(!manager.unsubscribe_order_book("B", 20));
+        assert!(manager.unsubscribe_order_book("B", 10));
+        assert!(!manager.unsubscribe_last_price("A"));
+        assert_eq!(vec!["A", "C"], manager.subscribed_instruments());
+
+        drop(manager);
+        let sent: Vec<String> = requests.by_ref().map(describe).collect().await;
+        assert_eq!(
+            vec![
+                "Subscribe candles [(\"A\", 1)]",
+                "Subscribe candles [(\"A\", 4)]",
+                "Subscribe order books [(\"B\", 10)]",
+                "Subscribe last prices [\"C\"]",
+                "Unsubscribe candles [(\"A\", 1)]",
+                "Unsubscribe order books [(\"B\", 10)]",
+            ],
+            sent
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_subscriptions() {
+        let mut manager = manager();
+        // До подключения подписки только сохраняются.
+        manager.subscribe_candles("B", CandleInterval::Day).unwrap();
+        manager
+            .subscribe_candles("A", CandleInterval::CandleInterval5Min)
+            .unwrap();
+        manager.subscribe_last_price("A");
+        manager.subscribe_last_price("A");
+
+        let replayed: Vec<String> = manager
+            .replay_requests()
+            .into_iter()
+            .map(describe)
+            .collect();
+        assert_eq!(
+            vec![
+                "Subscribe candles [(\"A\", 2), (\"B\", 5)]",
+                "Subscribe last prices [\"A\"]",
+            ],
+            replayed
+        );
+        manager.subscribe_order_book("C", 1);
+        manager.unsubscribe_order_book("C", 1);
+        manager.unsubscribe_candles("A", CandleInterval::CandleInterval5Min);
+        manager.unsubscribe_candles("B", CandleInterval::Day);
+        manager.unsubscribe_last_price("A");
+        assert!(manager.replay_requests().is_empty());
+        assert!(manager.subscribed_instruments().is_empty());
+    }
+}
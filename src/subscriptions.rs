@@ -0,0 +1,400 @@
+//! Построители запросов подписки для стрима рыночных данных.
+//!
+//! ```
+//! use t_invest_sdk::api::SubscriptionInterval;
+//! use t_invest_sdk::subscriptions;
+//!
+//! let request = subscriptions::candles()
+//!     .add_instrument("BBG004730N88", SubscriptionInterval::OneMinute)
+//!     .waiting_close(true)
+//!     .build();
+//! ```
+
+use crate::api::{
+    CandleInstrument, InfoInstrument, LastPriceInstrument, OrderBookInstrument, OrderBookType,
+    SubscribeCandlesRequest, SubscribeInfoRequest, SubscribeLastPriceRequest,
+    SubscribeOrderBookRequest, SubscribeTradesRequest, SubscriptionAction, SubscriptionInterval,
+    TradeInstrument, TradeSourceType, get_candles_request::CandleSource,
+};
+
+/// Возвращает построитель подписки на свечи.
+pub fn candles() -> CandleSubscriptionBuilder {
+    CandleSubscriptionBuilder::default()
+}
+
+/// Возвращает построитель подписки на стаканы.
+pub fn order_book() -> OrderBookSubscriptionBuilder {
+    OrderBookSubscriptionBuilder::default()
+}
+
+/// Возвращает построитель подписки на обезличенные сделки.
+pub fn trades() -> TradesSubscriptionBuilder {
+    TradesSubscriptionBuilder::default()
+}
+
+/// Возвращает построитель подписки на последние цены.
+pub fn last_price() -> LastPriceSubscriptionBuilder {
+    LastPriceSubscriptionBuilder::default()
+}
+
+/// Возвращает построитель подписки на торговые статусы.
+pub fn info() -> InfoSubscriptionBuilder {
+    InfoSubscriptionBuilder::default()
+}
+
+/// Построитель `SubscribeCandlesRequest`.
+///
+/// По умолчанию формирует запрос на подписку (`SubscriptionAction::Subscribe`).
+#[derive(Debug, Clone)]
+pub struct CandleSubscriptionBuilder {
+    action: SubscriptionAction,
+    instruments: Vec<CandleInstrument>,
+    waiting_close: bool,
+    candle_source: Option<CandleSource>,
+}
+
+impl Default for CandleSubscriptionBuilder {
+    fn default() -> Self {
+        Self {
+            action: SubscriptionAction::Subscribe,
+            instruments: Vec::new(),
+            waiting_close: false,
+            candle_source: None,
+        }
+    }
+}
+
+impl CandleSubscriptionBuilder {
+    /// Устанавливает действие: подписка или отписка.
+    pub fn action(&mut self, action: SubscriptionAction) -> &mut Self {
+        self.action = action;
+        self
+    }
+
+    /// Добавляет инструмент (FIGI или UID) с интервалом свечей.
+    pub fn add_instrument(
+        &mut self,
+        instrument_id: &str,
+        interval: SubscriptionInterval,
+    ) -> &mut Self {
+        self.instruments.push(CandleInstrument {
+            interval: interval as i32,
+            instrument_id: instrument_id.to_string(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Устанавливает флаг ожидания закрытия временного интервала для отправки свечи.
+    pub fn waiting_close(&mut self, waiting_close: bool) -> &mut Self {
+        self.waiting_close = waiting_close;
+        self
+    }
+
+    /// Устанавливает источник свечей.
+    pub fn candle_source(&mut self, candle_source: CandleSource) -> &mut Self {
+        self.candle_source = Some(candle_source);
+        self
+    }
+
+    /// Формирует запрос.
+    pub fn build(&self) -> SubscribeCandlesRequest {
+        SubscribeCandlesRequest {
+            subscription_action: self.action as i32,
+            instruments: self.instruments.clone(),
+            waiting_close: self.waiting_close,
+            candle_source_type: self.candle_source.map(|source| source as i32),
+        }
+    }
+}
+
+/// Построитель `SubscribeOrderBookRequest`.
+///
+/// По умолчанию формирует запрос на подписку (`SubscriptionAction::Subscribe`).
+#[derive(Debug, Clone)]
+pub struct OrderBookSubscriptionBuilder {
+    action: SubscriptionAction,
+    instruments: Vec<OrderBookInstrument>,
+}
+
+impl Default for OrderBookSubscriptionBuilder {
+    fn default() -> Self {
+        Self {
+            action: SubscriptionAction::Subscribe,
+            instruments: Vec::new(),
+        }
+    }
+}
+
+impl OrderBookSubscriptionBuilder {
+    /// Устанавливает действие: подписка или отписка.
+    pub fn action(&mut self, action: SubscriptionAction) -> &mut Self {
+        self.action = action;
+        self
+    }
+
+    /// Добавляет инструмент (FIGI или UID) с глубиной стакана.
+    pub fn add_instrument(&mut self, instrument_id: &str, depth: i32) -> &mut Self {
+        self.add_instrument_with_type(
+            instrument_id,
+            depth,
+            OrderBookType::OrderbookTypeUnspecified,
+        )
+    }
+
+    /// Добавляет инструмент (FIGI или UID) с глубиной и типом стакана.
+    pub fn add_instrument_with_type(
+        &mut self,
+        instrument_id: &str,
+        depth: i32,
+        order_book_type: OrderBookType,
+    ) -> &mut Self {
+        self.instruments.push(OrderBookInstrument {
+            depth,
+            instrument_id: instrument_id.to_string(),
+            order_book_type: order_book_type as i32,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Формирует запрос.
+    pub fn build(&self) -> SubscribeOrderBookRequest {
+        SubscribeOrderBookRequest {
+            subscription_action: self.action as i32,
+            instruments: self.instruments.clone(),
+        }
+    }
+}
+
+/// Построитель `SubscribeTradesRequest`.
+///
+/// По умолчанию формирует запрос на подписку (`SubscriptionAction::Subscribe`).
+#[derive(Debug, Clone)]
+pub struct TradesSubscriptionBuilder {
+    action: SubscriptionAction,
+    instruments: Vec<TradeInstrument>,
+    trade_source: TradeSourceType,
+    with_open_interest: bool,
+}
+
+impl Default for TradesSubscriptionBuilder {
+    fn default() -> Self {
+        Self {
+            action: SubscriptionAction::Subscribe,
+            instruments: Vec::new(),
+            trade_source: TradeSourceType::TradeSourceUnspecified,
+            with_open_interest: false,
+        }
+    }
+}
+
+impl TradesSubscriptionBuilder {
+    /// Устанавливает действие: подписка или отписка.
+    pub fn action(&mut self, action: SubscriptionAction) -> &mut Self {
+        self.action = action;
+        self
+    }
+
+    /// Добавляет инструмент (FIGI или UID).
+    pub fn add_instrument(&mut self, instrument_id: &str) -> &mut Self {
+        self.instruments.push(TradeInstrument {
+            instrument_id: instrument_id.to_string(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Устанавливает источник сделок.
+    pub fn trade_source(&mut self, trade_source: TradeSourceType) -> &mut Self {
+        self.trade_source = trade_source;
+        self
+    }
+
+    /// Устанавливает флаг получения открытого интереса в стриме.
+    pub fn with_open_interest(&mut self, with_open_interest: bool) -> &mut Self {
+        self.with_open_interest = with_open_interest;
+        self
+    }
+
+    /// Формирует запрос.
+    pub fn build(&self) -> SubscribeTradesRequest {
+        SubscribeTradesRequest {
+            subscription_action: self.action as i32,
+            instruments: self.instruments.clone(),
+            trade_source: self.trade_source as i32,
+            with_open_interest: self.with_open_interest,
+        }
+    }
+}
+
+/// Построитель `SubscribeLastPriceRequest`.
+///
+/// По умолчанию формирует запрос на подписку (`SubscriptionAction::Subscribe`).
+#[derive(Debug, Clone)]
+pub struct LastPriceSubscriptionBuilder {
+    action: SubscriptionAction,
+    instruments: Vec<LastPriceInstrument>,
+}
+
+impl Default for LastPriceSubscriptionBuilder {
+    fn default() -> Self {
+        Self {
+            action: SubscriptionAction::Subscribe,
+            instruments: Vec::new(),
+        }
+    }
+}
+
+impl LastPriceSubscriptionBuilder {
+    /// Устанавливает действие: подписка или отписка.
+    pub fn action(&mut self, action: SubscriptionAction) -> &mut Self {
+        self.action = action;
+        self
+    }
+
+    /// Добавляет инструмент (FIGI или UID).
+    pub fn add_instrument(&mut self, instrument_id: &str) -> &mut Self {
+        self.instruments.push(LastPriceInstrument {
+            instrument_id: instrument_id.to_string(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Формирует запрос.
+    pub fn build(&self) -> SubscribeLastPriceRequest {
+        SubscribeLastPriceRequest {
+            subscription_action: self.action as i32,
+            instruments: self.instruments.clone(),
+        }
+    }
+}
+
+/// Построитель `SubscribeInfoRequest`.
+///
+/// По умолчанию формирует запрос на подписку (`SubscriptionAction::Subscribe`).
+#[derive(Debug, Clone)]
+pub struct InfoSubscriptionBuilder {
+    action: SubscriptionAction,
+    instruments: Vec<InfoInstrument>,
+}
+
+impl Default for InfoSubscriptionBuilder {
+    fn default() -> Self {
+        Self {
+            action: SubscriptionAction::Subscribe,
+            instruments: Vec::new(),
+        }
+    }
+}
+
+impl InfoSubscriptionBuilder {
+    /// Устанавливает действие: подписка или отписка.
+    pub fn action(&mut self, action: SubscriptionAction) -> &mut Self {
+        self.action = action;
+        self
+    }
+
+    /// Добавляет инструмент (FIGI или UID).
+    pub fn add_instrument(&mut self, instrument_id: &str) -> &mut Self {
+        self.instruments.push(InfoInstrument {
+            instrument_id: instrument_id.to_string(),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Формирует запрос.
+    pub fn build(&self) -> SubscribeInfoRequest {
+        SubscribeInfoRequest {
+            subscription_action: self.action as i32,
+            instruments: self.instruments.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candles_request() {
+        let request = candles()
+            .add_instrument("BBG004730N88", SubscriptionInterval::OneMinute)
+            .add_instrument("BBG004731032", SubscriptionInterval::OneHour)
+            .waiting_close(true)
+            .candle_source(CandleSource::Exchange)
+            .build();
+
+        assert_eq!(
+            SubscriptionAction::Subscribe as i32,
+            request.subscription_action
+        );
+        assert!(request.waiting_close);
+        assert_eq!(
+            Some(CandleSource::Exchange as i32),
+            request.candle_source_type
+        );
+        assert_eq!(
+            vec![
+                ("BBG004730N88", SubscriptionInterval::OneMinute as i32),
+                ("BBG004731032", SubscriptionInterval::OneHour as i32),
+            ],
+            request
+                .instruments
+                .iter()
+                .map(|i| (i.instrument_id.as_str(), i.interval))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn order_book_request() {
+        let request = order_book()
+            .action(SubscriptionAction::Unsubscribe)
+            .add_instrument("BBG004730N88", 10)
+            .build();
+
+        assert_eq!(
+            SubscriptionAction::Unsubscribe as i32,
+            request.subscription_action
+        );
+        assert_eq!(1, request.instruments.len());
+        assert_eq!("BBG004730N88", request.instruments[0].instrument_id);
+        assert_eq!(10, request.instruments[0].depth);
+    }
+
+    #[test]
+    fn instrument_list_requests() {
+        let trades = trades()
+            .add_instrument("BBG004730N88")
+            .trade_source(TradeSourceType::TradeSourceExchange)
+            .with_open_interest(true)
+            .build();
+        assert_eq!(
+            SubscriptionAction::Subscribe as i32,
+            trades.subscription_action
+        );
+        assert_eq!("BBG004730N88", trades.instruments[0].instrument_id);
+        assert_eq!(
+            TradeSourceType::TradeSourceExchange as i32,
+            trades.trade_source
+        );
+        assert!(trades.with_open_interest);
+
+        let last_price = last_price()
+            .add_instrument("BBG004730N88")
+            .add_instrument("BBG004731032")
+            .build();
+        assert_eq!(2, last_price.instruments.len());
+        assert_eq!("BBG004731032", last_price.instruments[1].instrument_id);
+
+        let info = info().add_instrument("BBG004730N88").build();
+        assert_eq!(
+            SubscriptionAction::Subscribe as i32,
+            info.subscription_action
+        );
+        assert_eq!("BBG004730N88", info.instruments[0].instrument_id);
+    }
+}
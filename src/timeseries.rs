@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+
+/// Значения, упорядоченные по времени.
+///
+/// Моменты времени хранятся по возрастанию и не повторяются: значение,
+/// добавленное в уже существующий момент, заменяет прежнее.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeSeries<T> {
+    times: Vec<DateTime<Utc>>,
+    values: Vec<T>,
+}
+
+impl<T> Default for TimeSeries<T> {
+    fn default() -> Self {
+        Self {
+            times: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<T> TimeSeries<T> {
+    /// Создаёт пустой ряд.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет значение. Добавление в конец ряда не требует сдвига элементов.
+    pub fn push(&mut self, time: DateTime<Utc>, value: T) {
+        if self.times.last().is_none_or(|last| *last < time) {
+            self.times.push(time);
+            self.values.push(value);
+            return;
+        }
+
+        match self.times.binary_search(&time) {
+            Ok(index) => self.values[index] = value,
+            Err(index) => {
+                self.times.insert(index, time);
+                self.values.insert(index, value);
+            }
+        }
+    }
+
+    /// Возвращает значение в момент `time`.
+    pub fn get_at(&self, time: DateTime<Utc>) -> Option<&T> {
+        self.times
+            .binary_search(&time)
+            .ok()
+            .map(|index| &self.values[index])
+    }
+
+    /// Возвращает количество значений.
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Проверяет, пуст ли ряд.
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    /// Возвращает моменты времени по возрастанию.
+    pub fn times(&self) -> &[DateTime<Utc>] {
+        &self.times
+    }
+
+    /// Возвращает значения в порядке времени.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Возвращает итератор по парам `(время, значение)`.
+    pub fn iter(&self) -> impl Iterator<Item = (DateTime<Utc>, &T)> {
+        self.times.iter().copied().zip(&self.values)
+    }
+}
+
+impl<T: Clone> TimeSeries<T> {
+    /// Возвращает часть ряда за интервал `[from, to]`.
+    pub fn slice(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> TimeSeries<T> {
+        let start = self.times.partition_point(|time| *time < from);
+        let end = self.times.partition_point(|time| *time <= to).max(start);
+
+        TimeSeries {
+            times: self.times[start..end].to_vec(),
+            values: self.values[start..end].to_vec(),
+        }
+    }
+
+    /// Оставляет в обоих рядах только общие моменты времени.
+    pub fn align_with<U: Clone>(&self, other: &TimeSeries<U>) -> (TimeSeries<T>, TimeSeries<U>) {
+        let mut aligned = (TimeSeries::new(), TimeSeries::new());
+        let (mut i, mut j) = (0, 0);
+        while i < self.times.len() && j < other.times.len() {
+            match self.times[i].cmp(&other.times[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    aligned.0.push(self.times[i], self.values[i].clone());
+                    aligned.1.push(other.times[j], other.values[j].clone());
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        aligned
+    }
+}
+
+impl<T> FromIterator<(DateTime<Utc>, T)> for TimeSeries<T> {
+    fn from_iter<I: IntoIterator<Item = (DateTime<Utc>, T)>>(iter: I) -> Self {
+        let mut series = TimeSeries::new();
+        for (time, value) in iter {
+            series.push(time, value);
+        }
+        series
+    }
+}
+
+impl<T> IntoIterator for TimeSeries<T> {
+    type Item = (DateTime<Utc>, T);
+    type IntoIter = std::iter::Zip<std::vec::IntoIter<DateTime<Utc>>, std::vec::IntoIter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.times.into_iter().zip(self.values)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TimeSeries<T> {
+    type Item = (DateTime<Utc>, &'a T);
+    type IntoIter = std::iter::Zip<
+        std::iter::Copied<std::slice::Iter<'a, DateTime<Utc>>>,
+        std::slice::Iter<'a, T>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.times.iter().copied().zip(self.values.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn push_get_and_slice() {
+        let mut series = TimeSeries::new();
+        series.push(time(3), 30);
+        series.push(time(1), 10);
+        series.push(time(5), 50);
+        series.push(time(3), 31);
+
+        assert_eq!(vec![time(1), time(3), time(5)], series.times());
+        assert_eq!(&[10, 31, 50], series.values());
+        assert_eq!(Some(&31), series.get_at(time(3)));
+        assert_eq!(None, series.get_at(time(2)));
+
+        let slice = series.slice(time(2), time(5));
+        assert_eq!(
+            vec![(time(3), 31), (time(5), 50)],
+            slice.into_iter().collect::<Vec<_>>()
+        );
+        assert!(series.slice(time(6), time(9)).is_empty());
+        assert!(series.slice(time(5), time(1)).is_empty());
+    }
+
+    #[test]
+    fn align_by_common_times() {
+        let prices: TimeSeries<i32> = [(time(1), 10), (time(2), 20), (time(4), 40)]
+            .into_iter()
+            .collect();
+        let labels: TimeSeries<&str> = [(time(2), "b"), (time(3), "c"), (time(4), "d")]
+            .into_iter()
+            .collect();
+
+        let (prices, labels) = prices.align_with(&labels);
+
+        assert_eq!(vec![time(2), time(4)], prices.times());
+        assert_eq!(prices.times(), labels.times());
+        assert_eq!(
+            vec![(time(2), &20), (time(4), &40)],
+            (&prices).into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(&["b", "d"], labels.values());
+    }
+}
@@ -0,0 +1,407 @@
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Instant;
+
+use http_body::{Body, Frame, SizeHint};
+use tonic::codegen::http;
+use tonic::service::Interceptor;
+use tower::{Layer, Service};
+
+/// Перехватчик, логирующий каждый запрос через `tracing`.
+///
+/// Оборачивает другой перехватчик (обычно [`crate::TInvestInterceptor`]) и после его
+/// вызова записывает событие в span `tinvest.rpc` с `x-tracking-id`, добавленным
+/// вложенным перехватчиком, и именем вызываемого метода.
+///
+/// Перехватчики не видят ответов, поэтому для логирования статуса и длительности
+/// запросов используйте [`TracingLayer`].
+#[derive(Debug, Clone)]
+pub struct TracingInterceptor<I> {
+    inner: I,
+}
+
+impl<I> TracingInterceptor<I> {
+    /// Создаёт перехватчик поверх вложенного.
+    pub fn new(inner: I) -> Self {
+        Self { inner }
+    }
+}
+
+impl<I: Interceptor> Interceptor for TracingInterceptor<I> {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, tonic::Status> {
+        let request = self.inner.call(request)?;
+
+        let tracking_id = request
+            .metadata()
+            .get("x-tracking-id")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let method = request
+            .extensions()
+            .get::<tonic::GrpcMethod<'static>>()
+            .map(|method| format!("{}/{}", method.service(), method.method()))
+            .unwrap_or_default();
+
+        let span = tracing::info_span!("tinvest.rpc", tracking_id = %tracking_id, method = %method);
+        let _entered = span.enter();
+        tracing::info!("request");
+
+        Ok(request)
+    }
+}
+
+/// Слой `tower`, логирующий начало, статус и длительность каждого запроса.
+///
+/// ```no_run
+/// # use t_invest_sdk::{TInvestSdk, TracingLayer, api::instruments_service_client::InstrumentsServiceClient};
+/// # use tower::Layer;
+/// # fn run(sdk: TInvestSdk) {
+/// let channel = TracingLayer.layer(sdk.channel());
+/// let client = InstrumentsServiceClient::with_interceptor(channel, sdk.interceptor());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingLayer;
+
+impl<S> Layer<S> for TracingLayer {
+    type Service = TracingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracingService { inner }
+    }
+}
+
+/// Сервис, создаваемый [`TracingLayer`].
+///
+/// Статус gRPC берётся из заголовков ответа, если сервер вернул только заголовки,
+/// иначе из трейлеров тела ответа. Событие `request finished` записывается после
+/// получения статуса, поэтому `elapsed_ms` включает передачу всего тела. Если
+/// статус не получен, например тело закрыто раньше трейлеров, записывается
+/// `grpc_status = "unknown"`.
+#[derive(Debug, Clone)]
+pub struct TracingService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<http::Request<ReqBody>> for TracingService<S>
+where
+    S: Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+    S::Error: std::fmt::Display,
+{
+    type Response = http::Response<TracingBody<ResBody>>;
+    type Error = S::Error;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let tracking_id = request
+            .headers()
+            .get("x-tracking-id")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let span = tracing::info_span!(
+            "tinvest.rpc",
+            tracking_id = %tracking_id,
+            method = %request.uri().path()
+        );
+
+        span.in_scope(|| tracing::info!("request started"));
+        let started = Instant::now();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            match future.await {
+                Ok(response) => {
+                    let mut log = RequestLog {
+                        span,
+                        started,
+                        http_status: response.status().as_u16(),
+                        finished: false,
+                    };
+                    if let Some(grpc_status) = grpc_status(response.headers()) {
+                        log.finish(grpc_status);
+                    }
+
+                    Ok(response.map(|body| TracingBody {
+                        inner: Box::pin(body),
+                        log,
+                    }))
+                }
+                Err(error) => {
+                    let elapsed_ms = started.elapsed().as_millis() as u64;
+                    span.in_scope(|| tracing::warn!(%error, elapsed_ms, "request failed"));
+                    Err(error)
+                }
+            }
+        })
+    }
+}
+
+/// Тело ответа [`TracingService`], логирующее статус gRPC из трейлеров.
+pub struct TracingBody<B> {
+    inner: Pin<Box<B>>,
+    log: RequestLog,
+}
+
+impl<B: Default> Default for TracingBody<B> {
+    /// Пустое тело, не связанное с запросом и ничего не логирующее.
+    fn default() -> Self {
+        Self {
+            inner: Box::pin(B::default()),
+            log: RequestLog {
+                span: tracing::Span::none(),
+                started: Instant::now(),
+                http_status: 0,
+                finished: true,
+            },
+        }
+    }
+}
+
+impl<B> Body for TracingBody<B>
+where
+    B: Body,
+    B::Error: std::fmt::Display,
+{
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = &mut *self;
+        let frame = ready!(this.inner.as_mut().poll_frame(cx));
+
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(trailers) = frame.trailers_ref() {
+                    this.log
+                        .finish(grpc_status(trailers).unwrap_or(UNKNOWN_GRPC_STATUS));
+                }
+            }
+            Some(Err(error)) => this.log.fail(error),
+            None => this.log.finish(UNKNOWN_GRPC_STATUS),
+        }
+
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// Значение `grpc_status`, если статус не получен.
+const UNKNOWN_GRPC_STATUS: &str = "unknown";
+
+/// Состояние логирования одного запроса.
+struct RequestLog {
+    span: tracing::Span,
+    started: Instant,
+    http_status: u16,
+    finished: bool,
+}
+
+impl RequestLog {
+    fn finish(&mut self, grpc_status: &str) {
+        if std::mem::replace(&mut self.finished, true) {
+            return;
+        }
+
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        self.span.in_scope(|| {
+            tracing::info!(
+                http_status = self.http_status,
+                grpc_status,
+                elapsed_ms,
+                "request finished"
+            )
+        });
+    }
+
+    fn fail(&mut self, error: &dyn std::fmt::Display) {
+        if std::mem::replace(&mut self.finished, true) {
+            return;
+        }
+
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        self.span
+            .in_scope(|| tracing::warn!(%error, elapsed_ms, "request failed"));
+    }
+}
+
+impl Drop for RequestLog {
+    fn drop(&mut self) {
+        self.finish(UNKNOWN_GRPC_STATUS);
+    }
+}
+
+fn grpc_status(headers: &http::HeaderMap) -> Option<&str> {
+    headers
+        .get("grpc-status")
+        .and_then(|value| value.to_str().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+
+    use futures::future::{self, Ready};
+    use tonic::codegen::Bytes;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use super::*;
+    use crate::TInvestInterceptor;
+
+    /// Сохраняет значения `grpc_status` из событий.
+    #[derive(Clone, Default)]
+    struct StatusRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl Subscriber for StatusRecorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut visitor = StatusVisitor(None);
+            event.record(&mut visitor);
+            if let Some(status) = visitor.0 {
+                self.0.lock().unwrap().push(status);
+            }
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    struct StatusVisitor(Option<String>);
+
+    impl Visit for StatusVisitor {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "grpc_status" {
+                self.0 = Some(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, _: &Field, _: &dyn fmt::Debug) {}
+    }
+
+    /// Тело ответа, состоящее из одних трейлеров.
+    struct TrailersBody(Option<http::HeaderMap>);
+
+    impl Body for TrailersBody {
+        type Data = Bytes;
+        type Error = Infallible;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+            Poll::Ready(self.0.take().map(|trailers| Ok(Frame::trailers(trailers))))
+        }
+    }
+
+    /// Сервис, возвращающий заданный ответ.
+    struct ResponseService(Option<http::Response<TrailersBody>>);
+
+    impl Service<http::Request<()>> for ResponseService {
+        type Response = http::Response<TrailersBody>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: http::Request<()>) -> Self::Future {
+            future::ready(Ok(self.0.take().unwrap()))
+        }
+    }
+
+    fn status_headers(code: &'static str) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("grpc-status", http::HeaderValue::from_static(code));
+        headers
+    }
+
+    async fn drive(response: http::Response<TrailersBody>) {
+        let mut service = TracingLayer.layer(ResponseService(Some(response)));
+        let mut body = service
+            .call(http::Request::new(()))
+            .await
+            .unwrap()
+            .into_body();
+        while future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx))
+            .await
+            .is_some()
+        {}
+    }
+
+    #[test]
+    fn logs_grpc_status() {
+        let recorder = StatusRecorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            futures::executor::block_on(async {
+                // Статус в трейлерах после тела ответа.
+                drive(http::Response::new(TrailersBody(Some(status_headers("5"))))).await;
+
+                // Ответ только с заголовками.
+                let mut response = http::Response::new(TrailersBody(None));
+                *response.headers_mut() = status_headers("16");
+                drive(response).await;
+
+                // Тело закрыто без трейлеров.
+                drive(http::Response::new(TrailersBody(None))).await;
+
+                // Тело не прочитано.
+                let mut service = TracingLayer.layer(ResponseService(Some(http::Response::new(
+                    TrailersBody(Some(status_headers("0"))),
+                ))));
+                drop(service.call(http::Request::new(())).await.unwrap());
+            })
+        });
+
+        assert_eq!(
+            vec!["5", "16", "unknown", "unknown"],
+            *recorder.0.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_inner_headers() {
+        let mut interceptor = TracingInterceptor::new(TInvestInterceptor::new("t.test"));
+
+        let request = interceptor.call(tonic::Request::new(())).unwrap();
+
+        assert!(request.metadata().get("x-tracking-id").is_some());
+        assert_eq!(
+            "bearer t.test",
+            request.metadata().get("authorization").unwrap()
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use crate::api::{
+    Account, AccountStatus, AccountType, GetAccountsRequest, GetAccountsResponse, GetInfoRequest,
+    GetInfoResponse,
+};
+use crate::{TInvestError, TInvestSdk};
+
+/// Сведения о пользователе и его счетах.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserContext {
+    /// Признак премиум-клиента
+    pub prem_status: bool,
+    /// Признак квалифицированного инвестора
+    pub qual_status: bool,
+    /// Тариф пользователя
+    pub tariff: String,
+    /// Счета пользователя
+    pub accounts: Vec<Account>,
+}
+
+impl UserContext {
+    /// Собирает сведения из ответов `GetInfo` и `GetAccounts`.
+    pub fn new(info: GetInfoResponse, accounts: GetAccountsResponse) -> Self {
+        Self {
+            prem_status: info.prem_status,
+            qual_status: info.qual_status,
+            tariff: info.tariff,
+            accounts: accounts.accounts,
+        }
+    }
+
+    /// Возвращает открытые счета.
+    pub fn active_accounts(&self) -> Vec<&Account> {
+        self.accounts
+            .iter()
+            .filter(|account| account.status() == AccountStatus::Open)
+            .collect()
+    }
+
+    /// Проверяет, является ли пользователь премиум-клиентом.
+    pub fn is_premium(&self) -> bool {
+        self.prem_status
+    }
+
+    /// Возвращает идентификатор первого открытого брокерского счёта.
+    pub fn broker_account_id(&self) -> Option<&str> {
+        self.accounts
+            .iter()
+            .find(|account| {
+                account.r#type() == AccountType::Tinkoff && account.status() == AccountStatus::Open
+            })
+            .map(|account| account.id.as_str())
+    }
+}
+
+impl fmt::Display for UserContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} accounts ({} active), {}",
+            self.accounts.len(),
+            self.active_accounts().len(),
+            if self.prem_status {
+                "premium"
+            } else {
+                "standard"
+            }
+        )
+    }
+}
+
+/// Запрашивает сведения о пользователе и его счетах.
+pub async fn get_user_context(sdk: &TInvestSdk) -> Result<UserContext, TInvestError> {
+    let mut users = sdk.users();
+    let info = users.get_info(GetInfoRequest {}).await?.into_inner();
+    let accounts = users
+        .get_accounts(GetAccountsRequest::default())
+        .await?
+        .into_inner();
+
+    Ok(UserContext::new(info, accounts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(id: &str, account_type: AccountType, status: AccountStatus) -> Account {
+        Account {
+            id: id.to_string(),
+            r#type: account_type.into(),
+            status: status.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn context_from_responses() {
+        let info = GetInfoResponse {
+            prem_status: true,
+            tariff: "investor".to_string(),
+            ..Default::default()
+        };
+        let accounts = GetAccountsResponse {
+            accounts: vec![
+                account("1", AccountType::Tinkoff, AccountStatus::Closed),
+                account("2", AccountType::TinkoffIis, AccountStatus::Open),
+                account("3", AccountType::Tinkoff, AccountStatus::Open),
+                account("4", AccountType::Tinkoff, AccountStatus::New),
+            ],
+        };
+        let context = UserContext::new(info, accounts);
+
+        assert!(context.is_premium());
+        assert_eq!("investor", context.tariff);
+        assert_eq!(
+            vec!["2", "3"],
+            context
+                .active_accounts()
+                .iter()
+                .map(|account| account.id.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some("3"), context.broker_account_id());
+        assert_eq!("4 accounts (2 active), premium", context.to_string());
+
+        let empty = UserContext::new(GetInfoResponse::default(), GetAccountsResponse::default());
+        assert!(!empty.is_premium());
+        assert_eq!(None, empty.broker_account_id());
+        assert_eq!("0 accounts (0 active), standard", empty.to_string());
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::api::Trade;
+use crate::timestamp_to_datetime_utc;
+
+/// Накопительный расчёт средневзвешенной по объёму цены (VWAP).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VwapCalculator {
+    cumulative_pv: Decimal,
+    cumulative_volume: Decimal,
+}
+
+impl VwapCalculator {
+    /// Создаёт пустой калькулятор.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Создаёт калькулятор по сделкам. Сделки без цены пропускаются.
+    pub fn from_trades(trades: &[Trade]) -> Self {
+        let mut calculator = Self::new();
+        for trade in trades {
+            calculator.update_trade(trade);
+        }
+
+        calculator
+    }
+
+    /// Добавляет сделку с указанной ценой и объёмом.
+    pub fn update(&mut self, price: Decimal, volume: Decimal) -> &mut Self {
+        self.cumulative_pv += price * volume;
+        self.cumulative_volume += volume;
+        self
+    }
+
+    /// Добавляет сделку из стрима. Сделки без цены пропускаются.
+    pub fn update_trade(&mut self, trade: &Trade) -> &mut Self {
+        if let Some(price) = trade.price {
+            self.update(price.into(), Decimal::from(trade.quantity));
+        }
+        self
+    }
+
+    /// Возвращает VWAP или `None`, если суммарный объём равен нулю.
+    pub fn vwap(&self) -> Option<Decimal> {
+        if self.cumulative_volume.is_zero() {
+            return None;
+        }
+
+        Some(self.cumulative_pv / self.cumulative_volume)
+    }
+
+    /// Сбрасывает накопленные значения.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// VWAP торговой сессии, сбрасываемый при начале новой сессии.
+///
+/// Сессия начинается каждый день в `session_start` по UTC. Сделка, время которой
+/// относится к более поздней сессии, чем предыдущие сделки, сбрасывает накопленные
+/// значения. Сделки без времени или цены пропускаются.
+#[derive(Debug, Clone)]
+pub struct SessionVwap {
+    session_start: NaiveTime,
+    session: Option<NaiveDate>,
+    calculator: VwapCalculator,
+}
+
+impl SessionVwap {
+    /// Создаёт VWAP сессий, начинающихся в указанное время по UTC.
+    pub fn new(session_start: NaiveTime) -> Self {
+        Self {
+            session_start,
+            session: None,
+            calculator: VwapCalculator::new(),
+        }
+    }
+
+    /// Добавляет сделку, при необходимости начиная новую сессию.
+    pub fn update(&mut self, trade: &Trade) -> &mut Self {
+        let Some(time) = trade
+            .time
+            .and_then(|time| timestamp_to_datetime_utc(time).ok())
+        else {
+            return self;
+        };
+
+        let session = self.session_date(time);
+        if self.session.is_none_or(|current| session > current) {
+            self.session = Some(session);
+            self.calculator.reset();
+        }
+        self.calculator.update_trade(trade);
+        self
+    }
+
+    /// Возвращает VWAP текущей сессии.
+    pub fn vwap(&self) -> Option<Decimal> {
+        self.calculator.vwap()
+    }
+
+    /// Возвращает дату начала сессии, к которой относится `time`.
+    fn session_date(&self, time: DateTime<Utc>) -> NaiveDate {
+        let date = time.date_naive();
+        if time.time() < self.session_start {
+            date.pred_opt().unwrap_or(date)
+        } else {
+            date
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::datetime_utc_to_timestamp;
+
+    fn trade(day: u32, hour: u32, price: Decimal, quantity: i64) -> Trade {
+        Trade {
+            price: Some(price.try_into().unwrap()),
+            quantity,
+            time: Some(datetime_utc_to_timestamp(
+                Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap(),
+            )),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn vwap_reference_values() {
+        let mut calculator = VwapCalculator::new();
+        assert_eq!(None, calculator.vwap());
+
+        // (100 * 10 + 102 * 30 + 101 * 20) / 60 = 6080 / 60
+        calculator
+            .update(dec!(100), dec!(10))
+            .update(dec!(102), dec!(30))
+            .update(dec!(101), dec!(20));
+        assert_eq!(
+            Some(dec!(101.3333)),
+            calculator.vwap().map(|v| v.round_dp(4))
+        );
+
+        calculator.reset();
+        assert_eq!(None, calculator.vwap());
+
+        let trades = [
+            trade(12, 10, dec!(10), 1),
+            trade(12, 11, dec!(13), 2),
+            Trade::default(),
+        ];
+        assert_eq!(Some(dec!(12)), VwapCalculator::from_trades(&trades).vwap());
+    }
+
+    #[test]
+    fn session_reset() {
+        let mut vwap = SessionVwap::new(NaiveTime::from_hms_opt(7, 0, 0).unwrap());
+
+        vwap.update(&trade(12, 8, dec!(100), 1))
+            .update(&trade(12, 20, dec!(110), 1));
+        assert_eq!(Some(dec!(105)), vwap.vwap());
+
+        // До начала сессии 13-го числа сделки относятся к сессии 12-го.
+        vwap.update(&trade(13, 6, dec!(120), 2));
+        assert_eq!(Some(dec!(112.5)), vwap.vwap());
+
+        vwap.update(&trade(13, 7, dec!(90), 3));
+        assert_eq!(Some(dec!(90)), vwap.vwap());
+    }
+}
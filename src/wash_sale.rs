@@ -0,0 +1,369 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::api::{Operation, OperationState};
+#[cfg(feature = "csv")]
+use crate::io::OperationRecord;
+use crate::portfolio::trade_sign;
+use crate::timestamp_to_datetime_utc;
+
+/// Окно в днях до и после продажи, в котором покупка считается повторной.
+const WASH_SALE_WINDOW_DAYS: i64 = 30;
+
+/// Нарушение правила wash sale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WashSaleViolation {
+    /// Дата продажи с убытком
+    pub sell_date: NaiveDate,
+    /// Дата повторной покупки
+    pub buy_date: NaiveDate,
+    /// FIGI-идентификатор инструмента
+    pub figi: String,
+    /// Убыток, который не может быть учтён
+    pub disallowed_loss: Decimal,
+}
+
+/// Открытая часть позиции, купленная по одной цене.
+#[derive(Debug, Clone)]
+struct Lot {
+    date: NaiveDate,
+    quantity: Decimal,
+    price: Decimal,
+    /// Количество, ещё не засчитанное как повторная покупка
+    unmatched: Decimal,
+}
+
+/// Убыток от продажи, который ещё может быть запрещён последующей покупкой.
+#[derive(Debug, Clone)]
+struct PendingLoss {
+    sell_date: NaiveDate,
+    quantity: Decimal,
+    loss_per_share: Decimal,
+}
+
+/// Поиск нарушений правила wash sale по операциям.
+///
+/// Продажа с убытком нарушает правило, если в течение 30 дней до или после неё
+/// куплен тот же инструмент. Запрещённый убыток пропорционален количеству
+/// повторно купленных бумаг, каждая покупка засчитывается только один раз.
+/// Себестоимость проданных бумаг рассчитывается по FIFO, продажи сверх открытой
+/// позиции (короткие позиции) не учитываются. Комиссии в расчёте не участвуют.
+#[derive(Debug, Clone, Default)]
+pub struct WashSaleChecker {
+    lots: HashMap<String, VecDeque<Lot>>,
+    pending: HashMap<String, Vec<PendingLoss>>,
+    violations: Vec<WashSaleViolation>,
+}
+
+impl WashSaleChecker {
+    /// Создаёт пустой анализатор.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Обрабатывает операции, переданные в хронологическом порядке.
+    ///
+    /// Учитываются только исполненные операции покупки и продажи с датой и ценой.
+    /// Метод можно вызывать несколько раз для последовательных периодов.
+    pub fn process(&mut self, operations: &[Operation]) -> &mut Self {
+        for operation in operations {
+            if operation.state != OperationState::Executed as i32 {
+                continue;
+            }
+            let Some(sign) = trade_sign(operation.operation_type) else {
+                continue;
+            };
+            let Some(date) = operation
+                .date
+                .and_then(|date| timestamp_to_datetime_utc(date).ok())
+                .map(|date| date.date_naive())
+            else {
+                continue;
+            };
+            let Some(price) = operation.price.clone().map(Decimal::from) else {
+                continue;
+            };
+
+            let quantity = Decimal::from(operation.quantity - operation.quantity_rest);
+            if quantity.is_zero() {
+                continue;
+            }
+
+            if sign.is_sign_positive() {
+                self.buy(&operation.figi, date, quantity, price);
+            } else {
+                self.sell(&operation.figi, date, quantity, price);
+            }
+        }
+
+        self
+    }
+
+    /// Обрабатывает записи выгрузки брокерского отчёта (фича `csv`).
+    ///
+    /// Выгрузка содержит только тикеры, поэтому FIGI каждой сделки берётся
+    /// из `figi_by_ticker`. Записи преобразуются через `Operation::try_from`
+    /// и передаются в [`Self::process`]. Операции без покупки или продажи
+    /// пропускаются.
+    ///
+    /// # Ошибки
+    /// Возвращает ошибку, если для тикера сделки нет FIGI или запись не удалось
+    /// преобразовать. В этом случае ни одна запись не обрабатывается.
+    #[cfg(feature = "csv")]
+    pub fn process_records(
+        &mut self,
+        records: &[OperationRecord],
+        figi_by_ticker: &HashMap<String, String>,
+    ) -> Result<&mut Self, String> {
+        let mut operations = Vec::with_capacity(records.len());
+        for record in records {
+            if trade_sign(record.api_operation_type().into()).is_none() {
+                continue;
+            }
+
+            let figi = figi_by_ticker
+                .get(&record.ticker)
+                .ok_or_else(|| format!("No FIGI for ticker {:?}", record.ticker))?;
+            operations.push(Operation {
+                figi: figi.clone(),
+                ..Operation::try_from(record)?
+            });
+        }
+
+        Ok(self.process(&operations))
+    }
+
+    /// Возвращает найденные нарушения в порядке обнаружения.
+    pub fn get_violations(&self) -> Vec<WashSaleViolation> {
+        self.violations.clone()
+    }
+
+    fn buy(&mut self, figi: &str, date: NaiveDate, quantity: Decimal, price: Decimal) {
+        let mut unmatched = quantity;
+
+        if let Some(pending) = self.pending.get_mut(figi) {
+            pending.retain(|loss| (date - loss.sell_date).num_days() <= WASH_SALE_WINDOW_DAYS);
+
+            for loss in pending.iter_mut() {
+                if unmatched.is_zero() {
+                    break;
+                }
+
+                let matched = unmatched.min(loss.quantity);
+                loss.quantity -= matched;
+                unmatched -= matched;
+                self.violations.push(WashSaleViolation {
+                    sell_date: loss.sell_date,
+                    buy_date: date,
+                    figi: figi.to_string(),
+                    disallowed_loss: matched * loss.loss_per_share,
+                });
+            }
+
+            pending.retain(|loss| !loss.quantity.is_zero());
+        }
+
+        self.lots
+            .entry(figi.to_string())
+            .or_default()
+            .push_back(Lot {
+                date,
+                quantity,
+                price,
+                unmatched,
+            });
+    }
+
+    fn sell(&mut self, figi: &str, date: NaiveDate, quantity: Decimal, price: Decimal) {
+        let lots = self.lots.entry(figi.to_string()).or_default();
+        let mut remaining = quantity;
+        let mut sold = Decimal::ZERO;
+        let mut cost = Decimal::ZERO;
+
+        while !remaining.is_zero() {
+            let Some(lot) = lots.front_mut() else {
+                break;
+            };
+
+            let closed = remaining.min(lot.quantity);
+            sold += closed;
+            cost += closed * lot.price;
+            remaining -= closed;
+            lot.quantity -= closed;
+            lot.unmatched = lot.unmatched.min(lot.quantity);
+            if lot.quantity.is_zero() {
+                lots.pop_front();
+            }
+        }
+
+        let loss = cost - sold * price;
+        if sold.is_zero() || loss <= Decimal::ZERO {
+            return;
+        }
+
+        let loss_per_share = loss / sold;
+        let mut unmatched = sold;
+
+        // Бумаги, купленные в течение окна до продажи и оставшиеся в позиции.
+        for lot in lots.iter_mut() {
+            if unmatched.is_zero() {
+                break;
+            }
+            if lot.unmatched.is_zero() || (date - lot.date).num_days() > WASH_SALE_WINDOW_DAYS {
+                continue;
+            }
+
+            let matched = unmatched.min(lot.unmatched);
+            lot.unmatched -= matched;
+            unmatched -= matched;
+            self.violations.push(WashSaleViolation {
+                sell_date: date,
+                buy_date: lot.date,
+                figi: figi.to_string(),
+                disallowed_loss: matched * loss_per_share,
+            });
+        }
+
+        if !unmatched.is_zero() {
+            self.pending
+                .entry(figi.to_string())
+                .or_default()
+                .push(PendingLoss {
+                    sell_date: date,
+                    quantity: unmatched,
+                    loss_per_share,
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    use super::*;
+    use crate::api::{MoneyValue, OperationType, Quotation};
+    use crate::datetime_utc_to_timestamp;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn operation(
+        operation_type: OperationType,
+        month: u32,
+        day: u32,
+        quantity: i64,
+        price: Decimal,
+    ) -> Operation {
+        let price = Quotation::try_from(price).unwrap();
+        Operation {
+            figi: "BBG000B9XRY4".to_string(),
+            state: OperationState::Executed.into(),
+            operation_type: operation_type.into(),
+            quantity,
+            price: Some(MoneyValue {
+                currency: "usd".to_string(),
+                units: price.units,
+                nano: price.nano,
+            }),
+            date: Some(datetime_utc_to_timestamp(
+                Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap(),
+            )),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn repurchase_after_loss() {
+        let mut checker = WashSaleChecker::new();
+        checker.process(&[
+            operation(OperationType::Buy, 1, 10, 10, dec!(100)),
+            operation(OperationType::Sell, 2, 1, 10, dec!(80)),
+            operation(OperationType::Buy, 2, 20, 4, dec!(85)),
+            operation(OperationType::Buy, 3, 15, 10, dec!(90)),
+        ]);
+
+        assert_eq!(
+            vec![WashSaleViolation {
+                sell_date: date(2, 1),
+                buy_date: date(2, 20),
+                figi: "BBG000B9XRY4".to_string(),
+                disallowed_loss: dec!(80),
+            }],
+            checker.get_violations()
+        );
+    }
+
+    #[test]
+    fn purchase_before_loss() {
+        let mut checker = WashSaleChecker::new();
+        checker
+            .process(&[
+                operation(OperationType::Buy, 1, 2, 10, dec!(100)),
+                operation(OperationType::Buy, 1, 20, 5, dec!(95)),
+            ])
+            .process(&[
+                operation(OperationType::Sell, 2, 5, 10, dec!(90)),
+                operation(OperationType::Buy, 4, 1, 10, dec!(80)),
+            ]);
+
+        let violations = checker.get_violations();
+        assert_eq!(1, violations.len());
+        assert_eq!(date(1, 20), violations[0].buy_date);
+        assert_eq!(dec!(50), violations[0].disallowed_loss);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn process_export_records() {
+        let record = |operation_type: &str, day: u32, quantity: i64, price: i64| OperationRecord {
+            date: date(2, day).and_hms_opt(12, 0, 0).unwrap(),
+            operation_type: operation_type.to_string(),
+            ticker: "AAPL".to_string(),
+            quantity: Decimal::from(quantity),
+            price: Some(Quotation::from(price)),
+            commission: Decimal::ZERO,
+            payment: Decimal::from(quantity * price),
+            currency: "usd".to_string(),
+        };
+        let records = [
+            record("Покупка", 1, 10, 100),
+            record("Продажа", 5, 10, 80),
+            record("Пополнение", 6, 0, 0),
+            record("Покупка", 10, 10, 85),
+        ];
+        let figi = HashMap::from([("AAPL".to_string(), "BBG000B9XRY4".to_string())]);
+
+        let mut checker = WashSaleChecker::new();
+        checker.process_records(&records, &figi).unwrap();
+        assert_eq!(
+            vec![WashSaleViolation {
+                sell_date: date(2, 5),
+                buy_date: date(2, 10),
+                figi: "BBG000B9XRY4".to_string(),
+                disallowed_loss: dec!(200),
+            }],
+            checker.get_violations()
+        );
+
+        let mut checker = WashSaleChecker::new();
+        assert!(checker.process_records(&records, &HashMap::new()).is_err());
+        assert!(checker.get_violations().is_empty());
+    }
+
+    #[test]
+    fn profitable_sale() {
+        let mut checker = WashSaleChecker::new();
+        checker.process(&[
+            operation(OperationType::Buy, 1, 10, 10, dec!(100)),
+            operation(OperationType::Sell, 1, 15, 10, dec!(110)),
+            operation(OperationType::Buy, 1, 16, 10, dec!(105)),
+        ]);
+
+        assert!(checker.get_violations().is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Список отслеживаемых инструментов, хранящийся в локальном JSON файле.
+///
+/// Файл содержит массив FIGI, например `["BBG004730N88", "BBG004731032"]`.
+/// Список используется как входные данные для загрузчиков свечей
+/// и отслеживания цен.
+#[derive(Debug, Clone)]
+pub struct WatchlistManager {
+    path: PathBuf,
+    figis: Vec<String>,
+}
+
+impl WatchlistManager {
+    /// Создаёт пустой список, который будет сохраняться в указанный файл.
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            figis: Vec::new(),
+        }
+    }
+
+    /// Загружает список из файла.
+    pub fn load(path: &Path) -> Result<Self, io::Error> {
+        let content = std::fs::read_to_string(path)?;
+        let figis = serde_json::from_str(&content)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            figis,
+        })
+    }
+
+    /// Сохраняет список в файл.
+    pub fn save(&self) -> Result<(), io::Error> {
+        let content = serde_json::to_string_pretty(&self.figis)?;
+        std::fs::write(&self.path, content)
+    }
+
+    /// Добавляет инструмент в список, если его там ещё нет.
+    pub fn add(&mut self, figi: &str) {
+        if !self.figis.iter().any(|f| f == figi) {
+            self.figis.push(figi.to_string());
+        }
+    }
+
+    /// Удаляет инструмент из списка.
+    pub fn remove(&mut self, figi: &str) {
+        self.figis.retain(|f| f != figi);
+    }
+
+    /// Возвращает FIGI инструментов в порядке добавления.
+    pub fn list(&self) -> &[String] {
+        &self.figis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_remove() {
+        let mut watchlist = WatchlistManager::new(Path::new("watchlist.json"));
+
+        watchlist.add("BBG004730N88");
+        watchlist.add("BBG004731032");
+        watchlist.add("BBG004730N88");
+        assert_eq!(["BBG004730N88", "BBG004731032"], watchlist.list());
+
+        watchlist.remove("BBG004730N88");
+        assert_eq!(["BBG004731032"], watchlist.list());
+    }
+
+    #[test]
+    fn save_load() {
+        let path = std::env::temp_dir().join(format!("watchlist-{}.json", uuid::Uuid::new_v4()));
+
+        let mut watchlist = WatchlistManager::new(&path);
+        watchlist.add("BBG004730N88");
+        watchlist.add("BBG004731032");
+        watchlist.save().unwrap();
+
+        let loaded = WatchlistManager::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(watchlist.list(), loaded.list());
+        assert!(WatchlistManager::load(&path).is_err());
+    }
+}